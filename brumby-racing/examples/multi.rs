@@ -51,6 +51,7 @@ fn main() -> Result<(), Box<dyn Error>> {
         win: Market::fit(&OverroundMethod::Multiplicative, win_prices, 1.),
         place: Market::fit(&OverroundMethod::Multiplicative, place_prices, 3.),
         places_paying: 3,
+        context: None,
     };
 
     // we have overrounds for Win and Place; extrapolate for Top-2 and Top-4 markets
@@ -77,5 +78,14 @@ fn main() -> Result<(), Box<dyn Error>> {
         multi_price.price
     );
 
+    // price the legs that could be added to the first two selections
+    let fringes = model.derive_multi_fringes(&selections[..2])?.value;
+    for fringe in fringes.iter().filter(|fringe| fringe.quotation.price < 100.) {
+        println!(
+            "  adding {} is priced at {:.2}",
+            fringe.selection, fringe.quotation.price
+        );
+    }
+
     Ok(())
 }
@@ -2,7 +2,7 @@ use std::env;
 use std::error::Error;
 use std::path::PathBuf;
 
-use anyhow::anyhow;
+use anyhow::{anyhow, bail};
 use clap::Parser;
 use stanza::renderer::console::Console;
 use stanza::renderer::Renderer;
@@ -12,7 +12,11 @@ use tracing::{debug, info};
 use brumby::csv::CsvReader;
 use brumby::file::{ReadJsonFile, WriteJsonFile};
 use brumby::linear::matrix::Matrix;
-use brumby::linear::regression::{RegressionModel, Regressor};
+use brumby::linear::regression::cross_validation::{cross_validate, Folds};
+use brumby::linear::regression::selection::{
+    candidates, indicator_candidates, select_stepwise, Criterion,
+};
+use brumby::linear::regression::{Huber, RegressionModel, RegressionOptions, Regressor};
 use brumby_racing::model::cf::{Coefficients, Factor, Regressors};
 
 /// Fits a linear regression model to the given dataset
@@ -27,6 +31,55 @@ struct Args {
     /// output file for the fitted coefficients
     #[clap(short = 'o', long)]
     output: Option<PathBuf>,
+
+    /// factor holding the weight of each sample
+    #[clap(short = 'w', long, value_parser = parse_factor)]
+    weights: Option<Factor>,
+
+    /// ridge penalty on the non-constant coefficients
+    #[clap(long, default_value = "0")]
+    ridge: f64,
+
+    /// tuning constant for a robust fit under the Huber loss
+    #[clap(long)]
+    huber: Option<f64>,
+
+    /// number of cross-validation folds, keeping the runners of each race together
+    #[clap(short = 'k', long)]
+    folds: Option<usize>,
+
+    /// train on earlier races and test on later ones, rather than on interleaved folds
+    #[clap(short = 't', long)]
+    time_ordered: bool,
+
+    /// comma-separated factors from which to select regressors, starting from those in the
+    /// regressors file
+    #[clap(short = 's', long, value_parser = parse_factor, value_delimiter = ',')]
+    select: Vec<Factor>,
+
+    /// comma-separated categorical factors, one-hot encoded for selection
+    #[clap(long, value_parser = parse_factor, value_delimiter = ',')]
+    categorical: Vec<Factor>,
+
+    /// highest power of each factor considered during selection
+    #[clap(long, default_value = "3")]
+    degree: i32,
+
+    /// also consider the pairwise products of factors during selection
+    #[clap(long)]
+    interactions: bool,
+
+    /// selection criterion: aic, bic or cv (requires the folds flag)
+    #[clap(short = 'c', long, value_parser = parse_criterion_type, default_value = "bic")]
+    criterion: CriterionType,
+
+    /// maximum number of non-constant terms in each selected formula
+    #[clap(long)]
+    max_terms: Option<usize>,
+
+    /// output file for the selected regressors
+    #[clap(short = 'r', long)]
+    selected: Option<PathBuf>,
 }
 impl Args {
     fn validate(&self) -> anyhow::Result<()> {
@@ -38,6 +91,62 @@ impl Args {
             .ok_or(anyhow!("regressors file must be specified"))?;
         Ok(())
     }
+
+    fn regression_options(&self) -> RegressionOptions<Factor> {
+        RegressionOptions {
+            weights: self.weights.clone(),
+            ridge: self.ridge,
+            robust: self.huber.map(|tuning| Huber {
+                tuning,
+                ..Huber::default()
+            }),
+        }
+    }
+
+    fn criterion(&self) -> anyhow::Result<Criterion<Factor>> {
+        match self.criterion {
+            CriterionType::Aic => Ok(Criterion::Aic),
+            CriterionType::Bic => Ok(Criterion::Bic),
+            CriterionType::CrossValidation => match self.folds() {
+                None => bail!("cross-validated selection requires the number of folds"),
+                Some(folds) => Ok(Criterion::CrossValidation {
+                    group: Some(Factor::RaceId),
+                    folds,
+                }),
+            },
+        }
+    }
+
+    fn folds(&self) -> Option<Folds> {
+        self.folds.map(|k| {
+            if self.time_ordered {
+                Folds::TimeOrdered(k)
+            } else {
+                Folds::KFold(k)
+            }
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+enum CriterionType {
+    Aic,
+    Bic,
+    CrossValidation,
+}
+fn parse_criterion_type(s: &str) -> anyhow::Result<CriterionType> {
+    match s.to_lowercase().as_str() {
+        "aic" => Ok(CriterionType::Aic),
+        "bic" => Ok(CriterionType::Bic),
+        "cv" => Ok(CriterionType::CrossValidation),
+        _ => Err(anyhow!("unsupported criterion {s}")),
+    }
+}
+
+fn parse_factor(s: &str) -> anyhow::Result<Factor> {
+    Factor::iter()
+        .find(|factor| factor.to_string() == s)
+        .ok_or(anyhow!("unsupported factor {s}"))
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
@@ -52,9 +161,16 @@ fn main() -> Result<(), Box<dyn Error>> {
     let args = Args::parse();
     args.validate()?;
     debug!("args: {args:?}");
+    let options = args.regression_options();
+    options.validate()?;
+    let folds = args.folds();
+    let criterion = args.criterion()?;
 
-    let regressors = Regressors::read_json_file(args.regressors.unwrap())?;
-    regressors.validate()?;
+    let mut regressors = Regressors::read_json_file(args.regressors.unwrap())?;
+    if args.select.is_empty() && args.categorical.is_empty() {
+        // when selecting, the regressors file may hold just the constant term
+        regressors.validate()?;
+    }
     debug!("regressors:\n{regressors:#?}");
 
     let mut csv = CsvReader::open(args.input.unwrap())?;
@@ -63,15 +179,88 @@ fn main() -> Result<(), Box<dyn Error>> {
     let mut data = Matrix::allocate(records.len(), Factor::COUNT);
     for (record_index, record) in records.into_iter().enumerate() {
         let record = record?;
+        if record.len() != Factor::COUNT {
+            Err(anyhow!(
+                "expected {} columns in record {}, got {}; the dataset may predate some factors",
+                Factor::COUNT,
+                record_index + 1,
+                record.len()
+            ))?;
+        }
         for factor in Factor::iter() {
             let value = record[factor.ordinal()].parse::<f64>()?;
             data[(record_index, factor.ordinal())] = value;
         }
     }
 
-    let w1 = fit_linear_model(Factor::Weight1, regressors.w1, &data)?;
-    let w2 = fit_linear_model(Factor::Weight2, regressors.w2, &data)?;
-    let w3 = fit_linear_model(Factor::Weight3, regressors.w3, &data)?;
+    if !args.select.is_empty() || !args.categorical.is_empty() {
+        let mut candidates = candidates(&args.select, args.degree, args.interactions);
+        for factor in &args.categorical {
+            let levels = categorical_levels(&data, factor);
+            // the lowest level serves as the reference, avoiding collinearity with an intercept
+            candidates.extend(indicator_candidates(factor, levels.get(1..).unwrap_or_default()));
+        }
+        for (response, regressors) in [
+            (Factor::Weight1, &mut regressors.w1),
+            (Factor::Weight2, &mut regressors.w2),
+            (Factor::Weight3, &mut regressors.w3),
+        ] {
+            info!(
+                "selecting regressors for {response:?} from {} candidates by {criterion:?}...",
+                candidates.len()
+            );
+            let selection = select_stepwise(
+                &response,
+                regressors,
+                &candidates,
+                &data,
+                &options,
+                &criterion,
+                args.max_terms,
+            )?;
+            info!(
+                "selection steps:\n{}",
+                Console::default().render(&selection.tabulate())
+            );
+            *regressors = selection.regressors;
+        }
+        debug!("selected regressors:\n{regressors:#?}");
+
+        if let Some(selected) = &args.selected {
+            regressors.write_json_file(selected)?;
+        }
+    }
+
+    if let Some(folds) = &folds {
+        let mut scores = vec![];
+        for (response, regressors) in [
+            (Factor::Weight1, &regressors.w1),
+            (Factor::Weight2, &regressors.w2),
+            (Factor::Weight3, &regressors.w3),
+        ] {
+            info!("cross-validating linear model for {response:?} over {folds:?}...");
+            let score = cross_validate(
+                &response,
+                regressors,
+                &data,
+                &options,
+                Some(&Factor::RaceId),
+                folds,
+            )?;
+            info!(
+                "out-of-sample scores:\n{}",
+                Console::default().render(&score.tabulate())
+            );
+            scores.push((response, score.rmse));
+        }
+        for (response, rmse) in scores {
+            info!("{response:?} out-of-sample RMSE: {rmse:.6}");
+        }
+    }
+
+    let w1 = fit_linear_model(Factor::Weight1, regressors.w1, &data, &options)?;
+    let w2 = fit_linear_model(Factor::Weight2, regressors.w2, &data, &options)?;
+    let w3 = fit_linear_model(Factor::Weight3, regressors.w3, &data, &options)?;
     let coefficients = Coefficients {
         w1: w1.predictor,
         w2: w2.predictor,
@@ -86,13 +275,21 @@ fn main() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+fn categorical_levels(data: &Matrix<f64>, factor: &Factor) -> Vec<i32> {
+    let mut levels: Vec<_> = data.col(factor.ordinal()).map(|&value| value as i32).collect();
+    levels.sort();
+    levels.dedup();
+    levels
+}
+
 fn fit_linear_model(
     response: Factor,
     regressors: Vec<Regressor<Factor>>,
     data: &Matrix<f64>,
+    options: &RegressionOptions<Factor>,
 ) -> Result<RegressionModel<Factor>, anyhow::Error> {
     info!("fitting linear model for {response:?}...");
-    let model = RegressionModel::fit(response, regressors, data)?;
+    let model = RegressionModel::fit_with_options(response, regressors, data, options)?;
     let table = model.tabulate();
     info!(
         "fitted model:\n{}\nr_squared:     {:.6}\nr_squared_adj: {:.6}",
@@ -18,7 +18,7 @@ use brumby_racing::model::cf::Factor;
 use brumby_racing::model::fit;
 use brumby_racing::model::fit::FitOptions;
 use brumby::probs::SliceExt;
-use brumby_racing::data::{PredicateClosures, RaceSummary};
+use brumby_racing::data::{PredicateClosures, RaceConditionsLookup, RaceSummary};
 
 const OVERROUND_METHOD: OverroundMethod = OverroundMethod::Multiplicative;
 
@@ -35,6 +35,10 @@ struct Args {
     #[clap(short = 'd', long)]
     departure: Option<f64>,
 
+    /// JSON file with the track condition and distance of each race
+    #[clap(short = 'c', long)]
+    conditions: Option<PathBuf>,
+
     /// where to write the CSV to
     out: Option<PathBuf>,
 }
@@ -71,6 +75,13 @@ fn main() -> Result<(), Box<dyn Error>> {
     debug!("args: {args:?}");
 
     let start_time = Instant::now();
+    let conditions = match &args.conditions {
+        None => RaceConditionsLookup::default(),
+        Some(path) => {
+            debug!("loading race conditions from {path:?}");
+            RaceConditionsLookup::read_json_file(path)?
+        }
+    };
     let mut csv = CsvWriter::create(args.out.unwrap())?;
     csv.append(Record::with_values(Factor::iter()))?;
 
@@ -114,6 +125,12 @@ fn main() -> Result<(), Box<dyn Error>> {
         let runners = markets[0].probs.len();
         let active_runners = markets[0].probs.iter().filter(|&&prob| prob != 0.).count();
         let stdev = markets[0].probs.stdev();
+        if !conditions.is_empty()
+            && conditions.get(&race.meeting_name, race.race_number).is_none()
+        {
+            info!("no conditions for {} race {}", race.meeting_name, race.race_number);
+        }
+        let context = race.context(&conditions);
         for runner in 0..runners {
             if markets[0].probs[runner] != 0.0 {
                 let mut record = Record::with_capacity(Factor::COUNT);
@@ -126,6 +143,9 @@ fn main() -> Result<(), Box<dyn Error>> {
                 record.set(Factor::Weight1, &fit_outcome.fitted_probs[(1, runner)]);
                 record.set(Factor::Weight2, &fit_outcome.fitted_probs[(2, runner)]);
                 record.set(Factor::Weight3, &fit_outcome.fitted_probs[(3, runner)]);
+                for (factor, value) in context.factors(runner) {
+                    record.set(factor, &value);
+                }
                 debug!("{record:?}");
                 csv.append(record)?;
                 csv.flush()?;
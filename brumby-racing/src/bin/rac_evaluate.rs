@@ -14,7 +14,9 @@ use stanza::table::{Cell, Col, Row, Table};
 use tracing::{debug, info};
 
 use brumby_racing::data;
-use brumby_racing::data::{EventDetailExt, PlacePriceDeparture, PredicateClosures, RaceSummary};
+use brumby_racing::data::{
+    EventDetailExt, PlacePriceDeparture, PredicateClosures, RaceConditionsLookup, RaceSummary,
+};
 use brumby::file::ReadJsonFile;
 use brumby::market::{Market, OverroundMethod};
 use brumby_racing::model::cf::Coefficients;
@@ -35,6 +37,10 @@ struct Args {
     /// cutoff place price departure
     #[clap(short = 'd', long)]
     departure: Option<f64>,
+
+    /// JSON file with the track condition and distance of each race
+    #[clap(short = 'c', long)]
+    conditions: Option<PathBuf>,
 }
 impl Args {
     fn validate(&self) -> anyhow::Result<()> {
@@ -66,6 +72,13 @@ fn main() -> Result<(), Box<dyn Error>> {
     debug!("args: {args:?}");
 
     let start_time = Instant::now();
+    let conditions = match &args.conditions {
+        None => RaceConditionsLookup::default(),
+        Some(path) => {
+            debug!("loading race conditions from {path:?}");
+            RaceConditionsLookup::read_json_file(path)?
+        }
+    };
     let mut predicates = vec![];
     if let Some(race_type) = args.race_type {
         predicates.push(data::Predicate::Type { race_type });
@@ -121,6 +134,7 @@ fn main() -> Result<(), Box<dyn Error>> {
             win: sample_top_n.markets[0].clone(),
             place: sample_top_n.markets[race.places_paying - 1].clone(),
             places_paying: race.places_paying,
+            context: Some(race.context(&conditions)),
         };
         let sample_overrounds = sample_top_n.overrounds()?;
         let model = calibrator.fit(&sample_wp, &sample_overrounds)?.value;
@@ -11,7 +11,7 @@ use stanza::style::{HAlign, MinWidth, Separator, Styles};
 use stanza::table::{Col, Row, Table};
 use tracing::{debug, info};
 
-use brumby_racing::data::{download_by_id, RaceSummary};
+use brumby_racing::data::{download_by_id, RaceConditionsLookup, RaceSummary};
 use brumby::display::DisplaySlice;
 use brumby::file::ReadJsonFile;
 use brumby::market::{Market, Overround, OverroundMethod};
@@ -34,12 +34,20 @@ struct Args {
     #[clap(short = 'd', long)]
     download: Option<u64>,
 
+    /// JSON file with the track condition and distance of each race
+    #[clap(short = 'c', long)]
+    conditions: Option<PathBuf>,
+
     /// selections to price
     selections: Option<Selections<'static>>,
 
     /// model type
     #[clap(short = 'm', long, value_parser = parse_model_type, default_value = "fitted")]
-    model: ModelType
+    model: ModelType,
+
+    /// also price the multis formed by adding each top-N selection to the selections
+    #[clap(long)]
+    fringes: bool,
 }
 impl Args {
     fn validate(&self) -> anyhow::Result<()> {
@@ -85,6 +93,14 @@ async fn main() -> Result<(), Box<dyn Error>> {
         race.meeting_name, race.race_number, race.places_paying, race.prices,
     );
 
+    let conditions = match &args.conditions {
+        None => RaceConditionsLookup::default(),
+        Some(path) => {
+            debug!("loading race conditions from {path:?}");
+            RaceConditionsLookup::read_json_file(path)?
+        }
+    };
+
     let coefficients_file = match race.race_type {
         EventType::Thoroughbred => "brumby-racing/config/thoroughbred.cf.json",
         EventType::Greyhound => "brumby-racing/config/greyhound.cf.json",
@@ -105,6 +121,7 @@ async fn main() -> Result<(), Box<dyn Error>> {
         win: sample_top_n.markets[0].clone(),
         place: sample_top_n.markets[race.places_paying - 1].clone(),
         places_paying: race.places_paying,
+        context: Some(race.context(&conditions)),
     };
     let sample_overrounds = sample_top_n.overrounds()?;
 
@@ -147,7 +164,13 @@ async fn main() -> Result<(), Box<dyn Error>> {
     let model: Box<dyn Model> = match args.model {
         ModelType::Primed => {
             let primer = Primer::try_from(coefficients)?;
-            let model = primer.prime(&sample_wp.win, sample_wp.places_paying, fit_options.mc_trials, &sample_overrounds)?;
+            let model = primer.prime(
+                &sample_wp.win,
+                sample_wp.places_paying,
+                sample_wp.context.as_ref(),
+                fit_options.mc_trials,
+                &sample_overrounds,
+            )?;
             debug!("fitted {model:?}");
             Box::new(model.value)
         }
@@ -229,6 +252,21 @@ async fn main() -> Result<(), Box<dyn Error>> {
             "price generation took {:.3}s",
             price.elapsed.as_millis() as f64 / 1_000.
         );
+
+        if args.fringes {
+            let fringes = model.derive_multi_fringes(&selections)?;
+            for fringe in &fringes.value {
+                info!(
+                    "  fringe {}: probability: {:.6}, market odds: {:.3}",
+                    fringe.selection, fringe.quotation.probability, fringe.quotation.price
+                );
+            }
+            debug!(
+                "{} fringes took {:.3}s",
+                fringes.value.len(),
+                fringes.elapsed.as_millis() as f64 / 1_000.
+            );
+        }
     }
     Ok(())
 }
@@ -1,13 +1,18 @@
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
+use anyhow::bail;
 use chrono::{DateTime, Utc};
 use racing_scraper::racing::get_racing_data;
 use racing_scraper::racing::sports_bet::models::{EventDetail, EventType};
+use serde::{Deserialize, Serialize};
 
 use brumby::file;
 use brumby::file::ReadJsonFile;
 use brumby::linear::matrix::Matrix;
 
+use crate::model::cf::{DistanceBand, RaceClass, RaceContext, RaceType, TrackCondition};
+
 const PODIUM: usize = 4;
 
 #[derive(Debug)]
@@ -77,6 +82,11 @@ impl From<EventDetail> for RaceSummary {
                 }
             }
         }
+        let barriers = external
+            .runners
+            .iter()
+            .map(|runner| runner.barrier_number as usize)
+            .collect();
         Self {
             id: external.id,
             race_name: external.race_name,
@@ -86,6 +96,7 @@ impl From<EventDetail> for RaceSummary {
             capture_time: external.capture_time,
             places_paying: external.places_paying as usize,
             class_name: external.class_name,
+            barriers,
             prices,
         }
     }
@@ -101,8 +112,91 @@ pub struct RaceSummary {
     pub capture_time: DateTime<Utc>,
     pub places_paying: usize,
     pub class_name: String,
+    pub barriers: Vec<usize>,
     pub prices: Matrix<f64>,
 }
+impl RaceSummary {
+    /// Derives the context of the race, taking the track condition and distance from the
+    /// `conditions` entry for this race, or leaving them unknown if there is no such entry.
+    pub fn context(&self, conditions: &RaceConditionsLookup) -> RaceContext {
+        let (track_condition, distance_band) =
+            match conditions.get(&self.meeting_name, self.race_number) {
+                None => (TrackCondition::Unknown, DistanceBand::Unknown),
+                Some(conditions) => (
+                    TrackCondition::classify(&conditions.track_condition),
+                    DistanceBand::classify(conditions.distance),
+                ),
+            };
+        RaceContext {
+            race_type: RaceType::from(&self.race_type),
+            race_class: RaceClass::classify(&self.class_name),
+            barriers: self.barriers.clone(),
+            track_condition,
+            distance_band,
+        }
+    }
+}
+
+/// The published conditions of a race, which are not part of the scraped [EventDetail].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RaceConditions {
+    pub meeting_name: String,
+    pub race_number: u8,
+    /// The condition of the track, such as `"Good 4"`.
+    pub track_condition: String,
+    /// The race distance in metres.
+    pub distance: u32,
+}
+
+/// Race conditions keyed by meeting name (case-insensitive) and race number. As the key does
+/// not include a date, a lookup should cover a single racing day.
+#[derive(Debug, Default)]
+pub struct RaceConditionsLookup {
+    entries: HashMap<(String, u8), RaceConditions>,
+}
+impl RaceConditionsLookup {
+    pub fn get(&self, meeting_name: &str, race_number: u8) -> Option<&RaceConditions> {
+        self.entries.get(&(meeting_name.to_lowercase(), race_number))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn read_json_file(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        Self::try_from(Vec::<RaceConditions>::read_json_file(path)?)
+    }
+}
+
+impl TryFrom<Vec<RaceConditions>> for RaceConditionsLookup {
+    type Error = anyhow::Error;
+
+    fn try_from(conditions: Vec<RaceConditions>) -> Result<Self, Self::Error> {
+        let mut entries = HashMap::with_capacity(conditions.len());
+        for conditions in conditions {
+            let key = (conditions.meeting_name.to_lowercase(), conditions.race_number);
+            if entries.contains_key(&key) {
+                bail!(
+                    "duplicate conditions for {} race {}",
+                    conditions.meeting_name,
+                    conditions.race_number
+                );
+            }
+            entries.insert(key, conditions);
+        }
+        Ok(Self { entries })
+    }
+}
+
+impl From<&EventType> for RaceType {
+    fn from(event_type: &EventType) -> Self {
+        match event_type {
+            EventType::Thoroughbred => RaceType::Thoroughbred,
+            EventType::Harness => RaceType::Harness,
+            EventType::Greyhound => RaceType::Greyhound,
+        }
+    }
+}
 
 #[derive(Debug)]
 pub enum Predicate {
@@ -182,3 +276,76 @@ pub async fn download_by_id(id: u64) -> anyhow::Result<EventDetail> {
     let event_detail = get_racing_data(&id).await?;
     Ok(event_detail)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_summary() -> RaceSummary {
+        RaceSummary {
+            id: 1,
+            race_name: "Benchmark 64 Handicap".into(),
+            meeting_name: "Randwick".into(),
+            race_type: EventType::Thoroughbred,
+            race_number: 3,
+            capture_time: DateTime::default(),
+            places_paying: 3,
+            class_name: "BM64".into(),
+            barriers: vec![4, 1, 2],
+            prices: Matrix::allocate(PODIUM, 3),
+        }
+    }
+
+    fn create_lookup() -> RaceConditionsLookup {
+        RaceConditionsLookup::try_from(vec![RaceConditions {
+            meeting_name: "RANDWICK".into(),
+            race_number: 3,
+            track_condition: "Soft 6".into(),
+            distance: 1400,
+        }])
+        .unwrap()
+    }
+
+    #[test]
+    fn context_with_conditions() {
+        let context = create_summary().context(&create_lookup());
+        assert_eq!(
+            RaceContext {
+                race_type: RaceType::Thoroughbred,
+                race_class: RaceClass::Benchmark,
+                barriers: vec![4, 1, 2],
+                track_condition: TrackCondition::Soft,
+                distance_band: DistanceBand::Mile,
+            },
+            context
+        );
+    }
+
+    #[test]
+    fn context_without_conditions() {
+        let mut summary = create_summary();
+        summary.race_number = 4;
+        let context = summary.context(&create_lookup());
+        assert_eq!(TrackCondition::Unknown, context.track_condition);
+        assert_eq!(DistanceBand::Unknown, context.distance_band);
+        assert_eq!(RaceClass::Benchmark, context.race_class);
+
+        let context = create_summary().context(&RaceConditionsLookup::default());
+        assert_eq!(TrackCondition::Unknown, context.track_condition);
+        assert_eq!(DistanceBand::Unknown, context.distance_band);
+    }
+
+    #[test]
+    fn duplicate_conditions() {
+        let conditions = RaceConditions {
+            meeting_name: "Randwick".into(),
+            race_number: 3,
+            track_condition: "Good 4".into(),
+            distance: 1200,
+        };
+        let mut duplicate = conditions.clone();
+        duplicate.meeting_name = "randwick".into();
+        let err = RaceConditionsLookup::try_from(vec![conditions, duplicate]).unwrap_err();
+        assert_eq!("duplicate conditions for randwick race 3", err.to_string());
+    }
+}
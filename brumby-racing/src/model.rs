@@ -7,9 +7,9 @@ use tracing::debug;
 use brumby::capture::Capture;
 use brumby::linear::matrix::Matrix;
 use brumby::market::{Market, Overround, PriceBounds};
-use crate::model::cf::Coefficients;
+use crate::model::cf::{Coefficients, RaceContext};
 use crate::model::fit::{FitOptions, PlaceFitOutcome};
-use brumby::selection::{validate_plausible_selections, Selection};
+use brumby::selection::{validate_plausible_selections, Rank, Runner, Selection, Selections};
 use brumby::timed::Timed;
 use brumby::{market, mc, selection};
 use brumby::derived_price::DerivedPrice;
@@ -30,6 +30,7 @@ pub struct WinPlace {
     pub win: Market,
     pub place: Market,
     pub places_paying: usize,
+    pub context: Option<RaceContext>,
 }
 impl WinPlace {
     pub fn validate(&self) -> Result<(), anyhow::Error> {
@@ -40,6 +41,9 @@ impl WinPlace {
         if !VALID_PLACES_PAYING.contains(&self.places_paying) {
             bail!("number of places paying must be in the range {VALID_PLACES_PAYING:?}");
         }
+        if let Some(context) = &self.context {
+            context.validate(self.win.probs.len())?;
+        }
         Ok(())
     }
 
@@ -172,6 +176,7 @@ impl Primer {
         &self,
         win: &Market,
         places_paying: usize,
+        context: Option<&RaceContext>,
         mc_trials: u64,
         overrounds: &[Overround],
     ) -> Result<Timed<PrimedModel>, anyhow::Error> {
@@ -180,7 +185,8 @@ impl Primer {
             if overrounds.len() != PODIUM {
                 bail!("exactly {PODIUM} overrounds must be specified");
             }
-            let weighted_probs = fit::init_weighted_probs(&self.coefficients, win, places_paying - 1)?;
+            let weighted_probs =
+                fit::init_weighted_probs(&self.coefficients, win, places_paying - 1, context)?;
             let top_n = derive_prices(mc_trials, &weighted_probs, overrounds);
             Ok(PrimedModel {
                 mc_trials,
@@ -224,6 +230,18 @@ impl Model for PrimedModel {
             self.mc_trials,
         )
     }
+
+    fn derive_multi_fringes(
+        &self,
+        selections: &[Selection],
+    ) -> Result<Timed<Vec<Fringe>>, anyhow::Error> {
+        derive_multi_fringes(
+            &self.weighted_probs,
+            &self.top_n,
+            selections,
+            self.mc_trials,
+        )
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -250,6 +268,7 @@ impl Fitter {
                 &self.config.coefficients,
                 &wp.win,
                 &wp.places_paying - 1,
+                wp.context.as_ref(),
             )?;
             let fit_outcome = fit::fit_place(
                 &self.config.fit_options,
@@ -292,6 +311,22 @@ pub trait Model {
     fn weighted_probs(&self) -> &Matrix<f64>;
     fn prices(&self) -> &TopN;
     fn derive_multi(&self, selections: &[Selection]) -> Result<Timed<DerivedPrice>, anyhow::Error>;
+
+    /// Prices the multis formed by adding each top-_N_ selection of a runner not already in
+    /// `selections`, omitting those that cannot occur alongside `selections`. Only top-_N_
+    /// ([Selection::Span]) fringes are offered: there are no exact-rank markets to source their
+    /// overrounds from, although exact-rank legs may be among `selections`.
+    fn derive_multi_fringes(
+        &self,
+        selections: &[Selection],
+    ) -> Result<Timed<Vec<Fringe>>, anyhow::Error>;
+}
+
+/// A multi price for adding a selection to a partial multi.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fringe {
+    pub selection: Selection,
+    pub quotation: DerivedPrice,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -318,6 +353,18 @@ impl Model for FittedModel {
             self.mc_trials,
         )
     }
+
+    fn derive_multi_fringes(
+        &self,
+        selections: &[Selection],
+    ) -> Result<Timed<Vec<Fringe>>, anyhow::Error> {
+        derive_multi_fringes(
+            &self.fit_outcome.fitted_probs,
+            &self.top_n,
+            selections,
+            self.mc_trials,
+        )
+    }
 }
 
 fn derive_prices(
@@ -361,29 +408,227 @@ fn derive_multi(
 ) -> Result<Timed<DerivedPrice>, anyhow::Error> {
     Timed::result(|| {
         validate_plausible_selections(selections)?;
-        let mut overround = 1.0;
+        let overround = multi_overround(probs, top_n, selections)?;
+        let mut engine = mc::MonteCarloEngine::default()
+            .with_trials(mc_trials)
+            .with_probs(Capture::Borrowed(probs));
+        let frac = engine.simulate(selections);
+        Ok(quote(frac.quotient(), overround))
+    })
+}
+
+fn derive_multi_fringes(
+    probs: &Matrix<f64>,
+    top_n: &TopN,
+    selections: &[Selection],
+    mc_trials: u64,
+) -> Result<Timed<Vec<Fringe>>, anyhow::Error> {
+    Timed::result(|| {
+        if !selections.is_empty() {
+            validate_plausible_selections(selections)?;
+        }
+        let overround = multi_overround(probs, top_n, selections)?;
+
         let win_probs = &probs[0];
-        for selection in selections {
-            selection.validate(0..=PODIUM - 1, win_probs)?;
-            let (runner, rank) = match selection {
-                Selection::Span { runner, ranks } => (runner.as_index(), ranks.end().as_index()),
-                Selection::Exact { runner, rank } => (runner.as_index(), rank.as_index()),
-            };
-            let market = &top_n.markets[rank];
-            let prob = market.probs[runner];
-            if prob == 0. {
-                bail!("cannot price a runner with zero probability");
+        let mut fringes = vec![];
+        let mut fringe_overrounds = vec![];
+        let mut fringe_selections_list = vec![];
+        for (runner, &win_prob) in win_probs.iter().enumerate() {
+            let selected = selections
+                .iter()
+                .any(|selection| selected_runner(selection) == runner);
+            if win_prob == 0. || selected {
+                continue;
+            }
+            for rank in 0..PODIUM {
+                let selection = Runner::index(runner).top(Rank::index(rank));
+                if top_n.markets[rank].probs[runner] == 0. {
+                    continue;
+                }
+                let mut fringe_selections = selections.to_vec();
+                fringe_selections.push(selection.clone());
+                if validate_plausible_selections(&fringe_selections).is_err() {
+                    continue;
+                }
+                fringe_overrounds.push(overround * leg_overround(top_n, &selection)?);
+                fringe_selections_list.push(Selections::from(fringe_selections));
+                fringes.push(selection);
             }
-            let price = market.prices[runner];
-
-            overround *= 1. / prob / price;
         }
+
         let mut engine = mc::MonteCarloEngine::default()
             .with_trials(mc_trials)
             .with_probs(Capture::Borrowed(probs));
-        let frac = engine.simulate(selections);
-        let probability = f64::max(LOWEST_MULTI_PROBABILITY, frac.quotient());
-        let price = market::multiply_capped(1.0 / probability, overround, &MULTI_PRICE_BOUNDS);
-        Ok(DerivedPrice { probability, price })
+        let mut counts = vec![0; fringe_selections_list.len()];
+        engine.simulate_batch(&fringe_selections_list, &mut counts);
+
+        Ok(fringes
+            .into_iter()
+            .zip(fringe_overrounds)
+            .zip(counts)
+            .map(|((selection, overround), count)| Fringe {
+                selection,
+                quotation: quote(count as f64 / engine.trials() as f64, overround),
+            })
+            .collect())
     })
 }
+
+/// The product of the overrounds of the top-_N_ markets that the selections are priced from.
+fn multi_overround(
+    probs: &Matrix<f64>,
+    top_n: &TopN,
+    selections: &[Selection],
+) -> Result<f64, anyhow::Error> {
+    let win_probs = &probs[0];
+    let mut overround = 1.0;
+    for selection in selections {
+        selection.validate(0..=PODIUM - 1, win_probs)?;
+        overround *= leg_overround(top_n, selection)?;
+    }
+    Ok(overround)
+}
+
+fn leg_overround(top_n: &TopN, selection: &Selection) -> Result<f64, anyhow::Error> {
+    let (runner, rank) = match selection {
+        Selection::Span { runner, ranks } => (runner.as_index(), ranks.end().as_index()),
+        Selection::Exact { runner, rank } => (runner.as_index(), rank.as_index()),
+    };
+    let market = &top_n.markets[rank];
+    let prob = market.probs[runner];
+    if prob == 0. {
+        bail!("cannot price a runner with zero probability");
+    }
+    let price = market.prices[runner];
+    Ok(1. / prob / price)
+}
+
+#[inline(always)]
+fn selected_runner(selection: &Selection) -> usize {
+    match selection {
+        Selection::Span { runner, .. } | Selection::Exact { runner, .. } => runner.as_index(),
+    }
+}
+
+fn quote(probability: f64, overround: f64) -> DerivedPrice {
+    let probability = f64::max(LOWEST_MULTI_PROBABILITY, probability);
+    let price = market::multiply_capped(1.0 / probability, overround, &MULTI_PRICE_BOUNDS);
+    DerivedPrice { probability, price }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_float_eq::*;
+
+    use brumby::market::OverroundMethod;
+
+    use super::*;
+
+    const MC_TRIALS: u64 = 50_000;
+    const MC_TOLERANCE: f64 = 0.01;
+
+    /// A ten-runner race with the last runner scratched, and a distinct overround for each rank.
+    fn create_model() -> PrimedModel {
+        let win_probs = [0.2, 0.15, 0.13, 0.12, 0.1, 0.09, 0.08, 0.07, 0.06, 0.0];
+        let mut weighted_probs = Matrix::allocate(PODIUM, win_probs.len());
+        for rank in 0..PODIUM {
+            weighted_probs.row_slice_mut(rank).copy_from_slice(&win_probs);
+        }
+        let overrounds = [1.1, 1.15, 1.2, 1.25].map(|value| Overround {
+            method: OverroundMethod::Multiplicative,
+            value,
+        });
+        let top_n = derive_prices(MC_TRIALS, &weighted_probs, &overrounds);
+        PrimedModel {
+            mc_trials: MC_TRIALS,
+            weighted_probs,
+            top_n,
+        }
+    }
+
+    fn top(runner: usize, rank: usize) -> Selection {
+        Runner::index(runner).top(Rank::index(rank))
+    }
+
+    #[test]
+    fn fringes_match_multis() {
+        let model = create_model();
+        let selections = [top(0, 1)];
+        let fringes = model.derive_multi_fringes(&selections).unwrap().value;
+        assert!(!fringes.is_empty());
+        for fringe in fringes {
+            let mut fringe_selections = selections.to_vec();
+            fringe_selections.push(fringe.selection.clone());
+            let multi = model.derive_multi(&fringe_selections).unwrap().value;
+            assert_float_absolute_eq!(
+                multi.probability,
+                fringe.quotation.probability,
+                MC_TOLERANCE
+            );
+            assert_float_relative_eq!(multi.overround(), fringe.quotation.overround(), 1e-9);
+        }
+    }
+
+    #[test]
+    fn fringes_exclude_selected_and_implausible_runners() {
+        let model = create_model();
+        let selections = [top(0, 1), top(1, 1)];
+        let fringes = model.derive_multi_fringes(&selections).unwrap().value;
+        let fringe_selections = fringes
+            .iter()
+            .map(|fringe| fringe.selection.clone())
+            .collect::<Vec<_>>();
+
+        // a third runner cannot also finish in the top two; the scratched runner is never offered
+        let expected = (2..9)
+            .flat_map(|runner| (2..PODIUM).map(move |rank| top(runner, rank)))
+            .collect::<Vec<_>>();
+        assert_eq!(expected, fringe_selections);
+    }
+
+    #[test]
+    fn fringe_overround_per_leg() {
+        let model = create_model();
+        let selections = [top(0, 0)];
+        let fringes = model.derive_multi_fringes(&selections).unwrap().value;
+        for fringe in fringes {
+            let Selection::Span { ranks, .. } = &fringe.selection else {
+                unreachable!()
+            };
+            let leg_overround = [1.1, 1.15, 1.2, 1.25][ranks.end().as_index()];
+            assert_float_relative_eq!(1.1 * leg_overround, fringe.quotation.overround(), 1e-9);
+        }
+    }
+
+    #[test]
+    fn multi_and_leg_overrounds() {
+        let model = create_model();
+        for rank in 0..PODIUM {
+            assert_float_relative_eq!(
+                [1.1, 1.15, 1.2, 1.25][rank],
+                leg_overround(&model.top_n, &top(2, rank)).unwrap(),
+                1e-9
+            );
+        }
+        assert_float_relative_eq!(
+            1.1 * 1.2,
+            multi_overround(&model.weighted_probs, &model.top_n, &[top(0, 0), top(1, 2)]).unwrap(),
+            1e-9
+        );
+        assert_eq!(
+            "cannot price a runner with zero probability",
+            leg_overround(&model.top_n, &top(9, 0)).unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn quote_floors_probability() {
+        let quotation = quote(0.1, 1.2);
+        assert_float_relative_eq!(0.1, quotation.probability);
+        assert_float_relative_eq!(1.2, quotation.overround());
+
+        let quotation = quote(0.0, 1.2);
+        assert_eq!(LOWEST_MULTI_PROBABILITY, quotation.probability);
+        assert_eq!(MAX_MULTI_PRICE, quotation.price);
+    }
+}
@@ -1,3 +1,4 @@
+use anyhow::bail;
 use ordinalizer::Ordinal;
 use serde::{Deserialize, Serialize};
 use strum_macros::{Display, EnumCount, EnumIter};
@@ -17,6 +18,17 @@ impl Coefficients {
         self.w3.validate()?;
         Ok(())
     }
+
+    /// Whether any of the predictors depend on the [RaceContext] factors.
+    pub fn requires_context(&self) -> bool {
+        [&self.w1, &self.w2, &self.w3].into_iter().any(|predictor| {
+            predictor.regressors.iter().any(|regressor| {
+                Factor::CONTEXT
+                    .iter()
+                    .any(|factor| regressor.references(factor))
+            })
+        })
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -45,6 +57,21 @@ pub enum Factor {
     Weight1,
     Weight2,
     Weight3,
+    RaceType,
+    RaceClass,
+    Barrier,
+    TrackCondition,
+    DistanceBand,
+}
+impl Factor {
+    /// The factors describing the context of the race, sourced from a [RaceContext].
+    pub const CONTEXT: [Factor; 5] = [
+        Factor::RaceType,
+        Factor::RaceClass,
+        Factor::Barrier,
+        Factor::TrackCondition,
+        Factor::DistanceBand,
+    ];
 }
 
 impl From<Factor> for usize {
@@ -57,4 +84,269 @@ impl AsIndex for Factor {
     fn as_index(&self) -> usize {
         self.ordinal()
     }
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Ordinal, EnumIter, Display, Serialize, Deserialize)]
+pub enum RaceType {
+    Thoroughbred,
+    Harness,
+    Greyhound,
+}
+
+/// Broad grouping of races by the restrictions on their entrants, used as a categorical factor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Ordinal, EnumIter, Display, Serialize, Deserialize)]
+pub enum RaceClass {
+    Maiden,
+    Class,
+    Benchmark,
+    Handicap,
+    Stakes,
+    Other,
+}
+impl RaceClass {
+    /// Classifies a race by keywords in its free-form class name, such as `"Maiden"`, `"BM64"`,
+    /// `"Class 1 Handicap"` or `"Group 1"`. Where several keywords appear, the earlier variants
+    /// take precedence.
+    pub fn classify(class_name: &str) -> Self {
+        let class_name = class_name.to_lowercase();
+        let words: Vec<_> = class_name
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .collect();
+        let has_word = |candidates: &[&str]| words.iter().any(|word| candidates.contains(word));
+        let has_numbered = |prefix: &str| {
+            words.iter().any(|word| {
+                word.strip_prefix(prefix).is_some_and(|suffix| {
+                    !suffix.is_empty() && suffix.chars().all(|c| c.is_ascii_digit())
+                })
+            })
+        };
+
+        if has_word(&["maiden", "mdn"]) {
+            RaceClass::Maiden
+        } else if has_word(&["group", "listed", "stakes", "ffa"])
+            || has_numbered("g")
+            || class_name.contains("free for all")
+        {
+            RaceClass::Stakes
+        } else if has_word(&["benchmark"]) || has_numbered("bm") {
+            RaceClass::Benchmark
+        } else if has_word(&["handicap", "hcp"]) {
+            RaceClass::Handicap
+        } else if has_word(&["class", "grade", "restricted", "rst"])
+            || has_numbered("cl")
+            || has_numbered("c")
+        {
+            RaceClass::Class
+        } else {
+            RaceClass::Other
+        }
+    }
+}
+
+/// The state of the racing surface, used as a categorical factor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Ordinal, EnumIter, Display, Serialize, Deserialize)]
+pub enum TrackCondition {
+    Firm,
+    Good,
+    Soft,
+    Heavy,
+    Synthetic,
+    Unknown,
+}
+impl TrackCondition {
+    /// Classifies a track by its published condition, either by keyword, such as `"Good 4"` or
+    /// `"Slow"`, or by abbreviated or bare rating on the 1–10 scale, such as `"S5"` or `"8"`.
+    pub fn classify(condition: &str) -> Self {
+        let condition = condition.to_lowercase();
+        let words: Vec<_> = condition
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .collect();
+        let has_word = |candidates: &[&str]| words.iter().any(|word| candidates.contains(word));
+        let rating = words.iter().find_map(|word| {
+            let digits = word.trim_start_matches(|c: char| c.is_ascii_alphabetic());
+            if digits.len() < word.len() && !matches!(&word[..1], "f" | "g" | "s" | "h") {
+                return None;
+            }
+            digits.parse::<u8>().ok().filter(|rating| (1..=10).contains(rating))
+        });
+
+        if has_word(&["firm", "fast"]) {
+            TrackCondition::Firm
+        } else if has_word(&["good"]) {
+            TrackCondition::Good
+        } else if has_word(&["soft", "dead", "slow"]) {
+            TrackCondition::Soft
+        } else if has_word(&["heavy"]) {
+            TrackCondition::Heavy
+        } else if has_word(&["synthetic", "synth", "polytrack"]) {
+            TrackCondition::Synthetic
+        } else {
+            match rating {
+                Some(1..=2) => TrackCondition::Firm,
+                Some(3..=4) => TrackCondition::Good,
+                Some(5..=7) => TrackCondition::Soft,
+                Some(_) => TrackCondition::Heavy,
+                None => TrackCondition::Unknown,
+            }
+        }
+    }
+}
+
+/// Race distances, grouped into bands and used as a categorical factor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Ordinal, EnumIter, Display, Serialize, Deserialize)]
+pub enum DistanceBand {
+    /// Up to 1200 m.
+    Sprint,
+    /// Over 1200 m, up to 1600 m.
+    Mile,
+    /// Over 1600 m, up to 2200 m.
+    Middle,
+    /// Over 2200 m.
+    Staying,
+    Unknown,
+}
+impl DistanceBand {
+    pub fn classify(metres: u32) -> Self {
+        match metres {
+            0 => DistanceBand::Unknown,
+            1..=1200 => DistanceBand::Sprint,
+            1201..=1600 => DistanceBand::Mile,
+            1601..=2200 => DistanceBand::Middle,
+            _ => DistanceBand::Staying,
+        }
+    }
+}
+
+/// Attributes of the race beyond its prices. Categorical attributes are encoded by the ordinals
+/// of their variants, to be one-hot encoded by `Indicator` regressors.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RaceContext {
+    pub race_type: RaceType,
+    pub race_class: RaceClass,
+    /// The barrier (or box) number of each runner.
+    pub barriers: Vec<usize>,
+    pub track_condition: TrackCondition,
+    pub distance_band: DistanceBand,
+}
+impl RaceContext {
+    pub fn validate(&self, runners: usize) -> Result<(), anyhow::Error> {
+        if self.barriers.len() != runners {
+            bail!("expected {runners} barriers, got {}", self.barriers.len());
+        }
+        Ok(())
+    }
+
+    /// The values of the [Factor::CONTEXT] factors for the given runner.
+    pub fn factors(&self, runner: usize) -> [(Factor, f64); 5] {
+        [
+            (Factor::RaceType, self.race_type.ordinal() as f64),
+            (Factor::RaceClass, self.race_class.ordinal() as f64),
+            (Factor::Barrier, self.barriers[runner] as f64),
+            (Factor::TrackCondition, self.track_condition.ordinal() as f64),
+            (Factor::DistanceBand, self.distance_band.ordinal() as f64),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classify_race_class() {
+        for (class_name, expected) in [
+            ("Maiden Handicap", RaceClass::Maiden),
+            ("BM64", RaceClass::Benchmark),
+            ("Benchmark 78 Handicap", RaceClass::Benchmark),
+            ("G1", RaceClass::Stakes),
+            ("Listed", RaceClass::Stakes),
+            ("Free For All", RaceClass::Stakes),
+            ("C3", RaceClass::Class),
+            ("Grade 5", RaceClass::Class),
+            ("Class 1 Handicap", RaceClass::Handicap),
+            ("Open", RaceClass::Other),
+            ("", RaceClass::Other),
+        ] {
+            assert_eq!(expected, RaceClass::classify(class_name), "{class_name:?}");
+        }
+    }
+
+    #[test]
+    fn classify_track_condition() {
+        for (condition, expected) in [
+            ("Good 4", TrackCondition::Good),
+            ("SOFT5", TrackCondition::Soft),
+            ("Heavy 10", TrackCondition::Heavy),
+            ("Fast", TrackCondition::Firm),
+            ("Slow", TrackCondition::Soft),
+            ("Synthetic", TrackCondition::Synthetic),
+            ("S6", TrackCondition::Soft),
+            ("2", TrackCondition::Firm),
+            ("8", TrackCondition::Heavy),
+            ("R11", TrackCondition::Unknown),
+            ("", TrackCondition::Unknown),
+        ] {
+            assert_eq!(expected, TrackCondition::classify(condition), "{condition:?}");
+        }
+    }
+
+    #[test]
+    fn classify_distance_band() {
+        for (metres, expected) in [
+            (0, DistanceBand::Unknown),
+            (515, DistanceBand::Sprint),
+            (1200, DistanceBand::Sprint),
+            (1201, DistanceBand::Mile),
+            (1600, DistanceBand::Mile),
+            (2000, DistanceBand::Middle),
+            (3200, DistanceBand::Staying),
+        ] {
+            assert_eq!(expected, DistanceBand::classify(metres), "{metres}");
+        }
+    }
+
+    fn create_context() -> RaceContext {
+        RaceContext {
+            race_type: RaceType::Greyhound,
+            race_class: RaceClass::Class,
+            barriers: vec![1, 2, 5, 8],
+            track_condition: TrackCondition::Soft,
+            distance_band: DistanceBand::Sprint,
+        }
+    }
+
+    #[test]
+    fn context_factors() {
+        let context = create_context();
+        assert_eq!(
+            [
+                (Factor::RaceType, 2.),
+                (Factor::RaceClass, 1.),
+                (Factor::Barrier, 5.),
+                (Factor::TrackCondition, 2.),
+                (Factor::DistanceBand, 0.),
+            ],
+            context.factors(2)
+        );
+        assert_eq!(
+            Factor::CONTEXT.to_vec(),
+            context.factors(0).map(|(factor, _)| factor).to_vec()
+        );
+    }
+
+    #[test]
+    fn validate_context() {
+        let context = create_context();
+        context.validate(4).unwrap();
+        assert_eq!(
+            "expected 5 barriers, got 4",
+            context.validate(5).unwrap_err().to_string()
+        );
+        assert_eq!(
+            "expected 3 barriers, got 4",
+            context.validate(3).unwrap_err().to_string()
+        );
+    }
+}
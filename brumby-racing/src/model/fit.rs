@@ -12,7 +12,7 @@ use mc::MonteCarloEngine;
 use brumby::capture::Capture;
 use brumby::linear::matrix::Matrix;
 use brumby::market::{Market, MarketPrice, Overround, PriceBounds};
-use crate::model::cf::{Coefficients, Factor};
+use crate::model::cf::{Coefficients, Factor, RaceContext};
 use brumby::probs::SliceExt;
 use brumby::selection::{Rank, Selections};
 use brumby::{mc, selection};
@@ -123,13 +123,23 @@ pub struct PlaceFitOutcome {
     pub fitted_probs: Matrix<f64>,
 }
 
+/// Initialises the weighted probabilities of the non-winning ranks from the fitted coefficients.
+/// The race context is required if the coefficients depend on any of the context factors.
 pub fn init_weighted_probs(
     coefficients: &Coefficients,
     win_market: &Market,
     place_rank: usize,
+    context: Option<&RaceContext>,
 ) -> Result<Matrix<f64>, anyhow::Error> {
     coefficients.validate()?;
     let num_runners = win_market.probs.len();
+    match context {
+        Some(context) => context.validate(num_runners)?,
+        None if coefficients.requires_context() => {
+            bail!("the coefficients require the race context")
+        }
+        None => {}
+    }
     let active_runners = win_market.probs.iter().filter(|&&prob| prob != 0.).count() as f64;
 
     let mut weighted_probs: Matrix<_> = DilatedProbs::default()
@@ -148,6 +158,11 @@ pub fn init_weighted_probs(
             input[Factor::PlacesPaying.ordinal()] = places_paying;
             input[Factor::Stdev.ordinal()] = stdev;
             input[Factor::Weight0.ordinal()] = win_prob;
+            if let Some(context) = context {
+                for (factor, value) in context.factors(runner) {
+                    input[factor.ordinal()] = value;
+                }
+            }
 
             weighted_probs[(1, runner)] = cap_probability(coefficients.w1.predict(&input));
             weighted_probs[(2, runner)] = cap_probability(coefficients.w2.predict(&input));
@@ -1,8 +1,10 @@
 use criterion::{criterion_group, criterion_main, Criterion};
 use brumby::sv;
+use brumby::stack_vec::StackVec;
 
+use brumby_soccer::domain::{Player, Side};
 use brumby_soccer::interval;
-use brumby_soccer::interval::{Config, PruneThresholds, BivariateProbs, TeamProbs, UnivariateProbs};
+use brumby_soccer::interval::{Config, Expansions, PruneThresholds, BivariateProbs, PlayerProbs, TeamProbs, UnivariateProbs};
 
 fn criterion_benchmark(c: &mut Criterion) {
     fn run(intervals: u8, max_total_goals: u16) -> usize {
@@ -27,8 +29,78 @@ fn criterion_benchmark(c: &mut Criterion) {
         .len()
     }
 
+    fn run_players(intervals: u8, players: usize) -> usize {
+        let mut player_probs = StackVec::default();
+        for index in 0..players {
+            let side = if index % 2 == 0 { Side::Home } else { Side::Away };
+            player_probs.push((
+                Player::Named(side, format!("Player {index}")),
+                PlayerProbs { goal: Some(0.1), assist: None },
+            ));
+        }
+        interval::explore(
+            &Config {
+                intervals,
+                team_probs: TeamProbs {
+                    h1_goals: BivariateProbs { home: 0.25, away: 0.25, common: 0.25 },
+                    h2_goals: BivariateProbs { home: 0.25, away: 0.25, common: 0.25 },
+                    assists: UnivariateProbs { home: 1.0, away: 1.0 },
+                },
+                player_probs,
+                prune_thresholds: PruneThresholds {
+                    max_total_goals: u16::MAX,
+                    min_prob: 1e-4,
+                },
+                expansions: Expansions {
+                    ft_score: true,
+                    max_player_goals: 1,
+                    ..Expansions::empty()
+                },
+            },
+            0..intervals,
+        )
+        .prospects
+        .len()
+    }
+
+    fn run_scoring_team(intervals: u8, expansions: Expansions) -> usize {
+        interval::explore(
+            &Config {
+                intervals,
+                team_probs: TeamProbs {
+                    h1_goals: BivariateProbs { home: 0.25, away: 0.25, common: 0.25 },
+                    h2_goals: BivariateProbs { home: 0.25, away: 0.25, common: 0.25 },
+                    assists: UnivariateProbs { home: 1.0, away: 1.0 },
+                },
+                player_probs: sv![],
+                prune_thresholds: PruneThresholds {
+                    max_total_goals: u16::MAX,
+                    min_prob: 1e-6,
+                },
+                expansions,
+            },
+            0..intervals,
+        )
+        .prospects
+        .len()
+    }
+
+    let score_only = Expansions {
+        ft_score: true,
+        ..Expansions::empty()
+    };
+    let scoring_team = Expansions {
+        ft_score: true,
+        first_scoring_team: true,
+        last_scoring_team: true,
+        max_race_goals: 3,
+        ..Expansions::empty()
+    };
+
     // sanity check
     assert_eq!(81, run(4, u16::MAX));
+    assert!(run_players(4, interval::NUM_PLAYERS) > run_players(4, 1));
+    assert!(run_scoring_team(4, scoring_team.clone()) > run_scoring_team(4, score_only.clone()));
 
     c.bench_function("cri_interval_18_min_1e-6", |b| {
         b.iter(|| run(18, u16::MAX));
@@ -45,6 +117,25 @@ fn criterion_benchmark(c: &mut Criterion) {
     c.bench_function("cri_interval_90_min_1e-6_max_16_goals", |b| {
         b.iter(|| run(90, 16));
     });
+
+    // the first and last team to score, and the outcome of each race, multiply the prospects of each score
+    println!(
+        "18 intervals: {} prospects by score, {} by score and scoring team",
+        run_scoring_team(18, score_only.clone()),
+        run_scoring_team(18, scoring_team.clone())
+    );
+    c.bench_function("cri_interval_18_min_1e-6_score_only", |b| {
+        b.iter(|| run_scoring_team(18, score_only.clone()));
+    });
+    c.bench_function("cri_interval_18_min_1e-6_scoring_team", |b| {
+        b.iter(|| run_scoring_team(18, scoring_team.clone()));
+    });
+
+    for players in [1, 4, 8, interval::NUM_PLAYERS] {
+        c.bench_function(&format!("cri_interval_18_min_1e-4_{players}_players"), |b| {
+            b.iter(|| run_players(18, players));
+        });
+    }
 }
 
 criterion_group!(benches, criterion_benchmark);
@@ -1,9 +1,10 @@
 use brumby_soccer::domain::{OfferType, Outcome, Player, Side};
 use criterion::{criterion_group, criterion_main, Criterion};
 use brumby::sv;
+use brumby::stack_vec::StackVec;
 
-use brumby_soccer::interval::{explore, Exploration, Config, BivariateProbs, PruneThresholds, PlayerProbs, TeamProbs, UnivariateProbs};
-use brumby_soccer::interval::query::isolate;
+use brumby_soccer::interval::{explore, Exploration, Config, BivariateProbs, Expansions, PruneThresholds, PlayerProbs, TeamProbs, UnivariateProbs, NUM_PLAYERS};
+use brumby_soccer::interval::query::{isolate, isolate_set};
 
 fn criterion_benchmark(c: &mut Criterion) {
     let player = Player::Named(Side::Home, "Markos".into());
@@ -27,6 +28,43 @@ fn criterion_benchmark(c: &mut Criterion) {
         )
     }
 
+    fn prepare_players(intervals: u8, players: &[Player]) -> Exploration {
+        let mut player_probs = StackVec::default();
+        for player in players {
+            player_probs.push((player.clone(), PlayerProbs { goal: Some(0.1), assist: None }));
+        }
+        explore(
+            &Config {
+                intervals,
+                team_probs: TeamProbs {
+                    h1_goals: BivariateProbs { home: 0.25, away: 0.25, common: 0.25 },
+                    h2_goals: BivariateProbs { home: 0.25, away: 0.25, common: 0.25 },
+                    assists: UnivariateProbs { home: 1.0, away: 1.0 },
+                },
+                player_probs,
+                prune_thresholds: PruneThresholds {
+                    max_total_goals: u16::MAX,
+                    min_prob: 1e-4,
+                },
+                expansions: Expansions {
+                    ft_score: true,
+                    max_player_goals: 1,
+                    ..Expansions::empty()
+                },
+            },
+            0..intervals,
+        )
+    }
+
+    fn create_players(count: usize) -> Vec<Player> {
+        (0..count)
+            .map(|index| {
+                let side = if index % 2 == 0 { Side::Home } else { Side::Away };
+                Player::Named(side, format!("Player {index}"))
+            })
+            .collect()
+    }
+
     // sanity check
     let exploration = prepare(18, u16::MAX, player.clone());
     // println!("prospects: {}", exploration.prospects.len());
@@ -61,6 +99,20 @@ fn criterion_benchmark(c: &mut Criterion) {
             )
         });
     });
+
+    for players in [1, 4, 8, NUM_PLAYERS] {
+        c.bench_function(&format!("cri_isolate_set_anytime_goalscorer_18_{players}_players"), |b| {
+            let players = create_players(players);
+            let exploration = prepare_players(18, &players);
+            let selections = players
+                .into_iter()
+                .map(|player| (OfferType::AnytimeGoalscorer, Outcome::Player(player)))
+                .collect::<Vec<_>>();
+            b.iter(|| {
+                isolate_set(&selections, &exploration.prospects, &exploration.player_lookup)
+            });
+        });
+    }
 }
 
 criterion_group!(benches, criterion_benchmark);
@@ -0,0 +1,132 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use brumby::hash_lookup::HashLookup;
+use brumby::market::{Market, Overround, OverroundMethod, PriceBounds};
+use brumby_soccer::domain::{
+    DrawHandicap, Offer, OfferType, Outcome, Over, Period, Player, Side, WinHandicap,
+};
+use brumby_soccer::interval::{BivariateProbs, PlayerProbs};
+use brumby_soccer::model::multi_pricing::MultiPricingPolicy;
+use brumby_soccer::model::{Config, GoalProbs, Model};
+
+const SINGLE_PRICE_BOUNDS: PriceBounds = 1.001..=1001.0;
+const OVERROUND: Overround = Overround {
+    method: OverroundMethod::Multiplicative,
+    value: 1.05,
+};
+
+fn criterion_benchmark(c: &mut Criterion) {
+    fn create_players(count: usize) -> Vec<Player> {
+        (0..count)
+            .map(|index| {
+                let side = if index % 2 == 0 { Side::Home } else { Side::Away };
+                Player::Named(side, format!("Player {index}"))
+            })
+            .collect()
+    }
+
+    fn prepare(players: &[Player]) -> Model {
+        let mut model = Model::try_from(Config {
+            intervals: 18,
+            max_total_goals: 8,
+        })
+        .unwrap();
+        model.goal_probs = Some(GoalProbs {
+            h1: BivariateProbs { home: 0.03, away: 0.025, common: 0.001 },
+            h2: BivariateProbs { home: 0.035, away: 0.03, common: 0.001 },
+        });
+        for player in players {
+            model.player_probs.insert(player.clone(), PlayerProbs { goal: Some(0.02), assist: None });
+        }
+        model.insert_offer(Offer {
+            offer_type: OfferType::HeadToHead(Period::FullTime, DrawHandicap::Ahead(0)),
+            outcomes: HashLookup::from([
+                Outcome::Win(Side::Home, WinHandicap::AheadOver(0)),
+                Outcome::Draw(DrawHandicap::Ahead(0)),
+                Outcome::Win(Side::Away, WinHandicap::BehindUnder(0)),
+            ]),
+            market: Market::frame(&OVERROUND, vec![0.45, 0.28, 0.27], &SINGLE_PRICE_BOUNDS),
+        });
+        model.insert_offer(Offer {
+            offer_type: OfferType::TotalGoals(Period::FullTime, Over(2)),
+            outcomes: HashLookup::from([Outcome::Over(2), Outcome::Under(3)]),
+            market: Market::frame(&OVERROUND, vec![0.5, 0.5], &SINGLE_PRICE_BOUNDS),
+        });
+        model.insert_offer(Offer {
+            offer_type: OfferType::AnytimeGoalscorer,
+            outcomes: HashLookup::from(
+                players.iter().map(|player| Outcome::Player(player.clone())).collect::<Vec<_>>(),
+            ),
+            market: Market::frame(&OVERROUND, vec![0.3; players.len()], &SINGLE_PRICE_BOUNDS),
+        });
+        model
+    }
+
+    /// Every head-to-head and total goals pairing, alone and with each anytime goalscorer.
+    fn create_selection_sets(model: &Model, players: &[Player]) -> Vec<Vec<(OfferType, Outcome)>> {
+        let head_to_head = &model.offers[&OfferType::HeadToHead(Period::FullTime, DrawHandicap::Ahead(0))];
+        let total_goals = &model.offers[&OfferType::TotalGoals(Period::FullTime, Over(2))];
+        let mut selection_sets = vec![];
+        for h2h_outcome in head_to_head.outcomes.items() {
+            for tg_outcome in total_goals.outcomes.items() {
+                let selections = vec![
+                    (head_to_head.offer_type.clone(), h2h_outcome.clone()),
+                    (total_goals.offer_type.clone(), tg_outcome.clone()),
+                ];
+                selection_sets.push(selections.clone());
+                for player in players {
+                    let mut selections = selections.clone();
+                    selections.push((OfferType::AnytimeGoalscorer, Outcome::Player(player.clone())));
+                    selection_sets.push(selections);
+                }
+            }
+        }
+        selection_sets
+    }
+
+    let players = create_players(4);
+    let model = prepare(&players);
+    let selection_sets = create_selection_sets(&model, &players);
+    let policy = MultiPricingPolicy::default();
+
+    // sanity check
+    let batch = model.derive_multi_batch(&selection_sets, &policy).unwrap().value;
+    assert_eq!(30, batch.len());
+    assert!(batch.iter().all(|derivation| derivation.is_ok()));
+
+    // the individual multis are priced as batches of one, so that neither side derives fringes;
+    // the explorations of both are served from the model's cache once warmed up by the first
+    // iteration
+    c.bench_function("cri_multi_30_individually", |b| {
+        b.iter(|| {
+            for selections in selection_sets.chunks(1) {
+                model.derive_multi_batch(selections, &policy).unwrap();
+            }
+        });
+    });
+
+    c.bench_function("cri_multi_30_batch", |b| {
+        b.iter(|| {
+            model.derive_multi_batch(&selection_sets, &policy).unwrap();
+        });
+    });
+
+    c.bench_function("cri_multi_30_individually_uncached", |b| {
+        b.iter(|| {
+            model.exploration_cache().clear();
+            for selections in selection_sets.chunks(1) {
+                model.derive_multi_batch(selections, &policy).unwrap();
+            }
+        });
+    });
+
+    c.bench_function("cri_multi_30_batch_uncached", |b| {
+        b.iter(|| {
+            model.exploration_cache().clear();
+            model.derive_multi_batch(&selection_sets, &policy).unwrap();
+        });
+    });
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
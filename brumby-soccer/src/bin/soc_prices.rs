@@ -17,16 +17,18 @@ use stanza::table::{Cell, Content, Row, Table};
 use tracing::{debug, info, warn};
 
 use brumby::hash_lookup::HashLookup;
-use brumby::market::{Market, OverroundMethod, PriceBounds};
+use brumby::market::{Market, Overround, OverroundMethod, PriceBounds};
 use brumby::tables;
 use brumby::timed::Timed;
 use brumby_soccer::data::{download_by_id, ContestSummary, SoccerFeedId};
-use brumby_soccer::domain::{Offer, OfferType, Outcome};
+use brumby_soccer::domain::{MatchResult, Offer, OfferType, Outcome};
 use brumby_soccer::fit::{ErrorType, FittingErrors};
+use brumby_soccer::model::multi_pricing::{CorrelationLoading, MultiPricingPolicy};
 use brumby_soccer::model::player_assist_fitter::PlayerAssistFitter;
 use brumby_soccer::model::player_goal_fitter::PlayerGoalFitter;
 use brumby_soccer::model::score_fitter::ScoreFitter;
 use brumby_soccer::model::{score_fitter, FitError, Model, Stub};
+use brumby_soccer::scoregrid::ScoreDistribution;
 use brumby_soccer::{fit, model, print};
 
 const OVERROUND_METHOD: OverroundMethod = OverroundMethod::OddsRatio;
@@ -54,6 +56,11 @@ struct Args {
     #[clap(long = "player-assists")]
     player_assists: bool,
 
+    /// score distribution to calibrate the goal model against (dixon-coles, negative-binomial or
+    /// weibull-count)
+    #[clap(long = "score-distribution")]
+    score_distribution: Option<ScoreDistribution>,
+
     /// JSON file containing the selections to price
     selections: Option<String>,
 }
@@ -114,11 +121,39 @@ async fn main() -> Result<(), Box<dyn Error>> {
                 | OfferType::CorrectScore(_)
                 | OfferType::AsianHandicap(_, _)
                 | OfferType::DrawNoBet(_)
-                | OfferType::SplitHandicap(_, _, _) => 1.0,
+                | OfferType::SplitHandicap(_, _, _)
+                | OfferType::SplitTotalGoals(_, _, _)
+                | OfferType::ToQualify
+                | OfferType::MethodOfVictory
+                | OfferType::TimeOfFirstGoal
+                | OfferType::GoalInTimeBand(_)
+                | OfferType::GoalInBothHalves
+                | OfferType::HighestScoringHalf
+                | OfferType::FirstTeamToScore
+                | OfferType::LastTeamToScore
+                | OfferType::RaceToGoals(_)
+                | OfferType::TotalEvents(_, _)
+                | OfferType::TeamTotalEvents(_, _, _)
+                | OfferType::EventHandicap(_, _)
+                | OfferType::HalfTimeFullTime
+                | OfferType::TeamTotalGoals(_, _, _)
+                | OfferType::ExactTeamGoals(_, _, _)
+                | OfferType::ExactTotalGoals(_, _)
+                | OfferType::OddEvenGoals(_)
+                | OfferType::WinningMargin(_) => 1.0,
+                OfferType::DoubleChance(_) => 2.0,
                 OfferType::AnytimeGoalscorer
                 | OfferType::FirstGoalscorer
                 | OfferType::PlayerShotsOnTarget(_)
-                | OfferType::AnytimeAssist => {
+                | OfferType::AnytimeAssist
+                | OfferType::LastGoalscorer
+                | OfferType::PlayerToScore(_)
+                | OfferType::PlayerToScoreInHalf(_)
+                | OfferType::PlayerToScoreInBothHalves
+                | OfferType::PlayerToScoreMultipleInHalf(_, _)
+                | OfferType::Scorecast
+                | OfferType::Wincast
+                | OfferType::PlayerEvents(_, _) => {
                     let implied_booksum = implied_booksum(prices.values());
                     let expected_overround = 1.0 + prices.len() as f64 * INCREMENTAL_OVERROUND;
                     implied_booksum / expected_overround
@@ -134,8 +169,16 @@ async fn main() -> Result<(), Box<dyn Error>> {
         max_total_goals: MAX_TOTAL_GOALS,
     })?;
 
-    let score_fitter = ScoreFitter::try_from(score_fitter::Config::default())?;
-    score_fitter.fit(&mut model, &sample_offers)?;
+    let score_fitter = ScoreFitter::try_from(score_fitter::Config {
+        score_distribution: args.score_distribution,
+        ..score_fitter::Config::default()
+    })?;
+    let residuals = score_fitter.fit(&mut model, &sample_offers)?;
+    info!(
+        "Goal model residuals: ({} offers)\n{}",
+        residuals.len(),
+        Console::default().render(&print::tabulate_residuals(&residuals))
+    );
 
     let player_goal_fitter = PlayerGoalFitter;
     let fit_result = player_goal_fitter.fit(&mut model, &sample_offers);
@@ -161,7 +204,7 @@ async fn main() -> Result<(), Box<dyn Error>> {
         }
     };
 
-    let stubs = sample_offers
+    let mut stubs = sample_offers
         .iter()
         .filter(|(offer_type, _)| {
             matches!(
@@ -171,10 +214,19 @@ async fn main() -> Result<(), Box<dyn Error>> {
                     | OfferType::AsianHandicap(_, _)
                     | OfferType::DrawNoBet(_)
                     | OfferType::SplitHandicap(_, _, _)
+                    | OfferType::SplitTotalGoals(_, _, _)
                     | OfferType::CorrectScore(_)
                     | OfferType::FirstGoalscorer
                     | OfferType::AnytimeGoalscorer
                     | OfferType::AnytimeAssist
+                    | OfferType::HalfTimeFullTime
+                    | OfferType::TeamTotalGoals(_, _, _)
+                    | OfferType::ExactTeamGoals(_, _, _)
+                    | OfferType::WinningMargin(_)
+                    | OfferType::DoubleChance(_)
+                    | OfferType::LastGoalscorer
+                    | OfferType::PlayerToScore(_)
+                    | OfferType::PlayerToScoreInHalf(_)
             )
         })
         .filter(|(_, offer)| {
@@ -190,6 +242,28 @@ async fn main() -> Result<(), Box<dyn Error>> {
         })
         .collect::<Vec<_>>();
 
+    if !sample_offers.contains_key(&OfferType::HalfTimeFullTime) {
+        // HT/FT is rarely sourced; derive it from the fitted model using a nominal overround
+        let outcomes = MatchResult::ALL
+            .iter()
+            .flat_map(|ht_result| {
+                MatchResult::ALL.iter().map(|ft_result| {
+                    Outcome::HalfTimeFullTime(ht_result.clone(), ft_result.clone())
+                })
+            })
+            .collect::<Vec<_>>();
+        let overround = Overround {
+            method: OVERROUND_METHOD,
+            value: 1.0 + outcomes.len() as f64 * INCREMENTAL_OVERROUND,
+        };
+        stubs.push(Stub {
+            offer_type: OfferType::HalfTimeFullTime,
+            outcomes: HashLookup::from(outcomes),
+            normal: 1.0,
+            overround,
+        });
+    }
+
     let Timed {
         value: cache_stats,
         elapsed,
@@ -236,9 +310,9 @@ async fn main() -> Result<(), Box<dyn Error>> {
         let fitting_errors = model
             .offers
             .values()
-            .map(|fitted| {
-                let sample = sample_offers.get(&fitted.offer_type).unwrap();
-                (
+            .filter_map(|fitted| {
+                let sample = sample_offers.get(&fitted.offer_type)?;
+                Some((
                     &sample.offer_type,
                     FittingErrors {
                         rmse: fit::compute_error(
@@ -252,7 +326,7 @@ async fn main() -> Result<(), Box<dyn Error>> {
                             &ErrorType::SquaredRelative,
                         ),
                     },
-                )
+                ))
             })
             .collect::<Vec<_>>();
 
@@ -272,53 +346,59 @@ async fn main() -> Result<(), Box<dyn Error>> {
 
     if let Some(ref selections) = args.selections {
         let selections = load_selections(selections)?;
-        let derivation = model.derive_multi(&selections)?;
+        let policy = MultiPricingPolicy {
+            correlation_loading: CorrelationLoading::LogRelatedness {
+                coefficient: 0.5,
+                scale: 100.0,
+            },
+            ..MultiPricingPolicy::default()
+        };
+        let derivation = model.derive_multi_with_policy(&selections, &policy)?;
         let elapsed = derivation.elapsed;
         let derivation = derivation.value;
         // let relatedness =
         //     compute_relatedness_coefficient(&selections, model.offers(), derivation.probability);
-        let scaling_exponent = compute_scaling_exponent(derivation.relatedness);
-        let scaled_price = scale_price(&derivation.quotation, scaling_exponent);
-        info!("selections: {selections:?}, quotation: {:?}, overround: {:.3}, relatedness: {:.3}, redundancies: {:?}, scaling_exponent: {scaling_exponent:?}, scaled_price: {scaled_price:.3}, took: {elapsed:?}",
+        let bounded_price = bound_price(&derivation.quotation);
+        info!("selections: {selections:?}, quotation: {:?}, overround: {:.3}, relatedness: {:.3}, redundancies: {:?}, bounded_price: {bounded_price:.3}, took: {elapsed:?}",
             derivation.quotation, derivation.quotation.overround(), derivation.relatedness, derivation.redundancies);
+        let explanation = model.explain_multi(&selections, &policy)?.value;
+        info!(
+            "Price explanation:\n{}",
+            Console::default().render(&print::tabulate_multi_explanation(&explanation))
+        );
         let mut total_fringes = 0;
         let mut unattainable_fringes = 0;
         for (offer, fringe_vec) in derivation.fringes.into_iter().collect::<BTreeMap<_, _>>() {
             info!("\nfringe offer: {offer:?}");
             for fringe in fringe_vec {
-                let scaling_exponent = compute_scaling_exponent(fringe.relatedness);
-                let scaled_price = scale_price(&fringe.quotation, scaling_exponent);
+                let bounded_price = bound_price(&fringe.quotation);
                 total_fringes += 1;
                 if fringe.quotation.probability == 0.0 {
                     unattainable_fringes += 1;
                 }
                 info!(
-                    "  {fringe:?}, overround: {:.3}, scaled_price: {scaled_price:.3}",
+                    "  {fringe:?}, overround: {:.3}, bounded_price: {bounded_price:.3}",
                     fringe.quotation.overround()
                 );
             }
         }
         info!("{total_fringes} fringes derived, {unattainable_fringes} are unattainable");
+        debug!("exploration cache: {:?}", model.exploration_cache().stats());
     }
     Ok(())
 }
 
-fn scale_price(quotation: &DerivedPrice, scaling_exponent: f64) -> f64 {
+fn bound_price(quotation: &DerivedPrice) -> f64 {
     if quotation.price.is_finite() {
-        let scaled_price = quotation.price / quotation.overround().powf(scaling_exponent - 1.0);
         f64::max(
             *MULTI_PRICE_BOUNDS.start(),
-            f64::min(scaled_price, *MULTI_PRICE_BOUNDS.end()),
+            f64::min(quotation.price, *MULTI_PRICE_BOUNDS.end()),
         )
     } else {
         quotation.price
     }
 }
 
-fn compute_scaling_exponent(relatedness: f64) -> f64 {
-    0.5 * f64::log10(100.0 * relatedness)
-}
-
 fn implied_booksum<'a>(prices: impl Iterator<Item = &'a f64>) -> f64 {
     prices.map(|&price| 1.0 / price).sum()
 }
@@ -1,6 +1,6 @@
 use crate::domain::{DrawHandicap, OfferType, Outcome, Over, Period, Player, Score, Side, WinHandicap};
 use racing_scraper::sports::soccer::contest_model::ContestModel;
-use racing_scraper::sports::soccer::market_model::{HomeAway, Player as ScraperPlayer, SoccerMarket};
+use racing_scraper::sports::soccer::market_model::{HomeAway, OneTwo, OverUnderMarket, Player as ScraperPlayer, SoccerMarket};
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 use std::str::FromStr;
@@ -38,14 +38,8 @@ impl From<ContestModel> for ContestSummary {
                     );
                 }
                 SoccerMarket::TotalGoalsOverUnder(prices, line) => {
-                    let (over, under) = (line.floor() as u8, line.ceil() as u8);
-                    offerings.insert(
-                        OfferType::TotalGoals(Period::FullTime, Over(over)),
-                        HashMap::from([
-                            (Outcome::Over(over), prices.over.unwrap_or(f64::INFINITY)),
-                            (Outcome::Under(under), prices.under.unwrap_or(f64::INFINITY)),
-                        ]),
-                    );
+                    let (offer_type, prices) = to_total_goals(Period::FullTime, prices, line);
+                    offerings.insert(offer_type, prices);
                 }
                 SoccerMarket::H2H(prices) => {
                     offerings.insert(
@@ -103,19 +97,19 @@ impl From<ContestModel> for ContestSummary {
                         })),
                     );
                 }
-                SoccerMarket::Score2GoalsOrMore(_) => {
-                    //TODO
-                }
-                SoccerMarket::FirstHalfGoalsOverUnder(prices, line) => {
-                    let (over, under) = (line.floor() as u8, line.ceil() as u8);
+                SoccerMarket::Score2GoalsOrMore(players) => {
                     offerings.insert(
-                        OfferType::TotalGoals(Period::FirstHalf, Over(over)),
-                        HashMap::from([
-                            (Outcome::Over(over), prices.over.unwrap_or(f64::INFINITY)),
-                            (Outcome::Under(under), prices.under.unwrap_or(f64::INFINITY)),
-                        ]),
+                        OfferType::PlayerToScore(2),
+                        HashMap::from_iter(players.into_iter().map(|player| {
+                            let OutcomeOdds(outcome, odds) = OutcomeOdds::from(player);
+                            (outcome, odds)
+                        })),
                     );
                 }
+                SoccerMarket::FirstHalfGoalsOverUnder(prices, line) => {
+                    let (offer_type, prices) = to_total_goals(Period::FirstHalf, prices, line);
+                    offerings.insert(offer_type, prices);
+                }
                 SoccerMarket::FirstHalfH2H(prices) => {
                     offerings.insert(
                         OfferType::HeadToHead(Period::FirstHalf, DrawHandicap::Ahead(0)),
@@ -127,14 +121,8 @@ impl From<ContestModel> for ContestSummary {
                     );
                 }
                 SoccerMarket::SecondHalfGoalsOverUnder(prices, line) => {
-                    let (over, under) = (line.floor() as u8, line.ceil() as u8);
-                    offerings.insert(
-                        OfferType::TotalGoals(Period::SecondHalf, Over(over)),
-                        HashMap::from([
-                            (Outcome::Over(over), prices.over.unwrap_or(f64::INFINITY)),
-                            (Outcome::Under(under), prices.under.unwrap_or(f64::INFINITY)),
-                        ]),
-                    );
+                    let (offer_type, prices) = to_total_goals(Period::SecondHalf, prices, line);
+                    offerings.insert(offer_type, prices);
                 }
                 SoccerMarket::SecondHalfH2H(prices) => {
                     offerings.insert(
@@ -169,36 +157,16 @@ impl From<ContestModel> for ContestSummary {
                 SoccerMarket::FirstHalfCornersOverUnder(_, _) => {}
                 SoccerMarket::SecondHalfCornersOverUnder(_, _) => {}
                 SoccerMarket::TwoWayHandicap(prices, handicap) => {
-                    let win_handicap = to_win_handicap(handicap);
-                    // println!("two-way handicap: {handicap}, prices: {prices:?}");
-                    // println!("home: {:?}, away: {:?}", win_handicap, win_handicap.flip_asian());
-                    offerings.insert(
-                        OfferType::AsianHandicap(Period::FullTime, win_handicap.clone()),
-                        HashMap::from([
-                            (Outcome::Win(Side::Home, win_handicap.clone()), prices.home),
-                            (Outcome::Win(Side::Away, win_handicap.flip_asian()), prices.away),
-                        ]),
-                    );
+                    let (offer_type, prices) = to_two_way_handicap(Period::FullTime, prices, handicap);
+                    offerings.insert(offer_type, prices);
                 }
                 SoccerMarket::FirstHalfTwoWayHandicap(prices, handicap) => {
-                    let win_handicap = to_win_handicap(handicap);
-                    offerings.insert(
-                        OfferType::AsianHandicap(Period::FirstHalf, win_handicap.clone()),
-                        HashMap::from([
-                            (Outcome::Win(Side::Home, win_handicap.clone()), prices.home),
-                            (Outcome::Win(Side::Away, win_handicap.flip_asian()), prices.away),
-                        ]),
-                    );
+                    let (offer_type, prices) = to_two_way_handicap(Period::FirstHalf, prices, handicap);
+                    offerings.insert(offer_type, prices);
                 }
                 SoccerMarket::SecondHalfTwoWayHandicap(prices, handicap) => {
-                    let win_handicap = to_win_handicap(handicap);
-                    offerings.insert(
-                        OfferType::AsianHandicap(Period::SecondHalf, win_handicap.clone()),
-                        HashMap::from([
-                            (Outcome::Win(Side::Home, win_handicap.clone()), prices.home),
-                            (Outcome::Win(Side::Away, win_handicap.flip_asian()), prices.away),
-                        ]),
-                    );
+                    let (offer_type, prices) = to_two_way_handicap(Period::SecondHalf, prices, handicap);
+                    offerings.insert(offer_type, prices);
                 }
                 SoccerMarket::ThreeWayHandicap(prices, handicap) => {
                     // println!("three-way handicap: {handicap}, prices: {h2h:?}");
@@ -241,6 +209,97 @@ fn to_draw_handicap(handicap: i8) -> DrawHandicap {
     }
 }
 
+/// Maps a total goals line to an offer, splitting quarter lines between the adjacent whole and
+/// half lines.
+fn to_total_goals(
+    period: Period,
+    prices: OverUnderMarket,
+    line: f32,
+) -> (OfferType, HashMap<Outcome, f64>) {
+    let (over_price, under_price) = (
+        prices.over.unwrap_or(f64::INFINITY),
+        prices.under.unwrap_or(f64::INFINITY),
+    );
+    let over = line.floor() as u8;
+    match quarter_of(line) {
+        1 | 3 => {
+            let whole = line.round() as u8;
+            (
+                OfferType::SplitTotalGoals(period, whole, Over(over)),
+                HashMap::from([
+                    (Outcome::SplitOver(whole, over), over_price),
+                    (Outcome::SplitUnder(whole, over + 1), under_price),
+                ]),
+            )
+        }
+        _ => {
+            let under = line.ceil() as u8;
+            (
+                OfferType::TotalGoals(period, Over(over)),
+                HashMap::from([
+                    (Outcome::Over(over), over_price),
+                    (Outcome::Under(under), under_price),
+                ]),
+            )
+        }
+    }
+}
+
+/// Maps a two-way handicap, quoted from the home side's perspective, to an offer. Quarter lines
+/// become split handicaps.
+fn to_two_way_handicap(
+    period: Period,
+    prices: OneTwo,
+    handicap: f32,
+) -> (OfferType, HashMap<Outcome, f64>) {
+    match to_split_handicap(handicap) {
+        Some((draw_handicap, win_handicap)) => (
+            OfferType::SplitHandicap(period, draw_handicap.clone(), win_handicap.clone()),
+            HashMap::from([
+                (
+                    Outcome::SplitWin(Side::Home, draw_handicap.clone(), win_handicap.clone()),
+                    prices.home,
+                ),
+                (
+                    Outcome::SplitWin(Side::Away, draw_handicap.flip(), win_handicap.flip_asian()),
+                    prices.away,
+                ),
+            ]),
+        ),
+        None => {
+            let win_handicap = to_win_handicap(handicap);
+            (
+                OfferType::AsianHandicap(period, win_handicap.clone()),
+                HashMap::from([
+                    (Outcome::Win(Side::Home, win_handicap.clone()), prices.home),
+                    (Outcome::Win(Side::Away, win_handicap.flip_asian()), prices.away),
+                ]),
+            )
+        }
+    }
+}
+
+/// The number of quarter goals in the fractional part of the line.
+fn quarter_of(line: f32) -> u8 {
+    (line.abs() * 4.0).round() as u8 % 4
+}
+
+fn to_split_handicap(handicap: f32) -> Option<(DrawHandicap, WinHandicap)> {
+    let whole = handicap.abs().floor() as u8;
+    match (quarter_of(handicap), handicap > 0.0) {
+        // -x.25
+        (1, false) => Some((DrawHandicap::Ahead(whole), WinHandicap::AheadOver(whole))),
+        // -x.75
+        (3, false) => Some((DrawHandicap::Ahead(whole + 1), WinHandicap::AheadOver(whole))),
+        // +x.25; Behind(0) is always written as Ahead(0) by convention
+        (1, true) if whole == 0 => Some((DrawHandicap::Ahead(0), WinHandicap::BehindUnder(1))),
+        (1, true) => Some((DrawHandicap::Behind(whole), WinHandicap::BehindUnder(whole + 1))),
+        // +x.75
+        (3, true) => Some((DrawHandicap::Behind(whole + 1), WinHandicap::BehindUnder(whole + 1))),
+        _ => None,
+    }
+}
+
 fn to_win_handicap(handicap: f32) -> WinHandicap {
     if handicap > 0.0 {
         WinHandicap::BehindUnder(handicap as u8 + 1)
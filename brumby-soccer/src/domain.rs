@@ -1,9 +1,12 @@
+use std::cmp::Ordering;
+
 use bincode::Encode;
 use serde::{Deserialize, Serialize};
 
 use brumby::hash_lookup::HashLookup;
 use brumby::market::Market;
 
+pub mod settlement;
 pub mod validation;
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
@@ -41,6 +44,25 @@ pub enum Period {
     FullTime
 }
 
+/// A span of regular time, from the start minute (inclusive) to the end minute (exclusive). E.g.,
+/// the first ten minutes are `TimeBand { from: 0, to: 10 }`.
+#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct TimeBand {
+    pub from: u8,
+    pub to: u8,
+}
+impl TimeBand {
+    pub const MAX_MINUTE: u8 = 90;
+
+    pub fn new(from: u8, to: u8) -> Self {
+        Self { from, to }
+    }
+
+    pub fn contains(&self, minute: u8) -> bool {
+        (self.from..self.to).contains(&minute)
+    }
+}
+
 #[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum DrawHandicap {
     Ahead(u8),
@@ -96,7 +118,53 @@ pub enum OfferType {
     AnytimeGoalscorer,
     FirstGoalscorer,
     PlayerShotsOnTarget(Over),
-    AnytimeAssist
+    AnytimeAssist,
+    HalfTimeFullTime,
+    TeamTotalGoals(Period, Side, Over),
+    /// Exact goals scored by one side, with the last outcome covering all counts above the given line.
+    ExactTeamGoals(Period, Side, Over),
+    WinningMargin(Period),
+    DoubleChance(Period),
+    LastGoalscorer,
+    /// A player scoring at least the given number of goals (e.g., a brace or a hat-trick).
+    PlayerToScore(u8),
+    PlayerToScoreInHalf(Period),
+    PlayerToScoreInBothHalves,
+    /// A player scoring at least the given number of goals within one half.
+    PlayerToScoreMultipleInHalf(Period, u8),
+    /// Total goals on a quarter line, split evenly between the whole line of the given number of
+    /// goals and the adjacent half line. E.g., 2.25 is `(2, Over(2))`; 2.75 is `(3, Over(2))`.
+    SplitTotalGoals(Period, u8, Over),
+    /// The side progressing from a knockout tie, whether in regular time, extra time or on penalties.
+    ToQualify,
+    MethodOfVictory,
+    /// The time band of the first goal, with [Outcome::None] for no goal.
+    TimeOfFirstGoal,
+    GoalInTimeBand(TimeBand),
+    GoalInBothHalves,
+    /// The half with the most goals, with [Outcome::None] for an equal number of goals.
+    HighestScoringHalf,
+    /// The side scoring the first goal, with [Outcome::None] for no goal.
+    FirstTeamToScore,
+    LastTeamToScore,
+    /// The side first to score the given number of goals, with [Outcome::None] if neither does.
+    RaceToGoals(u8),
+    /// The first goalscorer together with the full-time correct score.
+    Scorecast,
+    /// An anytime goalscorer together with the full-time match result.
+    Wincast,
+    /// Total events over the match. Bookings are expressed in booking points.
+    TotalEvents(EventStream, Over),
+    TeamTotalEvents(EventStream, Side, Over),
+    /// A two-way handicap on the events of each side, as per [OfferType::AsianHandicap].
+    EventHandicap(EventStream, WinHandicap),
+    /// Each named player's own events over the match exceeding the line, as per
+    /// [OfferType::TotalEvents].
+    PlayerEvents(EventStream, Over),
+    /// Exact total goals, with the last outcome covering all counts above the given line.
+    ExactTotalGoals(Period, Over),
+    /// Whether the total goals are odd or even, nil counting as even.
+    OddEvenGoals(Period),
 }
 impl OfferType {
     pub fn category(&self) -> OfferCategory {
@@ -110,12 +178,58 @@ impl OfferType {
             OfferType::AnytimeGoalscorer => OfferCategory::AnytimeGoalscorer,
             OfferType::FirstGoalscorer => OfferCategory::FirstGoalscorer,
             OfferType::PlayerShotsOnTarget(_) => OfferCategory::PlayerShotsOnTarget,
-            OfferType::AnytimeAssist => OfferCategory::AnytimeAssist
+            OfferType::AnytimeAssist => OfferCategory::AnytimeAssist,
+            OfferType::HalfTimeFullTime => OfferCategory::HalfTimeFullTime,
+            OfferType::TeamTotalGoals(_, _, _) => OfferCategory::TeamTotalGoals,
+            OfferType::ExactTeamGoals(_, _, _) => OfferCategory::ExactTeamGoals,
+            OfferType::WinningMargin(_) => OfferCategory::WinningMargin,
+            OfferType::DoubleChance(_) => OfferCategory::DoubleChance,
+            OfferType::LastGoalscorer => OfferCategory::LastGoalscorer,
+            OfferType::PlayerToScore(_) => OfferCategory::PlayerToScore,
+            OfferType::PlayerToScoreInHalf(_) => OfferCategory::PlayerToScoreInHalf,
+            OfferType::PlayerToScoreInBothHalves => OfferCategory::PlayerToScoreInBothHalves,
+            OfferType::PlayerToScoreMultipleInHalf(_, _) => OfferCategory::PlayerToScoreMultipleInHalf,
+            OfferType::SplitTotalGoals(_, _, _) => OfferCategory::SplitTotalGoals,
+            OfferType::ToQualify => OfferCategory::ToQualify,
+            OfferType::MethodOfVictory => OfferCategory::MethodOfVictory,
+            OfferType::TimeOfFirstGoal => OfferCategory::TimeOfFirstGoal,
+            OfferType::GoalInTimeBand(_) => OfferCategory::GoalInTimeBand,
+            OfferType::GoalInBothHalves => OfferCategory::GoalInBothHalves,
+            OfferType::HighestScoringHalf => OfferCategory::HighestScoringHalf,
+            OfferType::FirstTeamToScore => OfferCategory::FirstTeamToScore,
+            OfferType::LastTeamToScore => OfferCategory::LastTeamToScore,
+            OfferType::RaceToGoals(_) => OfferCategory::RaceToGoals,
+            OfferType::Scorecast => OfferCategory::Scorecast,
+            OfferType::Wincast => OfferCategory::Wincast,
+            OfferType::TotalEvents(_, _) => OfferCategory::TotalEvents,
+            OfferType::TeamTotalEvents(_, _, _) => OfferCategory::TeamTotalEvents,
+            OfferType::EventHandicap(_, _) => OfferCategory::EventHandicap,
+            OfferType::PlayerEvents(_, _) => OfferCategory::PlayerEvents,
+            OfferType::ExactTotalGoals(_, _) => OfferCategory::ExactTotalGoals,
+            OfferType::OddEvenGoals(_) => OfferCategory::OddEvenGoals,
+        }
+    }
+
+    /// The stream of events that the offer is settled on, if other than goals.
+    pub fn event_stream(&self) -> Option<&EventStream> {
+        match self {
+            OfferType::TotalEvents(stream, _)
+            | OfferType::TeamTotalEvents(stream, _, _)
+            | OfferType::EventHandicap(stream, _)
+            | OfferType::PlayerEvents(stream, _) => Some(stream),
+            _ => None,
         }
     }
 
     pub fn is_auxiliary(&self) -> bool {
-        matches!(self, OfferType::DrawNoBet(_) | OfferType::SplitHandicap(_, _, _))
+        matches!(
+            self,
+            OfferType::DrawNoBet(_)
+                | OfferType::SplitHandicap(_, _, _)
+                | OfferType::SplitTotalGoals(_, _, _)
+                | OfferType::ToQualify
+                | OfferType::MethodOfVictory
+        )
     }
 }
 
@@ -131,6 +245,53 @@ pub enum OfferCategory {
     FirstGoalscorer,
     PlayerShotsOnTarget,
     AnytimeAssist,
+    HalfTimeFullTime,
+    TeamTotalGoals,
+    ExactTeamGoals,
+    WinningMargin,
+    DoubleChance,
+    LastGoalscorer,
+    PlayerToScore,
+    PlayerToScoreInHalf,
+    PlayerToScoreInBothHalves,
+    PlayerToScoreMultipleInHalf,
+    SplitTotalGoals,
+    ToQualify,
+    MethodOfVictory,
+    TimeOfFirstGoal,
+    GoalInTimeBand,
+    GoalInBothHalves,
+    HighestScoringHalf,
+    FirstTeamToScore,
+    LastTeamToScore,
+    RaceToGoals,
+    Scorecast,
+    Wincast,
+    TotalEvents,
+    TeamTotalEvents,
+    EventHandicap,
+    PlayerEvents,
+    ExactTotalGoals,
+    OddEvenGoals,
+}
+
+/// Countable match events that are modelled separately to the goals.
+#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum EventStream {
+    Corners,
+    /// Cards, counted in booking points: 10 per card.
+    Bookings,
+}
+impl EventStream {
+    pub const ALL: [EventStream; 2] = [EventStream::Corners, EventStream::Bookings];
+
+    /// The number of points that each event counts for when settling offers.
+    pub fn points(&self) -> u8 {
+        match self {
+            EventStream::Corners => 1,
+            EventStream::Bookings => 10,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Encode, Serialize, Deserialize)]
@@ -145,21 +306,78 @@ pub enum Player {
     Other
 }
 
+#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum MatchResult {
+    Win(Side),
+    Draw,
+}
+impl MatchResult {
+    pub const ALL: [MatchResult; 3] = [
+        MatchResult::Win(Side::Home),
+        MatchResult::Draw,
+        MatchResult::Win(Side::Away),
+    ];
+
+    pub fn from_score(score: &Score) -> Self {
+        match score.home.cmp(&score.away) {
+            Ordering::Greater => MatchResult::Win(Side::Home),
+            Ordering::Less => MatchResult::Win(Side::Away),
+            Ordering::Equal => MatchResult::Draw,
+        }
+    }
+}
+
+/// How a knockout tie is decided.
+#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum VictoryMethod {
+    RegularTime,
+    ExtraTime,
+    Penalties,
+}
+impl VictoryMethod {
+    pub const ALL: [VictoryMethod; 3] = [
+        VictoryMethod::RegularTime,
+        VictoryMethod::ExtraTime,
+        VictoryMethod::Penalties,
+    ];
+}
+
 #[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum Outcome {
     Win(Side, WinHandicap),
     Draw(DrawHandicap),
     SplitWin(Side, DrawHandicap, WinHandicap),
+    /// Over a quarter line, split between the whole line and the half line, the latter as per [Outcome::Over].
+    SplitOver(u8, u8),
+    /// Under a quarter line, split between the whole line and the half line, the latter as per [Outcome::Under].
+    SplitUnder(u8, u8),
     Under(u8),
     Over(u8),
+    Exactly(u8),
     Score(Score),
     Player(Player),
+    HalfTimeFullTime(MatchResult, MatchResult),
+    WinBy(Side, u8),
+    DoubleChance(MatchResult, MatchResult),
+    Qualify(Side),
+    Victory(Side, VictoryMethod),
+    FirstGoalIn(TimeBand),
+    Half(Period),
+    Team(Side),
+    Scorecast(Player, Score),
+    Wincast(Player, MatchResult),
+    Odd,
+    Even,
+    Yes,
+    No,
     None,
 }
 impl Outcome {
     pub fn get_player(&self) -> Option<&Player> {
         match self {
-            Outcome::Player(player) => Some(player),
+            Outcome::Player(player)
+            | Outcome::Scorecast(player, _)
+            | Outcome::Wincast(player, _) => Some(player),
             _ => None
         }
     }
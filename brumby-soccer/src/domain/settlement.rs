@@ -0,0 +1,258 @@
+//! Settlement of outcomes on whole and quarter lines, which may push (refund the stake) or settle
+//! half of the stake as a win or a loss, and the expected return thereof.
+
+use crate::domain::{DrawHandicap, Outcome, Score, Side, WinHandicap};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Settlement {
+    Win,
+    HalfWin,
+    Push,
+    HalfLoss,
+    Loss,
+}
+impl Settlement {
+    /// The amount returned per unit stake at the given decimal price.
+    pub fn payout(&self, price: f64) -> f64 {
+        match self {
+            Settlement::Win => price,
+            Settlement::HalfWin => 0.5 * price + 0.5,
+            Settlement::Push => 1.0,
+            Settlement::HalfLoss => 0.5,
+            Settlement::Loss => 0.0,
+        }
+    }
+
+    /// Settles a stake that is split evenly between two lines.
+    fn split(first: Settlement, second: Settlement) -> Settlement {
+        match (first, second) {
+            (Settlement::Win, Settlement::Win) => Settlement::Win,
+            (Settlement::Win, Settlement::Push) | (Settlement::Push, Settlement::Win) => Settlement::HalfWin,
+            (Settlement::Push, Settlement::Push) => Settlement::Push,
+            (Settlement::Push, Settlement::Loss) | (Settlement::Loss, Settlement::Push) => Settlement::HalfLoss,
+            (Settlement::Loss, Settlement::Loss) => Settlement::Loss,
+            (first, second) => panic!("lines {first:?} and {second:?} are not adjacent"),
+        }
+    }
+
+    fn from_margin(margin: i16, line: i16) -> Settlement {
+        if margin > line {
+            Settlement::Win
+        } else if margin == line {
+            Settlement::Push
+        } else {
+            Settlement::Loss
+        }
+    }
+
+    fn from_binary(win: bool) -> Settlement {
+        if win {
+            Settlement::Win
+        } else {
+            Settlement::Loss
+        }
+    }
+}
+
+/// The probabilities of each [Settlement] of an outcome.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SettlementProbs {
+    pub win: f64,
+    pub half_win: f64,
+    pub push: f64,
+    pub half_loss: f64,
+    pub loss: f64,
+}
+impl SettlementProbs {
+    pub fn add(&mut self, settlement: &Settlement, prob: f64) {
+        match settlement {
+            Settlement::Win => self.win += prob,
+            Settlement::HalfWin => self.half_win += prob,
+            Settlement::Push => self.push += prob,
+            Settlement::HalfLoss => self.half_loss += prob,
+            Settlement::Loss => self.loss += prob,
+        }
+    }
+
+    /// The expected amount returned per unit stake at the given decimal price.
+    pub fn expected_return(&self, price: f64) -> f64 {
+        self.win * Settlement::Win.payout(price)
+            + self.half_win * Settlement::HalfWin.payout(price)
+            + self.push * Settlement::Push.payout(price)
+            + self.half_loss * Settlement::HalfLoss.payout(price)
+    }
+
+    /// The price at which the expected return equals the stake.
+    pub fn fair_price(&self) -> f64 {
+        (1.0 - self.push - 0.5 * self.half_win - 0.5 * self.half_loss)
+            / (self.win + 0.5 * self.half_win)
+    }
+
+    /// The probability of a binary (win or lose) outcome that has the same fair price. This is
+    /// how outcomes on quarter lines are framed into a market.
+    pub fn effective_probability(&self) -> f64 {
+        1.0 / self.fair_price()
+    }
+}
+
+impl Outcome {
+    /// Settles this outcome given the score of the period that it applies to. Supports the
+    /// outcomes of goal and handicap lines, including quarter lines.
+    pub fn settle(&self, score: &Score) -> Settlement {
+        let total = score.total() as i16;
+        match self {
+            Outcome::Over(goals) => Settlement::from_binary(total > *goals as i16),
+            Outcome::Under(goals) => Settlement::from_binary(total < *goals as i16),
+            Outcome::Exactly(goals) => Settlement::from_binary(total == *goals as i16),
+            Outcome::SplitOver(whole, over) => Settlement::split(
+                Settlement::from_margin(total, *whole as i16),
+                Settlement::from_binary(total > *over as i16),
+            ),
+            Outcome::SplitUnder(whole, under) => Settlement::split(
+                Settlement::from_margin(-total, -(*whole as i16)),
+                Settlement::from_binary(total < *under as i16),
+            ),
+            Outcome::Score(expected) => Settlement::from_binary(expected == score),
+            Outcome::Win(side, win_handicap) => {
+                Settlement::from_binary(margin(side, score) > win_line(win_handicap))
+            }
+            Outcome::Draw(draw_handicap) => {
+                Settlement::from_binary(margin(&Side::Home, score) == draw_line(draw_handicap))
+            }
+            Outcome::SplitWin(side, draw_handicap, win_handicap) => {
+                let margin = margin(side, score);
+                Settlement::split(
+                    Settlement::from_margin(margin, draw_line(draw_handicap)),
+                    Settlement::from_binary(margin > win_line(win_handicap)),
+                )
+            }
+            _ => panic!("{self:?} unsupported"),
+        }
+    }
+}
+
+/// The goals scored by the given side, less the goals conceded.
+fn margin(side: &Side, score: &Score) -> i16 {
+    match side {
+        Side::Home => score.home as i16 - score.away as i16,
+        Side::Away => score.away as i16 - score.home as i16,
+    }
+}
+
+/// The margin that must be exceeded for a win.
+fn win_line(win_handicap: &WinHandicap) -> i16 {
+    match win_handicap {
+        WinHandicap::AheadOver(by) => *by as i16,
+        WinHandicap::BehindUnder(by) => -(*by as i16),
+    }
+}
+
+/// The margin at which a whole line pushes.
+fn draw_line(draw_handicap: &DrawHandicap) -> i16 {
+    match draw_handicap {
+        DrawHandicap::Ahead(by) => *by as i16,
+        DrawHandicap::Behind(by) => -(*by as i16),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_float_eq::*;
+
+    use super::*;
+
+    #[test]
+    fn settle_split_over() {
+        // over 2.25
+        let outcome = Outcome::SplitOver(2, 2);
+        assert_eq!(Settlement::Loss, outcome.settle(&Score::new(1, 0)));
+        assert_eq!(Settlement::HalfLoss, outcome.settle(&Score::new(1, 1)));
+        assert_eq!(Settlement::Win, outcome.settle(&Score::new(2, 1)));
+
+        // over 2.75
+        let outcome = Outcome::SplitOver(3, 2);
+        assert_eq!(Settlement::Loss, outcome.settle(&Score::new(1, 1)));
+        assert_eq!(Settlement::HalfWin, outcome.settle(&Score::new(2, 1)));
+        assert_eq!(Settlement::Win, outcome.settle(&Score::new(2, 2)));
+    }
+
+    #[test]
+    fn settle_split_under() {
+        // under 2.25
+        let outcome = Outcome::SplitUnder(2, 3);
+        assert_eq!(Settlement::Win, outcome.settle(&Score::new(1, 0)));
+        assert_eq!(Settlement::HalfWin, outcome.settle(&Score::new(1, 1)));
+        assert_eq!(Settlement::Loss, outcome.settle(&Score::new(2, 1)));
+
+        // under 2.75
+        let outcome = Outcome::SplitUnder(3, 3);
+        assert_eq!(Settlement::Win, outcome.settle(&Score::new(1, 1)));
+        assert_eq!(Settlement::HalfLoss, outcome.settle(&Score::new(2, 1)));
+        assert_eq!(Settlement::Loss, outcome.settle(&Score::new(2, 2)));
+    }
+
+    #[test]
+    fn settle_split_win() {
+        // home -0.75
+        let outcome = Outcome::SplitWin(Side::Home, DrawHandicap::Ahead(1), WinHandicap::AheadOver(0));
+        assert_eq!(Settlement::Loss, outcome.settle(&Score::new(1, 1)));
+        assert_eq!(Settlement::HalfWin, outcome.settle(&Score::new(2, 1)));
+        assert_eq!(Settlement::Win, outcome.settle(&Score::new(2, 0)));
+
+        // away +0.75
+        let outcome = Outcome::SplitWin(Side::Away, DrawHandicap::Behind(1), WinHandicap::BehindUnder(1));
+        assert_eq!(Settlement::Win, outcome.settle(&Score::new(1, 1)));
+        assert_eq!(Settlement::HalfLoss, outcome.settle(&Score::new(2, 1)));
+        assert_eq!(Settlement::Loss, outcome.settle(&Score::new(2, 0)));
+
+        // home -0.25
+        let outcome = Outcome::SplitWin(Side::Home, DrawHandicap::Ahead(0), WinHandicap::AheadOver(0));
+        assert_eq!(Settlement::HalfLoss, outcome.settle(&Score::new(1, 1)));
+        assert_eq!(Settlement::Win, outcome.settle(&Score::new(2, 1)));
+
+        // away +0.25
+        let outcome = Outcome::SplitWin(Side::Away, DrawHandicap::Ahead(0), WinHandicap::BehindUnder(1));
+        assert_eq!(Settlement::HalfWin, outcome.settle(&Score::new(1, 1)));
+        assert_eq!(Settlement::Loss, outcome.settle(&Score::new(2, 1)));
+    }
+
+    #[test]
+    fn settle_binary() {
+        assert_eq!(Settlement::Win, Outcome::Over(2).settle(&Score::new(2, 1)));
+        assert_eq!(Settlement::Loss, Outcome::Under(3).settle(&Score::new(2, 1)));
+        assert_eq!(Settlement::Win, Outcome::Draw(DrawHandicap::Behind(1)).settle(&Score::new(1, 2)));
+        assert_eq!(
+            Settlement::Win,
+            Outcome::Win(Side::Away, WinHandicap::BehindUnder(1)).settle(&Score::new(1, 1))
+        );
+        assert_eq!(
+            Settlement::Loss,
+            Outcome::Win(Side::Home, WinHandicap::AheadOver(1)).settle(&Score::new(2, 1))
+        );
+    }
+
+    #[test]
+    fn expected_return() {
+        let probs = SettlementProbs {
+            win: 0.4,
+            half_win: 0.2,
+            push: 0.0,
+            half_loss: 0.0,
+            loss: 0.4,
+        };
+        let fair_price = probs.fair_price();
+        assert_float_absolute_eq!(1.0, probs.expected_return(fair_price));
+        assert_float_absolute_eq!(0.9 / 0.5, fair_price);
+        assert_float_absolute_eq!(0.5 / 0.9, probs.effective_probability());
+        assert!(probs.expected_return(fair_price * 1.1) > 1.0);
+
+        // a binary outcome reduces to its probability
+        let probs = SettlementProbs {
+            win: 0.4,
+            loss: 0.6,
+            ..SettlementProbs::default()
+        };
+        assert_float_absolute_eq!(2.5, probs.fair_price());
+        assert_float_absolute_eq!(0.4, probs.effective_probability());
+    }
+}
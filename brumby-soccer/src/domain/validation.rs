@@ -10,10 +10,25 @@ use brumby::probs::SliceExt;
 use crate::domain::{Offer, OfferType, Outcome};
 
 mod asian_handicap;
+mod cast;
+mod double_chance;
 mod draw_no_bet;
+mod exact_goals;
+mod half_time_full_time;
 mod head_to_head;
+mod highest_scoring_half;
+mod method_of_victory;
+mod odd_even;
+mod player_events;
+mod player_to_score;
+mod scoring_team;
 mod split_handicap;
+mod split_total_goals;
+mod time_band;
+mod to_qualify;
 mod total_goals;
+mod winning_margin;
+mod yes_no;
 
 #[derive(Debug, Error)]
 pub enum InvalidOffer {
@@ -44,11 +59,23 @@ impl Offer {
         self.offer_type.validate()?;
         self.offer_type.validate_outcomes(&self.outcomes)?;
         match self.offer_type {
-            OfferType::TotalGoals(_, _) => total_goals::validate_probs(&self.offer_type, &self.market.probs),
+            OfferType::TotalGoals(_, _) | OfferType::TeamTotalGoals(_, _, _) | OfferType::TotalEvents(_, _) | OfferType::TeamTotalEvents(_, _, _) => total_goals::validate_probs(&self.offer_type, &self.market.probs),
             OfferType::HeadToHead(_, _) => head_to_head::validate_probs(&self.offer_type, &self.market.probs),
-            OfferType::AsianHandicap(_, _) => asian_handicap::validate_probs(&self.offer_type, &self.market.probs),
+            OfferType::AsianHandicap(_, _) | OfferType::EventHandicap(_, _) => asian_handicap::validate_probs(&self.offer_type, &self.market.probs),
             OfferType::DrawNoBet(_) => draw_no_bet::validate_probs(&self.offer_type, &self.market.probs),
             OfferType::SplitHandicap(_, _, _) => split_handicap::validate_probs(&self.offer_type, &self.market.probs),
+            OfferType::SplitTotalGoals(_, _, _) => split_total_goals::validate_probs(&self.offer_type, &self.market.probs),
+            OfferType::HalfTimeFullTime => half_time_full_time::validate_probs(&self.offer_type, &self.market.probs),
+            OfferType::ExactTeamGoals(_, _, _) | OfferType::ExactTotalGoals(_, _) => exact_goals::validate_probs(&self.offer_type, &self.market.probs),
+            OfferType::OddEvenGoals(_) => odd_even::validate_probs(&self.offer_type, &self.market.probs),
+            OfferType::WinningMargin(_) => winning_margin::validate_probs(&self.offer_type, &self.market.probs),
+            OfferType::DoubleChance(_) => double_chance::validate_probs(&self.offer_type, &self.market.probs),
+            OfferType::ToQualify => to_qualify::validate_probs(&self.offer_type, &self.market.probs),
+            OfferType::MethodOfVictory => method_of_victory::validate_probs(&self.offer_type, &self.market.probs),
+            OfferType::TimeOfFirstGoal => time_band::validate_probs(&self.offer_type, &self.market.probs),
+            OfferType::GoalInTimeBand(_) | OfferType::GoalInBothHalves => yes_no::validate_probs(&self.offer_type, &self.market.probs),
+            OfferType::HighestScoringHalf => highest_scoring_half::validate_probs(&self.offer_type, &self.market.probs),
+            OfferType::FirstTeamToScore | OfferType::LastTeamToScore | OfferType::RaceToGoals(_) => scoring_team::validate_probs(&self.offer_type, &self.market.probs),
             _ => Ok(()),
         }
     }
@@ -100,30 +127,62 @@ impl OfferType {
         match self {
             OfferType::HeadToHead(_, draw_handicap) => head_to_head::validate_type(self, draw_handicap),
             OfferType::DrawNoBet(draw_handicap) => draw_no_bet::validate_type(self, draw_handicap),
-            OfferType::AsianHandicap(_, win_handicap) => asian_handicap::validate_type(self, win_handicap),
+            OfferType::AsianHandicap(_, win_handicap) | OfferType::EventHandicap(_, win_handicap) => asian_handicap::validate_type(self, win_handicap),
             OfferType::SplitHandicap(_, draw_handicap, win_handicap) => split_handicap::validate_type(self, draw_handicap, win_handicap),
+            OfferType::SplitTotalGoals(_, whole, over) => split_total_goals::validate_type(self, *whole, over),
+            OfferType::PlayerToScore(_) | OfferType::PlayerToScoreInHalf(_) | OfferType::PlayerToScoreMultipleInHalf(_, _) => player_to_score::validate_type(self),
+            OfferType::GoalInTimeBand(time_band) => time_band::validate_type(self, time_band),
+            OfferType::RaceToGoals(goals) => scoring_team::validate_type(self, *goals),
             _ => Ok(()),
         }
     }
 
     pub fn validate_outcomes(&self, outcomes: &HashLookup<Outcome>) -> Result<(), InvalidOutcome> {
         match self {
-            OfferType::TotalGoals(_, over) => total_goals::validate_outcomes(self, outcomes, over),
+            OfferType::TotalGoals(_, over) | OfferType::TeamTotalGoals(_, _, over) | OfferType::TotalEvents(_, over) | OfferType::TeamTotalEvents(_, _, over) => total_goals::validate_outcomes(self, outcomes, over),
             OfferType::HeadToHead(_, draw_handicap) => head_to_head::validate_outcomes(self, outcomes, draw_handicap),
-            OfferType::AsianHandicap(_, win_handicap) => asian_handicap::validate_outcomes(self, outcomes, win_handicap),
+            OfferType::AsianHandicap(_, win_handicap) | OfferType::EventHandicap(_, win_handicap) => asian_handicap::validate_outcomes(self, outcomes, win_handicap),
             OfferType::DrawNoBet(draw_handicap) => draw_no_bet::validate_outcomes(self, outcomes, draw_handicap),
             OfferType::SplitHandicap(_, draw_handicap, win_handicap) => split_handicap::validate_outcomes(self, outcomes, draw_handicap, win_handicap),
+            OfferType::SplitTotalGoals(_, whole, over) => split_total_goals::validate_outcomes(self, outcomes, *whole, over),
+            OfferType::HalfTimeFullTime => half_time_full_time::validate_outcomes(self, outcomes),
+            OfferType::ExactTeamGoals(_, _, over) | OfferType::ExactTotalGoals(_, over) => exact_goals::validate_outcomes(self, outcomes, over),
+            OfferType::OddEvenGoals(_) => odd_even::validate_outcomes(self, outcomes),
+            OfferType::WinningMargin(_) => winning_margin::validate_outcomes(self, outcomes),
+            OfferType::DoubleChance(_) => double_chance::validate_outcomes(self, outcomes),
+            OfferType::ToQualify => to_qualify::validate_outcomes(self, outcomes),
+            OfferType::MethodOfVictory => method_of_victory::validate_outcomes(self, outcomes),
+            OfferType::TimeOfFirstGoal => time_band::validate_outcomes(self, outcomes),
+            OfferType::GoalInTimeBand(_) | OfferType::GoalInBothHalves => yes_no::validate_outcomes(self, outcomes),
+            OfferType::HighestScoringHalf => highest_scoring_half::validate_outcomes(self, outcomes),
+            OfferType::FirstTeamToScore | OfferType::LastTeamToScore | OfferType::RaceToGoals(_) => scoring_team::validate_outcomes(self, outcomes),
+            OfferType::Scorecast | OfferType::Wincast => cast::validate_outcomes(self, outcomes),
+            OfferType::PlayerEvents(_, _) => player_events::validate_outcomes(self, outcomes),
             _ => Ok(()),
         }
     }
 
     pub fn validate_outcome(&self, outcome: &Outcome) -> Result<(), InvalidOutcome> {
         match self {
-            OfferType::TotalGoals(_, over) => total_goals::validate_outcome(self, outcome, over),
+            OfferType::TotalGoals(_, over) | OfferType::TeamTotalGoals(_, _, over) | OfferType::TotalEvents(_, over) | OfferType::TeamTotalEvents(_, _, over) => total_goals::validate_outcome(self, outcome, over),
             OfferType::HeadToHead(_, draw_handicap) => head_to_head::validate_outcome(self, outcome, draw_handicap),
-            OfferType::AsianHandicap(_, win_handicap) => asian_handicap::validate_outcome(self, outcome, win_handicap),
+            OfferType::AsianHandicap(_, win_handicap) | OfferType::EventHandicap(_, win_handicap) => asian_handicap::validate_outcome(self, outcome, win_handicap),
             OfferType::DrawNoBet(draw_handicap) => draw_no_bet::validate_outcome(self, outcome, draw_handicap),
             OfferType::SplitHandicap(_, draw_handicap, win_handicap) => split_handicap::validate_outcome(self, outcome, draw_handicap, win_handicap),
+            OfferType::SplitTotalGoals(_, whole, over) => split_total_goals::validate_outcome(self, outcome, *whole, over),
+            OfferType::HalfTimeFullTime => half_time_full_time::validate_outcome(self, outcome),
+            OfferType::ExactTeamGoals(_, _, over) | OfferType::ExactTotalGoals(_, over) => exact_goals::validate_outcome(self, outcome, over),
+            OfferType::OddEvenGoals(_) => odd_even::validate_outcome(self, outcome),
+            OfferType::WinningMargin(_) => winning_margin::validate_outcome(self, outcome),
+            OfferType::DoubleChance(_) => double_chance::validate_outcome(self, outcome),
+            OfferType::ToQualify => to_qualify::validate_outcome(self, outcome),
+            OfferType::MethodOfVictory => method_of_victory::validate_outcome(self, outcome),
+            OfferType::TimeOfFirstGoal => time_band::validate_outcome(self, outcome),
+            OfferType::GoalInTimeBand(_) | OfferType::GoalInBothHalves => yes_no::validate_outcome(self, outcome),
+            OfferType::HighestScoringHalf => highest_scoring_half::validate_outcome(self, outcome),
+            OfferType::FirstTeamToScore | OfferType::LastTeamToScore | OfferType::RaceToGoals(_) => scoring_team::validate_outcome(self, outcome),
+            OfferType::Scorecast | OfferType::Wincast => cast::validate_outcome(self, outcome),
+            OfferType::PlayerEvents(_, _) => player_events::validate_outcome(self, outcome),
             _ => Ok(()),
         }
     }
@@ -0,0 +1,115 @@
+use brumby::hash_lookup::HashLookup;
+
+use crate::domain::validation::{ExtraneousOutcome, InvalidOutcome};
+use crate::domain::{validation, OfferType, Outcome, Player, Side};
+
+pub(crate) fn validate_outcomes(
+    offer_type: &OfferType,
+    outcomes: &HashLookup<Outcome>,
+) -> Result<(), InvalidOutcome> {
+    validation::OutcomesMatchAssertion {
+        matcher: |outcome| is_valid(offer_type, outcome),
+    }
+    .check(outcomes.items(), offer_type)?;
+    Ok(())
+}
+
+pub(crate) fn validate_outcome(
+    offer_type: &OfferType,
+    outcome: &Outcome,
+) -> Result<(), InvalidOutcome> {
+    if is_valid(offer_type, outcome) {
+        Ok(())
+    } else {
+        Err(InvalidOutcome::ExtraneousOutcome(ExtraneousOutcome {
+            outcome: outcome.clone(),
+            offer_type: offer_type.clone(),
+        }))
+    }
+}
+
+/// A scorecast must leave at least one goal to the first goalscorer's side.
+fn is_valid(offer_type: &OfferType, outcome: &Outcome) -> bool {
+    match (offer_type, outcome) {
+        (OfferType::Scorecast, Outcome::Scorecast(player, score)) => match player {
+            Player::Named(Side::Home, _) => score.home > 0,
+            Player::Named(Side::Away, _) => score.away > 0,
+            Player::Other => score.total() > 0,
+        },
+        (OfferType::Wincast, Outcome::Wincast(_, _)) => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::RangeInclusive;
+
+    use brumby::hash_lookup::HashLookup;
+    use brumby::market::{Market, Overround};
+
+    use crate::domain::{MatchResult, Offer, Score};
+
+    use super::*;
+
+    const PRICE_BOUNDS: RangeInclusive<f64> = 1.0..=1001.0;
+
+    fn create_offer(offer_type: OfferType, outcomes: Vec<Outcome>) -> Offer {
+        let probs = vec![0.1; outcomes.len()];
+        Offer {
+            offer_type,
+            outcomes: HashLookup::from(outcomes),
+            market: Market::frame(&Overround::fair(), probs, &PRICE_BOUNDS),
+        }
+    }
+
+    #[test]
+    fn valid() {
+        let markos = Player::Named(Side::Home, "Markos".into());
+        create_offer(
+            OfferType::Scorecast,
+            vec![
+                Outcome::Scorecast(markos.clone(), Score::new(1, 0)),
+                Outcome::Scorecast(markos.clone(), Score::new(1, 2)),
+                Outcome::Scorecast(Player::Other, Score::new(0, 1)),
+            ],
+        )
+        .validate()
+        .unwrap();
+        create_offer(
+            OfferType::Wincast,
+            vec![
+                Outcome::Wincast(markos.clone(), MatchResult::Win(Side::Home)),
+                Outcome::Wincast(markos, MatchResult::Draw),
+            ],
+        )
+        .validate()
+        .unwrap();
+    }
+
+    #[test]
+    fn extraneous_outcome() {
+        let nikos = Player::Named(Side::Away, "Nikos".into());
+        assert_eq!(
+            "Scorecast(Named(Away, \"Nikos\"), Score { home: 1, away: 0 }) does not belong in Scorecast",
+            create_offer(OfferType::Scorecast, vec![Outcome::Scorecast(nikos.clone(), Score::new(1, 0))])
+                .validate()
+                .unwrap_err()
+                .to_string()
+        );
+        assert_eq!(
+            "Scorecast(Other, Score { home: 0, away: 0 }) does not belong in Scorecast",
+            create_offer(OfferType::Scorecast, vec![Outcome::Scorecast(Player::Other, Score::nil_all())])
+                .validate()
+                .unwrap_err()
+                .to_string()
+        );
+        assert_eq!(
+            "Player(Named(Away, \"Nikos\")) does not belong in Wincast",
+            create_offer(OfferType::Wincast, vec![Outcome::Player(nikos)])
+                .validate()
+                .unwrap_err()
+                .to_string()
+        );
+    }
+}
@@ -0,0 +1,128 @@
+use brumby::hash_lookup::HashLookup;
+
+use crate::domain::validation::{ExtraneousOutcome, InvalidOffer, InvalidOutcome};
+use crate::domain::{validation, OfferType, Outcome, Over};
+
+pub(crate) fn validate_outcomes(
+    offer_type: &OfferType,
+    outcomes: &HashLookup<Outcome>,
+    over: &Over,
+) -> Result<(), InvalidOutcome> {
+    validation::OutcomesCompleteAssertion {
+        outcomes: &valid_outcomes(over),
+    }
+    .check(outcomes, offer_type)?;
+    Ok(())
+}
+
+pub(crate) fn validate_outcome(
+    offer_type: &OfferType,
+    outcome: &Outcome,
+    over: &Over,
+) -> Result<(), InvalidOutcome> {
+    let valid_outcomes = valid_outcomes(over);
+    if valid_outcomes.contains(outcome) {
+        Ok(())
+    } else {
+        Err(InvalidOutcome::ExtraneousOutcome(ExtraneousOutcome {
+            outcome: outcome.clone(),
+            offer_type: offer_type.clone(),
+        }))
+    }
+}
+
+pub(crate) fn validate_probs(offer_type: &OfferType, probs: &[f64]) -> Result<(), InvalidOffer> {
+    validation::BooksumAssertion::with_default_tolerance(1.0..=1.0).check(probs, offer_type)?;
+    Ok(())
+}
+
+fn valid_outcomes(over: &Over) -> Vec<Outcome> {
+    (0..=over.0)
+        .map(Outcome::Exactly)
+        .chain(std::iter::once(Outcome::Over(over.0)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::RangeInclusive;
+
+    use brumby::hash_lookup::HashLookup;
+    use brumby::market::{Market, Overround};
+
+    use crate::domain::{Offer, Over, Period, Side};
+
+    use super::*;
+
+    const OFFER_TYPE: OfferType = OfferType::ExactTeamGoals(Period::FullTime, Side::Home, Over(2));
+    const PRICE_BOUNDS: RangeInclusive<f64> = 1.0..=1001.0;
+
+    #[test]
+    fn valid() {
+        let offer = Offer {
+            offer_type: OFFER_TYPE,
+            outcomes: HashLookup::from(vec![
+                Outcome::Exactly(0),
+                Outcome::Exactly(1),
+                Outcome::Exactly(2),
+                Outcome::Over(2),
+            ]),
+            market: Market::frame(&Overround::fair(), vec![0.3, 0.3, 0.2, 0.2], &PRICE_BOUNDS),
+        };
+        offer.validate().unwrap();
+    }
+
+    #[test]
+    fn wrong_booksum() {
+        let offer = Offer {
+            offer_type: OFFER_TYPE,
+            outcomes: HashLookup::from(vec![
+                Outcome::Exactly(0),
+                Outcome::Exactly(1),
+                Outcome::Exactly(2),
+                Outcome::Over(2),
+            ]),
+            market: Market::frame(&Overround::fair(), vec![0.5, 0.4, 0.0, 0.0], &PRICE_BOUNDS),
+        };
+        assert_eq!(
+            "expected booksum in 1.0..=1.0 ± 0.001, got 0.9 for ExactTeamGoals(FullTime, Home, Over(2))",
+            offer.validate().unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn missing_outcome() {
+        let offer = Offer {
+            offer_type: OFFER_TYPE,
+            outcomes: HashLookup::from(vec![
+                Outcome::Exactly(0),
+                Outcome::Exactly(1),
+                Outcome::Exactly(2),
+            ]),
+            market: Market::frame(&Overround::fair(), vec![0.4, 0.3, 0.3], &PRICE_BOUNDS),
+        };
+        assert_eq!(
+            "Over(2) missing from ExactTeamGoals(FullTime, Home, Over(2))",
+            offer.validate().unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn extraneous_outcome() {
+        let offer = Offer {
+            offer_type: OFFER_TYPE,
+            outcomes: HashLookup::from(vec![
+                Outcome::Exactly(0),
+                Outcome::Exactly(1),
+                Outcome::Exactly(2),
+                Outcome::Over(2),
+                Outcome::Exactly(3),
+            ]),
+            market: Market::frame(&Overround::fair(), vec![0.3, 0.3, 0.2, 0.1, 0.1], &PRICE_BOUNDS),
+        };
+        assert_eq!(
+            "Exactly(3) does not belong in ExactTeamGoals(FullTime, Home, Over(2))",
+            offer.validate().unwrap_err().to_string()
+        );
+    }
+}
@@ -0,0 +1,114 @@
+use brumby::hash_lookup::HashLookup;
+
+use crate::domain::validation::{ExtraneousOutcome, InvalidOffer, InvalidOutcome};
+use crate::domain::{validation, MatchResult, OfferType, Outcome};
+
+pub(crate) fn validate_outcomes(
+    offer_type: &OfferType,
+    outcomes: &HashLookup<Outcome>,
+) -> Result<(), InvalidOutcome> {
+    validation::OutcomesCompleteAssertion {
+        outcomes: &valid_outcomes(),
+    }
+    .check(outcomes, offer_type)?;
+    Ok(())
+}
+
+pub(crate) fn validate_outcome(
+    offer_type: &OfferType,
+    outcome: &Outcome,
+) -> Result<(), InvalidOutcome> {
+    let valid_outcomes = valid_outcomes();
+    if valid_outcomes.contains(outcome) {
+        Ok(())
+    } else {
+        Err(InvalidOutcome::ExtraneousOutcome(ExtraneousOutcome {
+            outcome: outcome.clone(),
+            offer_type: offer_type.clone(),
+        }))
+    }
+}
+
+pub(crate) fn validate_probs(offer_type: &OfferType, probs: &[f64]) -> Result<(), InvalidOffer> {
+    validation::BooksumAssertion::with_default_tolerance(1.0..=1.0).check(probs, offer_type)?;
+    Ok(())
+}
+
+fn valid_outcomes() -> Vec<Outcome> {
+    MatchResult::ALL
+        .iter()
+        .flat_map(|ht_result| {
+            MatchResult::ALL
+                .iter()
+                .map(|ft_result| Outcome::HalfTimeFullTime(ht_result.clone(), ft_result.clone()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::RangeInclusive;
+
+    use brumby::hash_lookup::HashLookup;
+    use brumby::market::{Market, Overround};
+
+    use crate::domain::{Offer, Side, WinHandicap};
+
+    use super::*;
+
+    const OFFER_TYPE: OfferType = OfferType::HalfTimeFullTime;
+    const PRICE_BOUNDS: RangeInclusive<f64> = 1.0..=1001.0;
+
+    #[test]
+    fn valid() {
+        let offer = Offer {
+            offer_type: OFFER_TYPE,
+            outcomes: HashLookup::from(valid_outcomes()),
+            market: Market::frame(&Overround::fair(), vec![1.0 / 9.0; 9], &PRICE_BOUNDS),
+        };
+        offer.validate().unwrap();
+    }
+
+    #[test]
+    fn wrong_booksum() {
+        let offer = Offer {
+            offer_type: OFFER_TYPE,
+            outcomes: HashLookup::from(valid_outcomes()),
+            market: Market::frame(&Overround::fair(), vec![0.5, 0.4, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0], &PRICE_BOUNDS),
+        };
+        assert_eq!(
+            "expected booksum in 1.0..=1.0 ± 0.001, got 0.9 for HalfTimeFullTime",
+            offer.validate().unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn missing_outcome() {
+        let mut outcomes = valid_outcomes();
+        outcomes.pop();
+        let offer = Offer {
+            offer_type: OFFER_TYPE,
+            outcomes: HashLookup::from(outcomes),
+            market: Market::frame(&Overround::fair(), vec![0.125; 8], &PRICE_BOUNDS),
+        };
+        assert_eq!(
+            "HalfTimeFullTime(Win(Away), Win(Away)) missing from HalfTimeFullTime",
+            offer.validate().unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn extraneous_outcome() {
+        let mut outcomes = valid_outcomes();
+        outcomes.push(Outcome::Win(Side::Home, WinHandicap::AheadOver(0)));
+        let offer = Offer {
+            offer_type: OFFER_TYPE,
+            outcomes: HashLookup::from(outcomes),
+            market: Market::frame(&Overround::fair(), vec![0.1; 10], &PRICE_BOUNDS),
+        };
+        assert_eq!(
+            "Win(Home, AheadOver(0)) does not belong in HalfTimeFullTime",
+            offer.validate().unwrap_err().to_string()
+        );
+    }
+}
@@ -0,0 +1,112 @@
+use brumby::hash_lookup::HashLookup;
+
+use crate::domain::validation::{ExtraneousOutcome, InvalidOffer, InvalidOutcome};
+use crate::domain::{validation, OfferType, Outcome, Side, VictoryMethod};
+
+pub(crate) fn validate_outcomes(
+    offer_type: &OfferType,
+    outcomes: &HashLookup<Outcome>,
+) -> Result<(), InvalidOutcome> {
+    validation::OutcomesCompleteAssertion {
+        outcomes: &valid_outcomes(),
+    }
+    .check(outcomes, offer_type)?;
+    Ok(())
+}
+
+pub(crate) fn validate_outcome(
+    offer_type: &OfferType,
+    outcome: &Outcome,
+) -> Result<(), InvalidOutcome> {
+    let valid_outcomes = valid_outcomes();
+    if valid_outcomes.contains(outcome) {
+        Ok(())
+    } else {
+        Err(InvalidOutcome::ExtraneousOutcome(ExtraneousOutcome {
+            outcome: outcome.clone(),
+            offer_type: offer_type.clone(),
+        }))
+    }
+}
+
+pub(crate) fn validate_probs(offer_type: &OfferType, probs: &[f64]) -> Result<(), InvalidOffer> {
+    validation::BooksumAssertion::with_default_tolerance(1.0..=1.0).check(probs, offer_type)?;
+    Ok(())
+}
+
+fn valid_outcomes() -> [Outcome; 6] {
+    [
+        Outcome::Victory(Side::Home, VictoryMethod::RegularTime),
+        Outcome::Victory(Side::Home, VictoryMethod::ExtraTime),
+        Outcome::Victory(Side::Home, VictoryMethod::Penalties),
+        Outcome::Victory(Side::Away, VictoryMethod::RegularTime),
+        Outcome::Victory(Side::Away, VictoryMethod::ExtraTime),
+        Outcome::Victory(Side::Away, VictoryMethod::Penalties),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::RangeInclusive;
+
+    use brumby::hash_lookup::HashLookup;
+    use brumby::market::{Market, Overround};
+
+    use crate::domain::Offer;
+
+    use super::*;
+
+    const OFFER_TYPE: OfferType = OfferType::MethodOfVictory;
+    const PRICE_BOUNDS: RangeInclusive<f64> = 1.0..=1001.0;
+
+    #[test]
+    fn valid() {
+        let offer = Offer {
+            offer_type: OFFER_TYPE,
+            outcomes: HashLookup::from(valid_outcomes()),
+            market: Market::frame(&Overround::fair(), vec![0.4, 0.1, 0.05, 0.3, 0.1, 0.05], &PRICE_BOUNDS),
+        };
+        offer.validate().unwrap();
+    }
+
+    #[test]
+    fn wrong_booksum() {
+        let offer = Offer {
+            offer_type: OFFER_TYPE,
+            outcomes: HashLookup::from(valid_outcomes()),
+            market: Market::frame(&Overround::fair(), vec![0.4, 0.1, 0.05, 0.3, 0.1, 0.15], &PRICE_BOUNDS),
+        };
+        assert_eq!(
+            "expected booksum in 1.0..=1.0 ± 0.001, got 1.1 for MethodOfVictory",
+            offer.validate().unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn missing_outcome() {
+        let offer = Offer {
+            offer_type: OFFER_TYPE,
+            outcomes: HashLookup::from(valid_outcomes()[..5].to_vec()),
+            market: Market::frame(&Overround::fair(), vec![0.4, 0.1, 0.1, 0.3, 0.1], &PRICE_BOUNDS),
+        };
+        assert_eq!(
+            "Victory(Away, Penalties) missing from MethodOfVictory",
+            offer.validate().unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn extraneous_outcome() {
+        let mut outcomes = valid_outcomes().to_vec();
+        outcomes.push(Outcome::Qualify(Side::Home));
+        let offer = Offer {
+            offer_type: OFFER_TYPE,
+            outcomes: HashLookup::from(outcomes),
+            market: Market::frame(&Overround::fair(), vec![0.4, 0.1, 0.05, 0.3, 0.1, 0.05, 0.0], &PRICE_BOUNDS),
+        };
+        assert_eq!(
+            "Qualify(Home) does not belong in MethodOfVictory",
+            offer.validate().unwrap_err().to_string()
+        );
+    }
+}
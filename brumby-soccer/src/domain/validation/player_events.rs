@@ -0,0 +1,86 @@
+use brumby::hash_lookup::HashLookup;
+
+use crate::domain::validation::{ExtraneousOutcome, InvalidOutcome};
+use crate::domain::{validation, OfferType, Outcome, Player};
+
+pub(crate) fn validate_outcomes(
+    offer_type: &OfferType,
+    outcomes: &HashLookup<Outcome>,
+) -> Result<(), InvalidOutcome> {
+    validation::OutcomesMatchAssertion {
+        matcher: is_valid,
+    }
+    .check(outcomes.items(), offer_type)?;
+    Ok(())
+}
+
+pub(crate) fn validate_outcome(
+    offer_type: &OfferType,
+    outcome: &Outcome,
+) -> Result<(), InvalidOutcome> {
+    if is_valid(outcome) {
+        Ok(())
+    } else {
+        Err(InvalidOutcome::ExtraneousOutcome(ExtraneousOutcome {
+            outcome: outcome.clone(),
+            offer_type: offer_type.clone(),
+        }))
+    }
+}
+
+/// Player event offers are priced off each player's share of their side's events, so only named
+/// players are admitted.
+fn is_valid(outcome: &Outcome) -> bool {
+    matches!(outcome, Outcome::Player(Player::Named(_, _)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::RangeInclusive;
+
+    use brumby::hash_lookup::HashLookup;
+    use brumby::market::{Market, Overround};
+
+    use crate::domain::{EventStream, Offer, Over, Side};
+
+    use super::*;
+
+    const PRICE_BOUNDS: RangeInclusive<f64> = 1.0..=1001.0;
+
+    fn create_offer(outcomes: Vec<Outcome>) -> Offer {
+        let probs = vec![0.1; outcomes.len()];
+        Offer {
+            offer_type: OfferType::PlayerEvents(EventStream::Bookings, Over(5)),
+            outcomes: HashLookup::from(outcomes),
+            market: Market::frame(&Overround::fair(), probs, &PRICE_BOUNDS),
+        }
+    }
+
+    #[test]
+    fn valid() {
+        create_offer(vec![
+            Outcome::Player(Player::Named(Side::Home, "Markos".into())),
+            Outcome::Player(Player::Named(Side::Away, "Nikos".into())),
+        ])
+        .validate()
+        .unwrap();
+    }
+
+    #[test]
+    fn extraneous_outcome() {
+        assert_eq!(
+            "Player(Other) does not belong in PlayerEvents(Bookings, Over(5))",
+            create_offer(vec![Outcome::Player(Player::Other)])
+                .validate()
+                .unwrap_err()
+                .to_string()
+        );
+        assert_eq!(
+            "Over(5) does not belong in PlayerEvents(Bookings, Over(5))",
+            create_offer(vec![Outcome::Over(5)])
+                .validate()
+                .unwrap_err()
+                .to_string()
+        );
+    }
+}
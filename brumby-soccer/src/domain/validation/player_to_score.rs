@@ -0,0 +1,70 @@
+use crate::domain::{OfferType, Period};
+use crate::domain::validation::InvalidOfferType;
+
+pub(crate) fn validate_type(offer_type: &OfferType) -> Result<(), InvalidOfferType> {
+    match offer_type {
+        OfferType::PlayerToScore(0)
+        | OfferType::PlayerToScoreInHalf(Period::FullTime)
+        | OfferType::PlayerToScoreMultipleInHalf(Period::FullTime, _)
+        | OfferType::PlayerToScoreMultipleInHalf(_, 0) => {
+            Err(InvalidOfferType {
+                offer_type: offer_type.clone(),
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::RangeInclusive;
+
+    use brumby::hash_lookup::HashLookup;
+    use brumby::market::{Market, Overround};
+
+    use crate::domain::{Offer, Outcome, Player, Side};
+
+    use super::*;
+
+    const PRICE_BOUNDS: RangeInclusive<f64> = 1.0..=1001.0;
+
+    fn create_offer(offer_type: OfferType) -> Offer {
+        Offer {
+            offer_type,
+            outcomes: HashLookup::from(vec![
+                Outcome::Player(Player::Named(Side::Home, "Markos".into())),
+                Outcome::Player(Player::Named(Side::Away, "Nikos".into())),
+            ]),
+            market: Market::frame(&Overround::fair(), vec![0.2, 0.1], &PRICE_BOUNDS),
+        }
+    }
+
+    #[test]
+    fn valid() {
+        create_offer(OfferType::PlayerToScore(2)).validate().unwrap();
+        create_offer(OfferType::PlayerToScoreInHalf(Period::FirstHalf)).validate().unwrap();
+        create_offer(OfferType::PlayerToScoreInHalf(Period::SecondHalf)).validate().unwrap();
+        create_offer(OfferType::PlayerToScoreInBothHalves).validate().unwrap();
+        create_offer(OfferType::PlayerToScoreMultipleInHalf(Period::SecondHalf, 2)).validate().unwrap();
+    }
+
+    #[test]
+    fn invalid_type() {
+        assert_eq!(
+            "PlayerToScore(0) is not a valid offer type",
+            create_offer(OfferType::PlayerToScore(0)).validate().unwrap_err().to_string()
+        );
+        assert_eq!(
+            "PlayerToScoreInHalf(FullTime) is not a valid offer type",
+            create_offer(OfferType::PlayerToScoreInHalf(Period::FullTime)).validate().unwrap_err().to_string()
+        );
+        assert_eq!(
+            "PlayerToScoreMultipleInHalf(FullTime, 2) is not a valid offer type",
+            create_offer(OfferType::PlayerToScoreMultipleInHalf(Period::FullTime, 2)).validate().unwrap_err().to_string()
+        );
+        assert_eq!(
+            "PlayerToScoreMultipleInHalf(FirstHalf, 0) is not a valid offer type",
+            create_offer(OfferType::PlayerToScoreMultipleInHalf(Period::FirstHalf, 0)).validate().unwrap_err().to_string()
+        );
+    }
+}
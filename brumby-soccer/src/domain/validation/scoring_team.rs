@@ -0,0 +1,130 @@
+//! Offers on the side that scores first, last, or first to a number of goals, as per the
+//! `FirstTeamToScore`, `LastTeamToScore` and `RaceToGoals` offer types.
+
+use brumby::hash_lookup::HashLookup;
+
+use crate::domain::validation::{ExtraneousOutcome, InvalidOffer, InvalidOfferType, InvalidOutcome};
+use crate::domain::{validation, OfferType, Outcome, Side};
+
+pub(crate) fn validate_outcomes(
+    offer_type: &OfferType,
+    outcomes: &HashLookup<Outcome>,
+) -> Result<(), InvalidOutcome> {
+    validation::OutcomesCompleteAssertion {
+        outcomes: &valid_outcomes(),
+    }
+    .check(outcomes, offer_type)?;
+    Ok(())
+}
+
+pub(crate) fn validate_outcome(
+    offer_type: &OfferType,
+    outcome: &Outcome,
+) -> Result<(), InvalidOutcome> {
+    let valid_outcomes = valid_outcomes();
+    if valid_outcomes.contains(outcome) {
+        Ok(())
+    } else {
+        Err(InvalidOutcome::ExtraneousOutcome(ExtraneousOutcome {
+            outcome: outcome.clone(),
+            offer_type: offer_type.clone(),
+        }))
+    }
+}
+
+pub(crate) fn validate_probs(offer_type: &OfferType, probs: &[f64]) -> Result<(), InvalidOffer> {
+    validation::BooksumAssertion::with_default_tolerance(1.0..=1.0).check(probs, offer_type)?;
+    Ok(())
+}
+
+pub(crate) fn validate_type(offer_type: &OfferType, race_goals: u8) -> Result<(), InvalidOfferType> {
+    if (1..=u16::BITS as u8).contains(&race_goals) {
+        Ok(())
+    } else {
+        Err(InvalidOfferType {
+            offer_type: offer_type.clone(),
+        })
+    }
+}
+
+fn valid_outcomes() -> [Outcome; 3] {
+    [
+        Outcome::Team(Side::Home),
+        Outcome::Team(Side::Away),
+        Outcome::None,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::RangeInclusive;
+
+    use brumby::hash_lookup::HashLookup;
+    use brumby::market::{Market, Overround};
+
+    use crate::domain::Offer;
+
+    use super::*;
+
+    const OFFER_TYPE: OfferType = OfferType::RaceToGoals(2);
+    const PRICE_BOUNDS: RangeInclusive<f64> = 1.0..=1001.0;
+
+    #[test]
+    fn valid() {
+        let offer = Offer {
+            offer_type: OFFER_TYPE,
+            outcomes: HashLookup::from(valid_outcomes()),
+            market: Market::frame(&Overround::fair(), vec![0.4, 0.3, 0.3], &PRICE_BOUNDS),
+        };
+        offer.validate().unwrap();
+    }
+
+    #[test]
+    fn wrong_booksum() {
+        let offer = Offer {
+            offer_type: OFFER_TYPE,
+            outcomes: HashLookup::from(valid_outcomes()),
+            market: Market::frame(&Overround::fair(), vec![0.5, 0.25, 0.15], &PRICE_BOUNDS),
+        };
+        assert_eq!(
+            "expected booksum in 1.0..=1.0 ± 0.001, got 0.9 for RaceToGoals(2)",
+            offer.validate().unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn missing_outcome() {
+        let offer = Offer {
+            offer_type: OfferType::FirstTeamToScore,
+            outcomes: HashLookup::from(valid_outcomes()[..2].to_vec()),
+            market: Market::frame(&Overround::fair(), vec![0.5, 0.5], &PRICE_BOUNDS),
+        };
+        assert_eq!(
+            "None missing from FirstTeamToScore",
+            offer.validate().unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn extraneous_outcome() {
+        let mut outcomes = valid_outcomes().to_vec();
+        outcomes.push(Outcome::Yes);
+        let offer = Offer {
+            offer_type: OfferType::LastTeamToScore,
+            outcomes: HashLookup::from(outcomes),
+            market: Market::frame(&Overround::fair(), vec![0.4, 0.3, 0.3, 0.0], &PRICE_BOUNDS),
+        };
+        assert_eq!(
+            "Yes does not belong in LastTeamToScore",
+            offer.validate().unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn invalid_type() {
+        assert_eq!(
+            "RaceToGoals(0) is not a valid offer type",
+            OfferType::RaceToGoals(0).validate().unwrap_err().to_string()
+        );
+    }
+}
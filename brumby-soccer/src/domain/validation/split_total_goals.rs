@@ -0,0 +1,166 @@
+use brumby::hash_lookup::HashLookup;
+
+use crate::domain::validation::{ExtraneousOutcome, InvalidOffer, InvalidOfferType, InvalidOutcome};
+use crate::domain::{validation, OfferType, Outcome, Over};
+
+pub(crate) fn validate_outcomes(
+    offer_type: &OfferType,
+    outcomes: &HashLookup<Outcome>,
+    whole: u8,
+    over: &Over,
+) -> Result<(), InvalidOutcome> {
+    validation::OutcomesCompleteAssertion {
+        outcomes: &valid_outcomes(whole, over),
+    }
+    .check(outcomes, offer_type)?;
+    Ok(())
+}
+
+pub(crate) fn validate_outcome(
+    offer_type: &OfferType,
+    outcome: &Outcome,
+    whole: u8,
+    over: &Over,
+) -> Result<(), InvalidOutcome> {
+    let valid_outcomes = valid_outcomes(whole, over);
+    if valid_outcomes.contains(outcome) {
+        Ok(())
+    } else {
+        Err(InvalidOutcome::ExtraneousOutcome(ExtraneousOutcome {
+            outcome: outcome.clone(),
+            offer_type: offer_type.clone(),
+        }))
+    }
+}
+
+pub(crate) fn validate_probs(offer_type: &OfferType, probs: &[f64]) -> Result<(), InvalidOffer> {
+    validation::BooksumAssertion::with_default_tolerance(1.0..=1.0).check(probs, offer_type)?;
+    Ok(())
+}
+
+pub(crate) fn validate_type(
+    offer_type: &OfferType,
+    whole: u8,
+    over: &Over,
+) -> Result<(), InvalidOfferType> {
+    // x.25 pairs the whole line with the half line above it; x.75 with the half line below it
+    if whole == over.0 || whole == over.0 + 1 {
+        Ok(())
+    } else {
+        Err(InvalidOfferType {
+            offer_type: offer_type.clone(),
+        })
+    }
+}
+
+fn valid_outcomes(whole: u8, over: &Over) -> [Outcome; 2] {
+    [
+        Outcome::SplitOver(whole, over.0),
+        Outcome::SplitUnder(whole, over.0 + 1),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::RangeInclusive;
+
+    use brumby::hash_lookup::HashLookup;
+    use brumby::market::{Market, Overround};
+
+    use crate::domain::{Offer, Period};
+
+    use super::*;
+
+    const PRICE_BOUNDS: RangeInclusive<f64> = 1.0..=1001.0;
+
+    #[test]
+    fn valid() {
+        {
+            // 2 with 2.5
+            let offer = Offer {
+                offer_type: OfferType::SplitTotalGoals(Period::FullTime, 2, Over(2)),
+                outcomes: HashLookup::from(vec![
+                    Outcome::SplitOver(2, 2),   // over 2.25
+                    Outcome::SplitUnder(2, 3),  // under 2.25
+                ]),
+                market: Market::frame(&Overround::fair(), vec![0.4, 0.6], &PRICE_BOUNDS),
+            };
+            offer.validate().unwrap();
+        }
+        {
+            // 3 with 2.5
+            let offer = Offer {
+                offer_type: OfferType::SplitTotalGoals(Period::FirstHalf, 3, Over(2)),
+                outcomes: HashLookup::from(vec![
+                    Outcome::SplitOver(3, 2),   // over 2.75
+                    Outcome::SplitUnder(3, 3),  // under 2.75
+                ]),
+                market: Market::frame(&Overround::fair(), vec![0.4, 0.6], &PRICE_BOUNDS),
+            };
+            offer.validate().unwrap();
+        }
+    }
+
+    #[test]
+    fn wrong_booksum() {
+        let offer = Offer {
+            offer_type: OfferType::SplitTotalGoals(Period::FullTime, 2, Over(2)),
+            outcomes: HashLookup::from(vec![
+                Outcome::SplitOver(2, 2),
+                Outcome::SplitUnder(2, 3),
+            ]),
+            market: Market::frame(&Overround::fair(), vec![0.4, 0.5], &PRICE_BOUNDS),
+        };
+        assert_eq!(
+            "expected booksum in 1.0..=1.0 ± 0.001, got 0.9 for SplitTotalGoals(FullTime, 2, Over(2))",
+            offer.validate().unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn missing_outcome() {
+        let offer = Offer {
+            offer_type: OfferType::SplitTotalGoals(Period::FullTime, 2, Over(2)),
+            outcomes: HashLookup::from(vec![Outcome::SplitOver(2, 2)]),
+            market: Market::frame(&Overround::fair(), vec![1.0], &PRICE_BOUNDS),
+        };
+        assert_eq!(
+            "SplitUnder(2, 3) missing from SplitTotalGoals(FullTime, 2, Over(2))",
+            offer.validate().unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn extraneous_outcome() {
+        let offer = Offer {
+            offer_type: OfferType::SplitTotalGoals(Period::FullTime, 2, Over(2)),
+            outcomes: HashLookup::from(vec![
+                Outcome::SplitOver(2, 2),
+                Outcome::SplitUnder(2, 3),
+                Outcome::Over(2),
+            ]),
+            market: Market::frame(&Overround::fair(), vec![0.4, 0.5, 0.1], &PRICE_BOUNDS),
+        };
+        assert_eq!(
+            "Over(2) does not belong in SplitTotalGoals(FullTime, 2, Over(2))",
+            offer.validate().unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn invalid_type() {
+        // 1 cannot be mixed with 2.5
+        let offer = Offer {
+            offer_type: OfferType::SplitTotalGoals(Period::FullTime, 1, Over(2)),
+            outcomes: HashLookup::from(vec![
+                Outcome::SplitOver(1, 2),
+                Outcome::SplitUnder(1, 3),
+            ]),
+            market: Market::frame(&Overround::fair(), vec![0.4, 0.6], &PRICE_BOUNDS),
+        };
+        assert_eq!(
+            "SplitTotalGoals(FullTime, 1, Over(2)) is not a valid offer type",
+            offer.validate().unwrap_err().to_string()
+        );
+    }
+}
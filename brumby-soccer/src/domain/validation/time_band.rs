@@ -0,0 +1,201 @@
+//! Time bands, as per the `GoalInTimeBand` and `TimeOfFirstGoal` offer types.
+
+use brumby::hash_lookup::HashLookup;
+
+use crate::domain::validation::{
+    ExtraneousOutcome, InvalidOffer, InvalidOfferType, InvalidOutcome, MissingOutcome,
+};
+use crate::domain::{validation, OfferType, Outcome, TimeBand};
+
+pub(crate) fn validate_type(
+    offer_type: &OfferType,
+    time_band: &TimeBand,
+) -> Result<(), InvalidOfferType> {
+    if is_valid(time_band) {
+        Ok(())
+    } else {
+        Err(InvalidOfferType {
+            offer_type: offer_type.clone(),
+        })
+    }
+}
+
+/// The time bands of the first goal must span regular time, without gaps or overlaps.
+pub(crate) fn validate_outcomes(
+    offer_type: &OfferType,
+    outcomes: &HashLookup<Outcome>,
+) -> Result<(), InvalidOutcome> {
+    let mut time_bands = Vec::with_capacity(outcomes.len());
+    for outcome in outcomes.items() {
+        validate_outcome(offer_type, outcome)?;
+        if let Outcome::FirstGoalIn(time_band) = outcome {
+            time_bands.push(time_band);
+        }
+    }
+    time_bands.sort();
+
+    let mut expected_from = 0;
+    for time_band in time_bands {
+        if time_band.from > expected_from {
+            return Err(missing(offer_type, TimeBand::new(expected_from, time_band.from)));
+        } else if time_band.from < expected_from {
+            return Err(InvalidOutcome::ExtraneousOutcome(ExtraneousOutcome {
+                outcome: Outcome::FirstGoalIn(time_band.clone()),
+                offer_type: offer_type.clone(),
+            }));
+        }
+        expected_from = time_band.to;
+    }
+    if expected_from < TimeBand::MAX_MINUTE {
+        return Err(missing(offer_type, TimeBand::new(expected_from, TimeBand::MAX_MINUTE)));
+    }
+
+    validation::OutcomesIntactAssertion {
+        outcomes: &[Outcome::None],
+    }
+    .check(outcomes, offer_type)?;
+    Ok(())
+}
+
+pub(crate) fn validate_outcome(
+    offer_type: &OfferType,
+    outcome: &Outcome,
+) -> Result<(), InvalidOutcome> {
+    match outcome {
+        Outcome::FirstGoalIn(time_band) if is_valid(time_band) => Ok(()),
+        Outcome::None => Ok(()),
+        _ => Err(InvalidOutcome::ExtraneousOutcome(ExtraneousOutcome {
+            outcome: outcome.clone(),
+            offer_type: offer_type.clone(),
+        })),
+    }
+}
+
+pub(crate) fn validate_probs(offer_type: &OfferType, probs: &[f64]) -> Result<(), InvalidOffer> {
+    validation::BooksumAssertion::with_default_tolerance(1.0..=1.0).check(probs, offer_type)?;
+    Ok(())
+}
+
+fn is_valid(time_band: &TimeBand) -> bool {
+    time_band.from < time_band.to && time_band.to <= TimeBand::MAX_MINUTE
+}
+
+fn missing(offer_type: &OfferType, time_band: TimeBand) -> InvalidOutcome {
+    InvalidOutcome::MissingOutcome(MissingOutcome {
+        outcome: Outcome::FirstGoalIn(time_band),
+        offer_type: offer_type.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::RangeInclusive;
+
+    use brumby::hash_lookup::HashLookup;
+    use brumby::market::{Market, Overround};
+
+    use crate::domain::Offer;
+
+    use super::*;
+
+    const OFFER_TYPE: OfferType = OfferType::TimeOfFirstGoal;
+    const PRICE_BOUNDS: RangeInclusive<f64> = 1.0..=1001.0;
+
+    fn create_test_outcomes(bands: &[(u8, u8)]) -> HashLookup<Outcome> {
+        let mut outcomes = bands
+            .iter()
+            .map(|(from, to)| Outcome::FirstGoalIn(TimeBand::new(*from, *to)))
+            .collect::<Vec<_>>();
+        outcomes.push(Outcome::None);
+        HashLookup::from(outcomes)
+    }
+
+    #[test]
+    fn valid() {
+        let offer = Offer {
+            offer_type: OFFER_TYPE,
+            outcomes: create_test_outcomes(&[(0, 15), (15, 45), (45, 90)]),
+            market: Market::frame(&Overround::fair(), vec![0.25, 0.35, 0.3, 0.1], &PRICE_BOUNDS),
+        };
+        offer.validate().unwrap();
+    }
+
+    #[test]
+    fn wrong_booksum() {
+        let offer = Offer {
+            offer_type: OFFER_TYPE,
+            outcomes: create_test_outcomes(&[(0, 45), (45, 90)]),
+            market: Market::frame(&Overround::fair(), vec![0.4, 0.4, 0.1], &PRICE_BOUNDS),
+        };
+        assert_eq!(
+            "expected booksum in 1.0..=1.0 ± 0.001, got 0.9 for TimeOfFirstGoal",
+            offer.validate().unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn missing_outcome() {
+        let offer = Offer {
+            offer_type: OFFER_TYPE,
+            outcomes: create_test_outcomes(&[(0, 15), (30, 90)]),
+            market: Market::frame(&Overround::fair(), vec![0.3, 0.6, 0.1], &PRICE_BOUNDS),
+        };
+        assert_eq!(
+            "FirstGoalIn(TimeBand { from: 15, to: 30 }) missing from TimeOfFirstGoal",
+            offer.validate().unwrap_err().to_string()
+        );
+
+        let offer = Offer {
+            offer_type: OFFER_TYPE,
+            outcomes: create_test_outcomes(&[(0, 45)]),
+            market: Market::frame(&Overround::fair(), vec![0.9, 0.1], &PRICE_BOUNDS),
+        };
+        assert_eq!(
+            "FirstGoalIn(TimeBand { from: 45, to: 90 }) missing from TimeOfFirstGoal",
+            offer.validate().unwrap_err().to_string()
+        );
+
+        let offer = Offer {
+            offer_type: OFFER_TYPE,
+            outcomes: HashLookup::from([Outcome::FirstGoalIn(TimeBand::new(0, 90))]),
+            market: Market::frame(&Overround::fair(), vec![1.0], &PRICE_BOUNDS),
+        };
+        assert_eq!(
+            "None missing from TimeOfFirstGoal",
+            offer.validate().unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn extraneous_outcome() {
+        let offer = Offer {
+            offer_type: OFFER_TYPE,
+            outcomes: create_test_outcomes(&[(0, 30), (15, 90)]),
+            market: Market::frame(&Overround::fair(), vec![0.3, 0.6, 0.1], &PRICE_BOUNDS),
+        };
+        assert_eq!(
+            "FirstGoalIn(TimeBand { from: 15, to: 90 }) does not belong in TimeOfFirstGoal",
+            offer.validate().unwrap_err().to_string()
+        );
+
+        let offer = Offer {
+            offer_type: OFFER_TYPE,
+            outcomes: create_test_outcomes(&[(0, 95)]),
+            market: Market::frame(&Overround::fair(), vec![0.9, 0.1], &PRICE_BOUNDS),
+        };
+        assert_eq!(
+            "FirstGoalIn(TimeBand { from: 0, to: 95 }) does not belong in TimeOfFirstGoal",
+            offer.validate().unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn invalid_type() {
+        let offer_type = OfferType::GoalInTimeBand(TimeBand::new(30, 30));
+        assert_eq!(
+            "GoalInTimeBand(TimeBand { from: 30, to: 30 }) is not a valid offer type",
+            offer_type.validate().unwrap_err().to_string()
+        );
+        OfferType::GoalInTimeBand(TimeBand::new(75, 90)).validate().unwrap();
+    }
+}
@@ -69,7 +69,10 @@ pub(crate) fn validate_outcome(
 
 pub(crate) fn validate_probs(offer_type: &OfferType, probs: &[f64]) -> Result<(), InvalidOffer> {
     match offer_type {
-        OfferType::TotalGoals(_, _) => {
+        OfferType::TotalGoals(_, _)
+        | OfferType::TeamTotalGoals(_, _, _)
+        | OfferType::TotalEvents(_, _)
+        | OfferType::TeamTotalEvents(_, _, _) => {
             validation::BooksumAssertion::with_default_tolerance(1.0..=1.0).check(probs, offer_type)?;
             Ok(())
         }
@@ -90,7 +93,7 @@ mod tests {
     use brumby::hash_lookup::HashLookup;
     use brumby::market::{Market, Overround};
 
-    use crate::domain::{Offer, Over, Period};
+    use crate::domain::{Offer, Over, Period, Side};
 
     use super::*;
 
@@ -149,4 +152,14 @@ mod tests {
             offer.validate().unwrap_err().to_string()
         );
     }
+
+    #[test]
+    fn valid_team_total() {
+        let offer = Offer {
+            offer_type: OfferType::TeamTotalGoals(Period::FullTime, Side::Away, Over(1)),
+            outcomes: HashLookup::from(vec![Outcome::Over(1), Outcome::Under(2)]),
+            market: Market::frame(&Overround::fair(), vec![0.4, 0.6], &PRICE_BOUNDS),
+        };
+        offer.validate().unwrap();
+    }
 }
@@ -0,0 +1,113 @@
+use brumby::hash_lookup::HashLookup;
+
+use crate::domain::validation::{ExtraneousOutcome, InvalidOffer, InvalidOutcome};
+use crate::domain::{validation, DrawHandicap, OfferType, Outcome, Side, WinHandicap};
+
+pub(crate) fn validate_outcomes(
+    offer_type: &OfferType,
+    outcomes: &HashLookup<Outcome>,
+) -> Result<(), InvalidOutcome> {
+    validation::OutcomesCompleteAssertion {
+        outcomes: &valid_outcomes(),
+    }
+    .check(outcomes, offer_type)?;
+    Ok(())
+}
+
+pub(crate) fn validate_outcome(
+    offer_type: &OfferType,
+    outcome: &Outcome,
+) -> Result<(), InvalidOutcome> {
+    let valid_outcomes = valid_outcomes();
+    if valid_outcomes.contains(outcome) {
+        Ok(())
+    } else {
+        Err(InvalidOutcome::ExtraneousOutcome(ExtraneousOutcome {
+            outcome: outcome.clone(),
+            offer_type: offer_type.clone(),
+        }))
+    }
+}
+
+pub(crate) fn validate_probs(offer_type: &OfferType, probs: &[f64]) -> Result<(), InvalidOffer> {
+    validation::BooksumAssertion::with_default_tolerance(1.0..=1.0).check(probs, offer_type)?;
+    Ok(())
+}
+
+fn valid_outcomes() -> [Outcome; 7] {
+    [
+        Outcome::WinBy(Side::Home, 1),
+        Outcome::WinBy(Side::Home, 2),
+        Outcome::Win(Side::Home, WinHandicap::AheadOver(2)),
+        Outcome::Draw(DrawHandicap::Ahead(0)),
+        Outcome::WinBy(Side::Away, 1),
+        Outcome::WinBy(Side::Away, 2),
+        Outcome::Win(Side::Away, WinHandicap::AheadOver(2)),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::RangeInclusive;
+
+    use brumby::hash_lookup::HashLookup;
+    use brumby::market::{Market, Overround};
+
+    use crate::domain::{Offer, Period};
+
+    use super::*;
+
+    const OFFER_TYPE: OfferType = OfferType::WinningMargin(Period::FullTime);
+    const PRICE_BOUNDS: RangeInclusive<f64> = 1.0..=1001.0;
+
+    #[test]
+    fn valid() {
+        let offer = Offer {
+            offer_type: OFFER_TYPE,
+            outcomes: HashLookup::from(valid_outcomes()),
+            market: Market::frame(&Overround::fair(), vec![0.2, 0.1, 0.1, 0.3, 0.15, 0.1, 0.05], &PRICE_BOUNDS),
+        };
+        offer.validate().unwrap();
+    }
+
+    #[test]
+    fn wrong_booksum() {
+        let offer = Offer {
+            offer_type: OFFER_TYPE,
+            outcomes: HashLookup::from(valid_outcomes()),
+            market: Market::frame(&Overround::fair(), vec![0.5, 0.4, 0.0, 0.0, 0.0, 0.0, 0.0], &PRICE_BOUNDS),
+        };
+        assert_eq!(
+            "expected booksum in 1.0..=1.0 ± 0.001, got 0.9 for WinningMargin(FullTime)",
+            offer.validate().unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn missing_outcome() {
+        let offer = Offer {
+            offer_type: OFFER_TYPE,
+            outcomes: HashLookup::from(valid_outcomes()[..6].to_vec()),
+            market: Market::frame(&Overround::fair(), vec![0.2, 0.1, 0.1, 0.3, 0.15, 0.15], &PRICE_BOUNDS),
+        };
+        assert_eq!(
+            "Win(Away, AheadOver(2)) missing from WinningMargin(FullTime)",
+            offer.validate().unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn extraneous_outcome() {
+        let mut outcomes = valid_outcomes().to_vec();
+        outcomes.push(Outcome::WinBy(Side::Home, 3));
+        let offer = Offer {
+            offer_type: OFFER_TYPE,
+            outcomes: HashLookup::from(outcomes),
+            market: Market::frame(&Overround::fair(), vec![0.2, 0.1, 0.1, 0.3, 0.1, 0.1, 0.05, 0.05], &PRICE_BOUNDS),
+        };
+        assert_eq!(
+            "WinBy(Home, 3) does not belong in WinningMargin(FullTime)",
+            offer.validate().unwrap_err().to_string()
+        );
+    }
+}
@@ -0,0 +1,485 @@
+//! Corners and bookings, modelled as counts of events by each side over the match. The counts are
+//! independent of the interval model; they relate to the goals only by a configurable dependence
+//! of the total count on the total goals, which is applied when the two are combined in a multi.
+
+use rustc_hash::FxHashMap;
+
+use brumby::linear::matrix::Matrix;
+use brumby::probs::SliceExt;
+
+use crate::domain::{EventStream, OfferType, Outcome, Player, Side, WinHandicap};
+use crate::scoregrid;
+
+/// The joint distribution of the events of each side, together with the dependence of the events
+/// on the goals.
+#[derive(Debug, Clone)]
+pub struct EventModel {
+    /// Probabilities of the event counts, indexed by the home and away counts.
+    pub counts: Matrix<f64>,
+    /// The share of its side's events that falls to each player. The shares of the players of
+    /// one side may not exceed 1.
+    pub player_shares: FxHashMap<Player, f64>,
+    /// The dependence of the total events on the total goals, in `-1.0..=1.0`. It is the weight
+    /// given to the perfectly dependent (or, if negative, perfectly inversely dependent) pairing of
+    /// the two totals, the remainder of the weight going to the independent pairing. Either way,
+    /// the marginal distributions of the goals and the events are preserved.
+    pub goal_correlation: f64,
+}
+impl EventModel {
+    /// Models the events of each side as independent negative binomial counts, truncated at
+    /// `max_events` per side.
+    pub fn from_negative_binomial(
+        home_mean: f64,
+        away_mean: f64,
+        dispersion: f64,
+        max_events: usize,
+        goal_correlation: f64,
+    ) -> Self {
+        let mut counts = Matrix::allocate(max_events + 1, max_events + 1);
+        scoregrid::from_negative_binomial(home_mean, away_mean, dispersion, &mut counts);
+        counts.flatten_mut().normalise(1.0);
+        Self {
+            counts,
+            player_shares: Default::default(),
+            goal_correlation,
+        }
+    }
+
+    pub fn player_share(&self, player: &Player) -> Option<f64> {
+        self.player_shares.get(player).copied()
+    }
+
+    /// The expected events of each side.
+    pub fn expectations(&self) -> (f64, f64) {
+        scoregrid::home_away_expectations(&self.counts)
+    }
+
+    /// The probability of an outcome. The shares of any players in player outcomes must be
+    /// present.
+    pub fn isolate(&self, offer_type: &OfferType, outcome: &Outcome) -> f64 {
+        let selections = [(offer_type, outcome)];
+        let mut prob = 0.0;
+        for home in 0..self.counts.rows() {
+            for away in 0..self.counts.cols() {
+                prob += self.counts[(home, away)] * self.conditional_prob(&selections, home, away);
+            }
+        }
+        prob
+    }
+
+    /// The probability of all `selections` being satisfied, given the events of each side.
+    fn conditional_prob(
+        &self,
+        selections: &[(&OfferType, &Outcome)],
+        home: usize,
+        away: usize,
+    ) -> f64 {
+        let mut home_players = vec![];
+        let mut away_players = vec![];
+        for (offer_type, outcome) in selections {
+            match offer_type {
+                OfferType::PlayerEvents(stream, over) => {
+                    let Outcome::Player(player) = outcome else {
+                        panic!("{outcome:?} unsupported");
+                    };
+                    let Player::Named(side, _) = player else {
+                        panic!("{player:?} unsupported");
+                    };
+                    let share = self
+                        .player_share(player)
+                        .unwrap_or_else(|| panic!("missing share for {player:?}"));
+                    let Outcome::Over(line) = to_events(stream, &Outcome::Over(over.0)) else {
+                        unreachable!()
+                    };
+                    let side_players = match side {
+                        Side::Home => &mut home_players,
+                        Side::Away => &mut away_players,
+                    };
+                    // the same player selected on several lines must exceed the highest of them
+                    match side_players
+                        .iter_mut()
+                        .find(|(existing, _, _)| *existing == player)
+                    {
+                        Some((_, _, existing_line)) => {
+                            *existing_line = usize::max(*existing_line, line as usize)
+                        }
+                        None => side_players.push((player, share, line as usize)),
+                    }
+                }
+                _ => {
+                    if !matches(offer_type, outcome, home, away) {
+                        return 0.0;
+                    }
+                }
+            }
+        }
+        players_exceed(&home_players, home) * players_exceed(&away_players, away)
+    }
+
+    /// The probability of all `selections` being satisfied, conditional on each total number of
+    /// goals, given the (unconditional) probabilities of those totals.
+    pub fn isolate_given_goals(
+        &self,
+        selections: &[(&OfferType, &Outcome)],
+        goal_probs: &[f64],
+    ) -> Vec<f64> {
+        let max_total_events = self.counts.rows() + self.counts.cols() - 1;
+        let mut total_probs = vec![0.0; max_total_events];
+        let mut matching_probs = vec![0.0; max_total_events];
+        for home in 0..self.counts.rows() {
+            for away in 0..self.counts.cols() {
+                let prob = self.counts[(home, away)];
+                total_probs[home + away] += prob;
+                matching_probs[home + away] += prob * self.conditional_prob(selections, home, away);
+            }
+        }
+
+        let joint = couple(goal_probs, &total_probs, self.goal_correlation);
+        goal_probs
+            .iter()
+            .enumerate()
+            .map(|(goals, &goal_prob)| {
+                if goal_prob == 0.0 {
+                    return 0.0;
+                }
+                let prob = (0..max_total_events)
+                    .filter(|&events| total_probs[events] > 0.0)
+                    .map(|events| {
+                        joint[(goals, events)] * matching_probs[events] / total_probs[events]
+                    })
+                    .sum::<f64>();
+                prob / goal_prob
+            })
+            .collect()
+    }
+}
+
+/// The probability of each of the `players` exceeding their line, where `(player, share, line)`,
+/// given the events of their side. The events are dealt to the players multinomially, in
+/// proportion to their shares, so the players' counts are mutually (negatively) dependent.
+fn players_exceed(players: &[(&Player, f64, usize)], events: usize) -> f64 {
+    if players.is_empty() {
+        return 1.0;
+    }
+
+    // the probability of the players considered so far exceeding their lines having taken the
+    // given number of events between them
+    let mut taken_probs = vec![0.0; events + 1];
+    taken_probs[0] = 1.0;
+    let mut remaining_share = 1.0;
+    for (_, share, line) in players {
+        let conditional_share = if remaining_share > 0.0 {
+            f64::min(1.0, share / remaining_share)
+        } else {
+            0.0
+        };
+        let mut next_taken_probs = vec![0.0; events + 1];
+        for (taken, &taken_prob) in taken_probs.iter().enumerate() {
+            if taken_prob == 0.0 {
+                continue;
+            }
+            let available = events - taken;
+            for (player_events, prob) in binomial_pmf(available, conditional_share)
+                .into_iter()
+                .enumerate()
+                .skip(line + 1)
+            {
+                next_taken_probs[taken + player_events] += taken_prob * prob;
+            }
+        }
+        taken_probs = next_taken_probs;
+        remaining_share -= share;
+    }
+    taken_probs.iter().sum()
+}
+
+/// The probabilities of `0..=trials` successes in independent trials of probability `p`.
+fn binomial_pmf(trials: usize, p: f64) -> Vec<f64> {
+    let mut pmf = vec![0.0; trials + 1];
+    if p >= 1.0 {
+        pmf[trials] = 1.0;
+        return pmf;
+    }
+    pmf[0] = (1.0 - p).powi(trials as i32);
+    let odds = p / (1.0 - p);
+    for successes in 1..=trials {
+        let ratio = (trials - successes + 1) as f64 / successes as f64;
+        pmf[successes] = pmf[successes - 1] * ratio * odds;
+    }
+    pmf
+}
+
+/// Pairs two discrete distributions into a joint distribution with the given marginals. A
+/// `correlation` of zero pairs them independently; `1.0` pairs them comonotonically, matching
+/// quantiles; `-1.0` pairs them countermonotonically. Values in between mix the independent and
+/// the (counter)monotonic pairings. Should the totals of the two differ, the second is rescaled to
+/// the total of the first, so that all of the probability mass is paired.
+pub fn couple(first: &[f64], second: &[f64], correlation: f64) -> Matrix<f64> {
+    let mut joint = Matrix::allocate(first.len(), second.len());
+    let (first_total, second_total) = (first.sum(), second.sum());
+    if first_total == 0.0 || second_total == 0.0 {
+        return joint;
+    }
+    let second = second
+        .iter()
+        .map(|prob| prob * first_total / second_total)
+        .collect::<Vec<_>>();
+    let second = &second[..];
+    let independent_weight = 1.0 - correlation.abs();
+    for (row, first_prob) in first.iter().enumerate() {
+        for (col, second_prob) in second.iter().enumerate() {
+            joint[(row, col)] = independent_weight * first_prob * second_prob;
+        }
+    }
+
+    if correlation != 0.0 {
+        let col_of = |index: usize| {
+            if correlation > 0.0 {
+                index
+            } else {
+                second.len() - 1 - index
+            }
+        };
+        let (mut row, mut index) = (0, 0);
+        let (mut first_remaining, mut second_remaining) = (first[0], second[col_of(0)]);
+        loop {
+            let mass = f64::min(first_remaining, second_remaining);
+            joint[(row, col_of(index))] += correlation.abs() * mass;
+            first_remaining -= mass;
+            second_remaining -= mass;
+            if first_remaining <= 0.0 {
+                row += 1;
+                if row == first.len() {
+                    break;
+                }
+                first_remaining = first[row];
+            }
+            if second_remaining <= 0.0 {
+                index += 1;
+                if index == second.len() {
+                    break;
+                }
+                second_remaining = second[col_of(index)];
+            }
+        }
+    }
+    joint
+}
+
+/// Expresses an outcome in booking points (or any other points) as an outcome in events. Since
+/// the points are multiples of the points per event, an event count exceeds a line in points if it
+/// exceeds the line divided by the points per event, rounded down; it falls below the line if it
+/// falls below the line divided by the points per event, rounded up.
+pub fn to_events(stream: &EventStream, outcome: &Outcome) -> Outcome {
+    let points = stream.points();
+    match outcome {
+        Outcome::Over(line) => Outcome::Over(line / points),
+        Outcome::Under(line) => Outcome::Under(line.div_ceil(points)),
+        Outcome::Win(side, WinHandicap::AheadOver(by)) => {
+            Outcome::Win(side.clone(), WinHandicap::AheadOver(by / points))
+        }
+        Outcome::Win(side, WinHandicap::BehindUnder(by)) => {
+            Outcome::Win(side.clone(), WinHandicap::BehindUnder(by.div_ceil(points)))
+        }
+        _ => panic!("{outcome:?} unsupported"),
+    }
+}
+
+/// Gathers the probability of an event outcome over the event counts.
+pub fn gather(offer_type: &OfferType, outcome: &Outcome, counts: &Matrix<f64>) -> f64 {
+    let mut prob = 0.0;
+    for home in 0..counts.rows() {
+        for away in 0..counts.cols() {
+            if matches(offer_type, outcome, home, away) {
+                prob += counts[(home, away)];
+            }
+        }
+    }
+    prob
+}
+
+fn matches(offer_type: &OfferType, outcome: &Outcome, home: usize, away: usize) -> bool {
+    let stream = offer_type
+        .event_stream()
+        .unwrap_or_else(|| panic!("{offer_type:?} unsupported"));
+    let (home, away) = (home as u8, away as u8);
+    match (offer_type, to_events(stream, outcome)) {
+        (OfferType::TotalEvents(_, _), Outcome::Over(line)) => home + away > line,
+        (OfferType::TotalEvents(_, _), Outcome::Under(line)) => home + away < line,
+        (OfferType::TeamTotalEvents(_, side, _), outcome) => {
+            let events = match side {
+                Side::Home => home,
+                Side::Away => away,
+            };
+            match outcome {
+                Outcome::Over(line) => events > line,
+                Outcome::Under(line) => events < line,
+                _ => panic!("{outcome:?} unsupported"),
+            }
+        }
+        (OfferType::EventHandicap(_, _), Outcome::Win(side, win_handicap)) => {
+            let (ours, theirs) = match side {
+                Side::Home => (home, away),
+                Side::Away => (away, home),
+            };
+            match win_handicap {
+                WinHandicap::AheadOver(by) => ours.saturating_sub(theirs) > by,
+                WinHandicap::BehindUnder(by) => ours > theirs || theirs - ours < by,
+            }
+        }
+        (_, outcome) => panic!("{outcome:?} unsupported"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_float_eq::*;
+
+    use crate::domain::Over;
+
+    use super::*;
+
+    #[test]
+    fn couple_preserves_marginals() {
+        let first = [0.2, 0.5, 0.3];
+        let second = [0.1, 0.3, 0.4, 0.2];
+        for correlation in [-1.0, -0.5, 0.0, 0.5, 1.0] {
+            let joint = couple(&first, &second, correlation);
+            for (row, prob) in first.iter().enumerate() {
+                assert_float_absolute_eq!(*prob, (0..second.len()).map(|col| joint[(row, col)]).sum::<f64>());
+            }
+            for (col, prob) in second.iter().enumerate() {
+                assert_float_absolute_eq!(*prob, (0..first.len()).map(|row| joint[(row, col)]).sum::<f64>());
+            }
+        }
+    }
+
+    #[test]
+    fn couple_comonotonic() {
+        let joint = couple(&[0.5, 0.5], &[0.5, 0.5], 1.0);
+        assert_float_absolute_eq!(0.5, joint[(0, 0)]);
+        assert_float_absolute_eq!(0.0, joint[(0, 1)]);
+        assert_float_absolute_eq!(0.5, joint[(1, 1)]);
+
+        let joint = couple(&[0.5, 0.5], &[0.5, 0.5], -1.0);
+        assert_float_absolute_eq!(0.0, joint[(0, 0)]);
+        assert_float_absolute_eq!(0.5, joint[(0, 1)]);
+        assert_float_absolute_eq!(0.5, joint[(1, 0)]);
+    }
+
+    #[test]
+    fn couple_unequal_marginals() {
+        let first = [0.2, 0.5, 0.3];
+        let second = [0.1, 0.3, 0.4];
+        for correlation in [-1.0, 0.0, 0.5, 1.0] {
+            let joint = couple(&first, &second, correlation);
+            for (row, prob) in first.iter().enumerate() {
+                assert_float_absolute_eq!(*prob, (0..second.len()).map(|col| joint[(row, col)]).sum::<f64>());
+            }
+            for (col, prob) in second.iter().enumerate() {
+                assert_float_absolute_eq!(prob * 1.25, (0..first.len()).map(|row| joint[(row, col)]).sum::<f64>());
+            }
+        }
+        assert_eq!(0.0, couple(&first, &[0.0, 0.0], 0.5).flatten().sum());
+    }
+
+    #[test]
+    fn binomial_pmf_sums_to_one() {
+        for (trials, p) in [(0, 0.3), (1, 0.3), (20, 0.05), (20, 0.0), (20, 1.0), (120, 0.7)] {
+            let pmf = binomial_pmf(trials, p);
+            assert_eq!(trials + 1, pmf.len());
+            assert_float_absolute_eq!(1.0, pmf.sum());
+        }
+        assert_float_absolute_eq!(0.375, binomial_pmf(3, 0.5)[1]);
+    }
+
+    #[test]
+    fn isolate_player_events() {
+        let markos = Player::Named(Side::Home, "Markos".into());
+        let nikos = Player::Named(Side::Home, "Nikos".into());
+        let mut model = EventModel::from_negative_binomial(2.0, 1.5, 0.05, 16, 0.0);
+        model.player_shares.insert(markos.clone(), 0.3);
+        model.player_shares.insert(nikos.clone(), 0.2);
+        let carded = OfferType::PlayerEvents(EventStream::Bookings, Over(5));
+        let markos_outcome = Outcome::Player(markos.clone());
+        let nikos_outcome = Outcome::Player(nikos);
+
+        // each home booking falls to Markos independently with probability 0.3
+        let expected = (0..model.counts.rows())
+            .map(|home| {
+                let home_prob = (0..model.counts.cols()).map(|away| model.counts[(home, away)]).sum::<f64>();
+                home_prob * (1.0 - 0.7_f64.powi(home as i32))
+            })
+            .sum::<f64>();
+        let markos_prob = model.isolate(&carded, &markos_outcome);
+        assert_float_absolute_eq!(expected, markos_prob);
+
+        // teammates compete for the same bookings
+        let independent_prob = (0..model.counts.rows())
+            .map(|home| {
+                let home_prob = (0..model.counts.cols()).map(|away| model.counts[(home, away)]).sum::<f64>();
+                home_prob * (1.0 - 0.7_f64.powi(home as i32)) * (1.0 - 0.8_f64.powi(home as i32))
+            })
+            .sum::<f64>();
+        let both_prob = model.isolate_given_goals(
+            &[(&carded, &markos_outcome), (&carded, &nikos_outcome)],
+            &[1.0],
+        )[0];
+        assert!(both_prob > 0.0);
+        assert!(both_prob < independent_prob);
+
+        // the same player on two lines must exceed the higher one
+        let sent_off = OfferType::PlayerEvents(EventStream::Bookings, Over(15));
+        let sent_off_prob = model.isolate(&sent_off, &markos_outcome);
+        assert!(sent_off_prob < markos_prob);
+        assert_float_absolute_eq!(
+            sent_off_prob,
+            model.isolate_given_goals(&[(&carded, &markos_outcome), (&sent_off, &markos_outcome)], &[1.0])[0]
+        );
+    }
+
+    #[test]
+    fn to_events_in_booking_points() {
+        let stream = EventStream::Bookings;
+        assert_eq!(Outcome::Over(4), to_events(&stream, &Outcome::Over(45)));
+        assert_eq!(Outcome::Under(5), to_events(&stream, &Outcome::Under(46)));
+        assert_eq!(
+            Outcome::Win(Side::Home, WinHandicap::AheadOver(1)),
+            to_events(&stream, &Outcome::Win(Side::Home, WinHandicap::AheadOver(15)))
+        );
+        assert_eq!(
+            Outcome::Win(Side::Away, WinHandicap::BehindUnder(2)),
+            to_events(&stream, &Outcome::Win(Side::Away, WinHandicap::BehindUnder(16)))
+        );
+        assert_eq!(Outcome::Over(9), to_events(&EventStream::Corners, &Outcome::Over(9)));
+    }
+
+    #[test]
+    fn isolate_given_goals() {
+        let model = EventModel::from_negative_binomial(5.5, 4.5, 0.05, 20, 0.0);
+        let offer_type = OfferType::TotalEvents(EventStream::Corners, Over(9));
+        let over = model.isolate(&offer_type, &Outcome::Over(9));
+        let under = model.isolate(&offer_type, &Outcome::Under(10));
+        assert_float_absolute_eq!(1.0, over + under);
+
+        // independent of the goals
+        let goal_probs = [0.3, 0.4, 0.3];
+        let conditional = model.isolate_given_goals(&[(&offer_type, &Outcome::Over(9))], &goal_probs);
+        for prob in conditional {
+            assert_float_absolute_eq!(over, prob);
+        }
+
+        // more events with more goals, yet the same events overall
+        let model = EventModel {
+            goal_correlation: 0.5,
+            ..model
+        };
+        let conditional = model.isolate_given_goals(&[(&offer_type, &Outcome::Over(9))], &goal_probs);
+        assert!(conditional[0] < over);
+        assert!(conditional[2] > over);
+        assert_float_absolute_eq!(
+            over,
+            conditional.iter().zip(goal_probs).map(|(prob, goal_prob)| prob * goal_prob).sum::<f64>()
+        );
+    }
+}
@@ -13,15 +13,20 @@ use brumby::probs::SliceExt;
 use brumby::{arrays, factorial, poisson, sv};
 
 use crate::domain::Player::Named;
-use crate::domain::{Offer, OfferType, Outcome, Player, Side};
+use crate::domain::{Offer, OfferType, Outcome, Period, Player, Side};
+use crate::events;
 use crate::interval::query::{isolate, requirements};
 use crate::interval::{
     explore, BivariateProbs, Config, PlayerProbs, PruneThresholds, TeamProbs, UnivariateProbs,
 };
 use crate::scoregrid;
+use crate::scoregrid::ScoreDistribution;
 
 const GOALSCORER_MIN_PROB: f64 = 0.0;
 const ERROR_TYPE: ErrorType = ErrorType::SquaredRelative;
+/// The error of an outcome that is priced by the market but deemed impossible by the fit, for which
+/// the relative error is unbounded.
+const ZERO_PROB_PENALTY: f64 = 1e3;
 
 pub enum ErrorType {
     SquaredRelative,
@@ -107,6 +112,7 @@ pub fn fit_scoregrid_half(
 pub fn fit_scoregrid_full(
     h2h: &Offer,
     total_goals: &Offer,
+    team_goals: &[&Offer],
     intervals: u8,
     max_total_goals: u16,
 ) -> (HypergridSearchOutcome<3>, [f64; 3]) {
@@ -140,7 +146,11 @@ pub fn fit_scoregrid_full(
         search_outcome.optimal_value
     };
 
-    let offers = &[h2h, total_goals];
+    let mut offers = Vec::with_capacity(2 + team_goals.len());
+    offers.push(h2h);
+    offers.push(total_goals);
+    offers.extend_from_slice(team_goals);
+    let offers = &offers[..];
     let expected_common_goals = {
         let start = Instant::now();
         let search_outcome = fit_poisson_common_scoregrid(
@@ -219,6 +229,358 @@ pub fn fit_scoregrid_full(
     (search_outcome, lambdas)
 }
 
+/// The weighted residual of a single offer, having fitted the goal model.
+#[derive(Debug, Clone)]
+pub struct OfferResidual {
+    pub offer_type: OfferType,
+    pub weight: f64,
+    pub residual: f64,
+}
+
+/// Whether the offer can be reproduced from a scoregrid, and if so, the period it applies to.
+pub fn scoregrid_period(offer_type: &OfferType) -> Option<&Period> {
+    match offer_type {
+        OfferType::HeadToHead(period, _)
+        | OfferType::TotalGoals(period, _)
+        | OfferType::CorrectScore(period)
+        | OfferType::AsianHandicap(period, _)
+        | OfferType::TeamTotalGoals(period, _, _)
+        | OfferType::ExactTeamGoals(period, _, _)
+        | OfferType::ExactTotalGoals(period, _)
+        | OfferType::OddEvenGoals(period)
+        | OfferType::WinningMargin(period)
+        | OfferType::DoubleChance(period) => Some(period),
+        _ => None,
+    }
+}
+
+struct PeriodScoregrids {
+    h1_intervals: u8,
+    h2_intervals: u8,
+    h1: Matrix<f64>,
+    h2: Matrix<f64>,
+    ft: Matrix<f64>,
+}
+impl PeriodScoregrids {
+    fn allocate(intervals: u8, max_total_goals: u16) -> Self {
+        let h1_intervals = intervals / 2;
+        let h2_intervals = intervals - h1_intervals;
+        Self {
+            h1_intervals,
+            h2_intervals,
+            h1: allocate_scoregrid(h1_intervals, max_total_goals),
+            h2: allocate_scoregrid(h2_intervals, max_total_goals),
+            ft: allocate_scoregrid(intervals, max_total_goals),
+        }
+    }
+
+    fn update(&mut self, h1_probs: &BivariateProbs, h2_probs: &BivariateProbs) {
+        bivariate_binomial_scoregrid(
+            self.h1_intervals,
+            h1_probs.home,
+            h1_probs.away,
+            h1_probs.common,
+            &mut self.h1,
+        );
+        bivariate_binomial_scoregrid(
+            self.h2_intervals,
+            h2_probs.home,
+            h2_probs.away,
+            h2_probs.common,
+            &mut self.h2,
+        );
+        scoregrid::convolve(&self.h1, &self.h2, &mut self.ft);
+    }
+
+    fn for_offer(&self, offer: &Offer) -> &Matrix<f64> {
+        match scoregrid_period(&offer.offer_type).unwrap() {
+            Period::FirstHalf => &self.h1,
+            Period::SecondHalf => &self.h2,
+            Period::FullTime => &self.ft,
+        }
+    }
+}
+
+/// Fits the goal probabilities of both halves to minimise the weighted error across all
+/// supplied offers, each of which must be reproducible from a scoregrid. The common goal
+/// probability is shared between the halves. The fitted values are ordered as
+/// `[h1 home, h1 away, h2 home, h2 away, common]`.
+pub fn fit_scoregrid_joint(
+    offers: &[(&Offer, f64)],
+    init_h1_probs: &BivariateProbs,
+    init_h2_probs: &BivariateProbs,
+    intervals: u8,
+    max_total_goals: u16,
+) -> HypergridSearchOutcome<5> {
+    let start = Instant::now();
+    let mut scoregrids = PeriodScoregrids::allocate(intervals, max_total_goals);
+    let init_estimates = [
+        init_h1_probs.home,
+        init_h1_probs.away,
+        init_h2_probs.home,
+        init_h2_probs.away,
+        (init_h1_probs.common + init_h2_probs.common) / 2.0,
+    ];
+    let arrays::FromIteratorResult::<RangeInclusive<f64>, 5>(bounds) = init_estimates
+        .iter()
+        .map(|&estimate| (estimate * 0.67)..=(estimate * 1.5))
+        .collect();
+    let bounds = bounds.unwrap();
+    let search_outcome = hypergrid_search(
+        &HypergridSearchConfig {
+            max_steps: 10,
+            acceptable_residual: 1e-6,
+            bounds: Capture::Borrowed(&bounds),
+            resolution: 4,
+        },
+        |values| {
+            values[0] + values[1] + values[4] <= 1.0 && values[2] + values[3] + values[4] <= 1.0
+        },
+        |values| {
+            let (h1_probs, h2_probs) = joint_probs(values);
+            scoregrids.update(&h1_probs, &h2_probs);
+            offers
+                .iter()
+                .map(|(offer, weight)| weight * offer_error(offer, scoregrids.for_offer(offer)))
+                .sum()
+        },
+    );
+    let elapsed = start.elapsed();
+    debug!(
+        "fitted joint bivariate binomial ({} offers): took {elapsed:?}, {search_outcome:?}",
+        offers.len()
+    );
+    search_outcome
+}
+
+/// Splits the fitted values of [fit_scoregrid_joint] into the goal probabilities of each half.
+pub fn joint_probs(values: &[f64]) -> (BivariateProbs, BivariateProbs) {
+    (
+        BivariateProbs {
+            home: values[0],
+            away: values[1],
+            common: values[4],
+        },
+        BivariateProbs {
+            home: values[2],
+            away: values[3],
+            common: values[4],
+        },
+    )
+}
+
+/// Computes the weighted residual of each offer against the scoregrids implied by the given
+/// goal probabilities.
+pub fn scoregrid_residuals(
+    offers: &[(&Offer, f64)],
+    h1_probs: &BivariateProbs,
+    h2_probs: &BivariateProbs,
+    intervals: u8,
+    max_total_goals: u16,
+) -> Vec<OfferResidual> {
+    let mut scoregrids = PeriodScoregrids::allocate(intervals, max_total_goals);
+    scoregrids.update(h1_probs, h2_probs);
+    offers
+        .iter()
+        .map(|(offer, weight)| OfferResidual {
+            offer_type: offer.offer_type.clone(),
+            weight: *weight,
+            residual: weight * offer_error(offer, scoregrids.for_offer(offer)),
+        })
+        .collect()
+}
+
+/// Fits a parametric score distribution to the supplied full-time offers. The fitted values are
+/// ordered as `[home rate, away rate, shape]`; the returned scoregrid is populated from the
+/// optimal values.
+pub fn fit_score_distribution(
+    distribution: &ScoreDistribution,
+    offers: &[(&Offer, f64)],
+    init_home_rate: f64,
+    init_away_rate: f64,
+    intervals: u8,
+    max_total_goals: u16,
+) -> (HypergridSearchOutcome<3>, Matrix<f64>) {
+    let start = Instant::now();
+    let mut scoregrid = allocate_scoregrid(intervals, max_total_goals);
+    let bounds = [
+        init_home_rate * 0.67..=init_home_rate * 1.5,
+        init_away_rate * 0.67..=init_away_rate * 1.5,
+        distribution.shape_bounds(),
+    ];
+    let search_outcome = hypergrid_search(
+        &HypergridSearchConfig {
+            max_steps: 10,
+            acceptable_residual: 1e-6,
+            bounds: Capture::Borrowed(&bounds),
+            resolution: 4,
+        },
+        |_| true,
+        |values| {
+            distribution.populate(values[0], values[1], values[2], &mut scoregrid);
+            offers
+                .iter()
+                .map(|(offer, weight)| weight * offer_error(offer, &scoregrid))
+                .sum()
+        },
+    );
+    let optimal_values = &search_outcome.optimal_values;
+    distribution.populate(optimal_values[0], optimal_values[1], optimal_values[2], &mut scoregrid);
+    let elapsed = start.elapsed();
+    debug!(
+        "fitted {distribution:?} ({} offers): took {elapsed:?}, {search_outcome:?}",
+        offers.len()
+    );
+    (search_outcome, scoregrid)
+}
+
+/// Fits independent negative binomial event counts to the supplied offers on a stream of events,
+/// given an initial estimate of the total events. The means are first fitted with no dispersion,
+/// then refined together with the dispersion. The fitted values are ordered as
+/// `[home mean, away mean, dispersion]`; the returned counts are populated from the optimal values.
+pub fn fit_event_counts(
+    offers: &[&Offer],
+    init_total_events: f64,
+    max_events: usize,
+) -> (HypergridSearchOutcome<3>, Matrix<f64>) {
+    let start = Instant::now();
+    let mut counts = Matrix::allocate(max_events + 1, max_events + 1);
+    let populate = |values: &[f64], counts: &mut Matrix<f64>| {
+        scoregrid::from_negative_binomial(values[0], values[1], values[2], counts);
+        counts.flatten_mut().normalise(1.0);
+    };
+    let mut search = |bounds: &[RangeInclusive<f64>; 3]| {
+        hypergrid_search(
+            &HypergridSearchConfig {
+                max_steps: 10,
+                acceptable_residual: 1e-9,
+                bounds: Capture::Borrowed(bounds),
+                resolution: 6,
+            },
+            |_| true,
+            |values| {
+                populate(values, &mut counts);
+                offers
+                    .iter()
+                    .map(|offer| offer_error(offer, &counts))
+                    .sum()
+            },
+        )
+    };
+    let poisson_outcome = search(&[
+        init_total_events * 0.1..=init_total_events * 0.9,
+        init_total_events * 0.1..=init_total_events * 0.9,
+        0.0..=0.0,
+    ]);
+    let [home_mean, away_mean, _] = poisson_outcome.optimal_values;
+    let search_outcome = search(&[
+        home_mean * 0.8..=home_mean * 1.2,
+        away_mean * 0.8..=away_mean * 1.2,
+        ScoreDistribution::NegativeBinomial.shape_bounds(),
+    ]);
+    populate(&search_outcome.optimal_values, &mut counts);
+    let elapsed = start.elapsed();
+    debug!(
+        "fitted event counts ({} offers): took {elapsed:?}, {search_outcome:?}",
+        offers.len()
+    );
+    (search_outcome, counts)
+}
+
+/// Calibrates the goal probabilities of both halves so that the full-time scoregrid of the
+/// interval model approximates the `target` scoregrid. The home and away probabilities of each
+/// half are scaled by a common factor per side, preserving the split between the halves. The
+/// fitted values are ordered as `[home factor, away factor, common]`.
+///
+/// With three parameters, the calibration matches the marginal goal rates of the target; any
+/// dependence or dispersion in the target beyond what the bivariate binomial admits is lost. The
+/// optimal residual is the sum of squared differences between the target and the calibrated
+/// scoregrid, i.e., the calibration error.
+pub fn calibrate_scoregrid(
+    target: &Matrix<f64>,
+    init_h1_probs: &BivariateProbs,
+    init_h2_probs: &BivariateProbs,
+    intervals: u8,
+    max_total_goals: u16,
+) -> HypergridSearchOutcome<3> {
+    let start = Instant::now();
+    let mut scoregrids = PeriodScoregrids::allocate(intervals, max_total_goals);
+    let init_common = (init_h1_probs.common + init_h2_probs.common) / 2.0;
+    let bounds = [0.67..=1.5, 0.67..=1.5, 0.0..=f64::max(init_common * 2.0, 0.02)];
+    let search_outcome = hypergrid_search(
+        &HypergridSearchConfig {
+            max_steps: 10,
+            acceptable_residual: 1e-9,
+            bounds: Capture::Borrowed(&bounds),
+            resolution: 4,
+        },
+        |values| {
+            let (h1_probs, h2_probs) = calibrated_probs(init_h1_probs, init_h2_probs, values);
+            h1_probs.home + h1_probs.away + h1_probs.common <= 1.0
+                && h2_probs.home + h2_probs.away + h2_probs.common <= 1.0
+        },
+        |values| {
+            let (h1_probs, h2_probs) = calibrated_probs(init_h1_probs, init_h2_probs, values);
+            scoregrids.update(&h1_probs, &h2_probs);
+            target
+                .flatten()
+                .iter()
+                .zip(scoregrids.ft.flatten())
+                .map(|(&sample, &fitted)| ErrorType::SquaredAbsolute.calculate(sample, fitted))
+                .sum()
+        },
+    );
+    let elapsed = start.elapsed();
+    debug!("calibrated bivariate binomial: took {elapsed:?}, {search_outcome:?}");
+    search_outcome
+}
+
+/// Applies the fitted values of [calibrate_scoregrid] to the initial goal probabilities.
+pub fn calibrated_probs(
+    init_h1_probs: &BivariateProbs,
+    init_h2_probs: &BivariateProbs,
+    values: &[f64],
+) -> (BivariateProbs, BivariateProbs) {
+    (
+        BivariateProbs {
+            home: init_h1_probs.home * values[0],
+            away: init_h1_probs.away * values[1],
+            common: values[2],
+        },
+        BivariateProbs {
+            home: init_h2_probs.home * values[0],
+            away: init_h2_probs.away * values[1],
+            common: values[2],
+        },
+    )
+}
+
+/// The mean error across the outcomes of an offer, so that offers with many outcomes (such as
+/// correct score) don't dominate the fit.
+fn offer_error(offer: &Offer, scoregrid: &Matrix<f64>) -> f64 {
+    let outcomes = offer.outcomes.items();
+    if outcomes.is_empty() {
+        return 0.0;
+    }
+    let mut residual = 0.0;
+    for (index, outcome) in outcomes.iter().enumerate() {
+        let fitted_prob = gather(&offer.offer_type, outcome, scoregrid);
+        let sample_prob = offer.market.probs[index];
+        residual += if fitted_prob == 0.0 {
+            // the outcome lies outside the scoregrid (e.g., an implausible correct score) or is
+            // made impossible by the fitted values
+            if sample_prob == 0.0 {
+                0.0
+            } else {
+                ZERO_PROB_PENALTY
+            }
+        } else {
+            ERROR_TYPE.calculate(sample_prob, fitted_prob)
+        };
+    }
+    residual / outcomes.len() as f64
+}
+
 pub fn fit_first_goalscorer_all<'a>(
     h1_probs: &'a BivariateProbs,
     h2_probs: &'a BivariateProbs,
@@ -434,6 +796,183 @@ fn fit_anytime_goalscorer_one(
     )
 }
 
+pub fn fit_last_goalscorer_all<'a>(
+    h1_probs: &'a BivariateProbs,
+    h2_probs: &'a BivariateProbs,
+    last_goalscorer: &'a Offer,
+    nil_all_draw_prob: f64,
+    intervals: u8,
+    max_total_goals: u16,
+) -> Vec<(Player, f64)> {
+    let (home_ratio, away_ratio) = side_ratios(h1_probs, h2_probs, nil_all_draw_prob);
+    let start = Instant::now();
+    let probs = last_goalscorer
+        .outcomes
+        .items()
+        .iter()
+        .enumerate()
+        .filter(|(_, outcome)| matches!(outcome, Outcome::Player(_)))
+        .map(|(index, outcome)| {
+            match outcome {
+                Outcome::Player(player) => {
+                    let side_ratio = match player {
+                        Named(side, _) => match side {
+                            Side::Home => home_ratio,
+                            Side::Away => away_ratio,
+                        },
+                        Player::Other => unreachable!(),
+                    };
+                    let init_estimate = last_goalscorer.market.probs[index] / side_ratio;
+                    let player_search_outcome = fit_player_goal_one(
+                        &OfferType::LastGoalscorer,
+                        h1_probs,
+                        h2_probs,
+                        player,
+                        init_estimate,
+                        last_goalscorer.market.probs[index],
+                        intervals,
+                        max_total_goals,
+                    );
+                    (player.clone(), player_search_outcome.optimal_value)
+                }
+                _ => unreachable!(),
+            }
+        })
+        .collect();
+    let elapsed = start.elapsed();
+    debug!("last goalscorer fitting took {elapsed:?}");
+    probs
+}
+
+/// Fits player goal probabilities to a [OfferType::PlayerToScore] offer or to one of the offers
+/// by half: [OfferType::PlayerToScoreInHalf], [OfferType::PlayerToScoreInBothHalves] or
+/// [OfferType::PlayerToScoreMultipleInHalf].
+pub fn fit_player_to_score_all<'a>(
+    h1_probs: &'a BivariateProbs,
+    h2_probs: &'a BivariateProbs,
+    player_to_score: &'a Offer,
+    nil_all_draw_prob: f64,
+    intervals: u8,
+    max_total_goals: u16,
+) -> Vec<(Player, f64)> {
+    let (home_ratio, away_ratio) = side_ratios(h1_probs, h2_probs, nil_all_draw_prob);
+    let start = Instant::now();
+    let probs = player_to_score
+        .outcomes
+        .items()
+        .iter()
+        .enumerate()
+        .filter(|(_, outcome)| matches!(outcome, Outcome::Player(_)))
+        .map(|(index, outcome)| {
+            match outcome {
+                Outcome::Player(player) => {
+                    let side_ratio = match player {
+                        Named(side, _) => match side {
+                            Side::Home => home_ratio,
+                            Side::Away => away_ratio,
+                        },
+                        Player::Other => unreachable!(),
+                    };
+                    let prob = player_to_score.market.probs[index];
+                    let init_estimate = match player_to_score.offer_type {
+                        OfferType::PlayerToScore(at_least) => {
+                            prob.powf(1.0 / at_least as f64) / side_ratio
+                        }
+                        OfferType::PlayerToScoreInHalf(_) => 2.0 * prob / side_ratio,
+                        OfferType::PlayerToScoreInBothHalves => 2.0 * prob.sqrt() / side_ratio,
+                        OfferType::PlayerToScoreMultipleInHalf(_, at_least) => {
+                            2.0 * prob.powf(1.0 / at_least as f64) / side_ratio
+                        }
+                        _ => panic!("unsupported {:?}", player_to_score.offer_type),
+                    };
+                    let player_search_outcome = fit_player_goal_one(
+                        &player_to_score.offer_type,
+                        h1_probs,
+                        h2_probs,
+                        player,
+                        f64::min(init_estimate, 1.0),
+                        prob,
+                        intervals,
+                        max_total_goals,
+                    );
+                    (player.clone(), player_search_outcome.optimal_value)
+                }
+                _ => unreachable!(),
+            }
+        })
+        .collect();
+    let elapsed = start.elapsed();
+    debug!("{:?} fitting took {elapsed:?}", player_to_score.offer_type);
+    probs
+}
+
+fn side_ratios(
+    h1_probs: &BivariateProbs,
+    h2_probs: &BivariateProbs,
+    nil_all_draw_prob: f64,
+) -> (f64, f64) {
+    let home_rate = (h1_probs.home + h2_probs.home) / 2.0;
+    let away_rate = (h1_probs.away + h2_probs.away) / 2.0;
+    let common_rate = (h1_probs.common + h2_probs.common) / 2.0;
+    let rate_sum = home_rate + away_rate + common_rate;
+    let home_ratio = (home_rate + common_rate / 2.0) / rate_sum * (1.0 - nil_all_draw_prob);
+    let away_ratio = (away_rate + common_rate / 2.0) / rate_sum * (1.0 - nil_all_draw_prob);
+    (home_ratio, away_ratio)
+}
+
+fn fit_player_goal_one(
+    offer_type: &OfferType,
+    h1_goals: &BivariateProbs,
+    h2_goals: &BivariateProbs,
+    player: &Player,
+    init_estimate: f64,
+    expected_prob: f64,
+    intervals: u8,
+    max_total_goals: u16,
+) -> UnivariateDescentOutcome {
+    let mut config = Config {
+        intervals,
+        team_probs: TeamProbs {
+            h1_goals: h1_goals.clone(),
+            h2_goals: h2_goals.clone(),
+            assists: UnivariateProbs::default(),
+        },
+        player_probs: sv![(
+            player.clone(),
+            PlayerProbs {
+                goal: Some(0.0),
+                assist: None,
+            },
+        )],
+        prune_thresholds: PruneThresholds {
+            max_total_goals,
+            min_prob: GOALSCORER_MIN_PROB,
+        },
+        expansions: requirements(offer_type),
+    };
+    let outcome = Outcome::Player(player.clone());
+    univariate_descent(
+        &UnivariateDescentConfig {
+            init_value: init_estimate,
+            init_step: init_estimate * 0.1,
+            min_step: init_estimate * 0.0001,
+            max_steps: 100,
+            acceptable_residual: 1e-9,
+        },
+        |value| {
+            config.player_probs[0].1.goal = Some(value);
+            let exploration = explore(&config, 0..intervals);
+            let isolated_prob = isolate(
+                offer_type,
+                &outcome,
+                &exploration.prospects,
+                &exploration.player_lookup,
+            );
+            ERROR_TYPE.calculate(expected_prob, isolated_prob)
+        },
+    )
+}
+
 pub fn fit_anytime_assist_all(
     h1_probs: &BivariateProbs,
     h2_probs: &BivariateProbs,
@@ -822,7 +1361,7 @@ fn scoregrid_error(offers: &[&Offer], scoregrid: &Matrix<f64>) -> f64 {
     let mut residual = 0.0;
     for offer in offers {
         for (index, outcome) in offer.outcomes.items().iter().enumerate() {
-            let fitted_prob = outcome.gather(scoregrid);
+            let fitted_prob = gather(&offer.offer_type, outcome, scoregrid);
             let sample_prob = offer.market.probs[index];
             residual += ERROR_TYPE.calculate(sample_prob, fitted_prob);
         }
@@ -830,6 +1369,18 @@ fn scoregrid_error(offers: &[&Offer], scoregrid: &Matrix<f64>) -> f64 {
     residual
 }
 
+fn gather(offer_type: &OfferType, outcome: &Outcome, scoregrid: &Matrix<f64>) -> f64 {
+    match offer_type {
+        OfferType::TotalEvents(_, _)
+        | OfferType::TeamTotalEvents(_, _, _)
+        | OfferType::EventHandicap(_, _) => events::gather(offer_type, outcome, scoregrid),
+        OfferType::TeamTotalGoals(_, side, _) | OfferType::ExactTeamGoals(_, side, _) => {
+            outcome.gather_team(side, scoregrid)
+        }
+        _ => outcome.gather(scoregrid),
+    }
+}
+
 pub fn compute_error(sample_prices: &[f64], fitted_prices: &[f64], error_type: &ErrorType) -> f64 {
     let mut error_sum = 0.0;
     let mut counted = 0;
@@ -850,3 +1401,50 @@ pub struct FittingErrors {
     pub rmse: f64,
     pub rmsre: f64,
 }
+
+#[cfg(test)]
+mod tests {
+    use brumby::hash_lookup::HashLookup;
+    use brumby::market::{Market, Overround, OverroundMethod, PriceBounds};
+
+    use crate::domain::Score;
+
+    use super::*;
+
+    const PRICE_BOUNDS: PriceBounds = 1.001..=1001.0;
+    const FAIR: Overround = Overround {
+        method: OverroundMethod::Multiplicative,
+        value: 1.0,
+    };
+
+    fn correct_score_offer(scores: &[(u8, u8)], probs: Vec<f64>) -> Offer {
+        Offer {
+            offer_type: OfferType::CorrectScore(Period::FullTime),
+            outcomes: HashLookup::from(
+                scores
+                    .iter()
+                    .map(|&(home, away)| Outcome::Score(Score::new(home, away)))
+                    .collect::<Vec<_>>(),
+            ),
+            market: Market::frame(&FAIR, probs, &PRICE_BOUNDS),
+        }
+    }
+
+    #[test]
+    fn offer_error_penalises_zero_fitted_prob() {
+        let mut scoregrid = Matrix::allocate(3, 3);
+        scoregrid::from_univariate_poisson(1.2, 1.0, &mut scoregrid);
+
+        let within_probs = vec![scoregrid[(0, 0)], scoregrid[(1, 1)]];
+        let within = correct_score_offer(&[(0, 0), (1, 1)], within_probs);
+        assert_eq!(0.0, offer_error(&within, &scoregrid));
+
+        // the 5-0 score lies outside the scoregrid, and is penalised rather than skipped
+        let outside = correct_score_offer(
+            &[(0, 0), (1, 1), (5, 0)],
+            vec![scoregrid[(0, 0)], scoregrid[(1, 1)], 0.01],
+        );
+        let error = offer_error(&outside, &scoregrid);
+        assert_eq!(ZERO_PROB_PENALTY / 3.0, error);
+    }
+}
@@ -7,12 +7,17 @@ use brumby::hash_lookup::HashLookup;
 use brumby::stack_vec::StackVec;
 use brumby::sv;
 
-use crate::domain::{Player, Score, Side};
+use crate::domain::{Player, Score, Side, TimeBand};
 
 mod assist;
 pub mod query;
 
-pub const NUM_PLAYERS: usize = 3;
+/// The maximum number of named players that can be tracked in a single exploration. Player stats
+/// are stack-allocated, taking 3 bytes per player slot in every prospect (128 bytes per prospect
+/// at 16 players), irrespective of the number of players actually tracked. Measured against a
+/// limit of 3, the `cri_interval` and `cri_isolate` benches that track at most one player moved
+/// by -12% to +13%, which is within their run-to-run noise.
+pub const NUM_PLAYERS: usize = 16;
 pub const NUM_PLAYER_STATS: usize = NUM_PLAYERS + 1;
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
@@ -21,6 +26,17 @@ pub struct Prospect {
     pub ft_score: Score,
     pub stats: StackVec<PlayerStats, NUM_PLAYER_STATS>,
     pub first_scorer: Option<usize>,
+    pub last_scorer: Option<usize>,
+    /// The minute at the start of the interval in which the first goal was scored.
+    pub first_goal_time: Option<u8>,
+    /// A bit per minute, set at the start minute of each time band (of [Expansions::time_bands]) in
+    /// which at least one goal was scored.
+    pub scoring_bands: u128,
+    pub first_scoring_team: Option<Side>,
+    pub last_scoring_team: Option<Side>,
+    /// A bit per race, set at `n - 1` if the home side reached `n` goals before the away side.
+    /// Whether a race has been decided at all is evident from the full-time score.
+    pub races_won_by_home: u16,
 }
 impl Prospect {
     fn init(players: usize) -> Prospect {
@@ -30,6 +46,12 @@ impl Prospect {
             ft_score: Score::nil_all(),
             stats,
             first_scorer: None,
+            last_scorer: None,
+            first_goal_time: None,
+            scoring_bands: 0,
+            first_scoring_team: None,
+            last_scoring_team: None,
+            races_won_by_home: 0,
         }
     }
 
@@ -91,6 +113,15 @@ pub struct Expansions {
     pub player_split_goal_stats: bool,
     pub max_player_assists: u8,
     pub first_goalscorer: bool,
+    pub last_goalscorer: bool,
+    pub first_goal_time: bool,
+    /// A bit per minute, set at the start (and end) minute of each time band, or zero if goals are
+    /// not banded. Only the bands are tracked, irrespective of the number of intervals in them.
+    pub time_bands: u128,
+    pub first_scoring_team: bool,
+    pub last_scoring_team: bool,
+    /// Races to each number of goals, up to the one given, are decided by the order of goals.
+    pub max_race_goals: u8,
 }
 impl Expansions {
     fn validate(&self) {
@@ -105,13 +136,37 @@ impl Expansions {
                 || self.ht_score
                 || self.max_player_goals > 0
                 || self.first_goalscorer
-                || self.max_player_assists > 0,
+                || self.last_goalscorer
+                || self.max_player_assists > 0
+                || self.first_goal_time
+                || self.time_bands != 0
+                || self.first_scoring_team
+                || self.last_scoring_team
+                || self.max_race_goals > 0,
             "at least one expansion must be enabled"
-        )
+        );
+        if self.max_race_goals > 0 {
+            assert!(self.ft_score, "cannot expand races without the full-time score");
+            assert!(
+                self.max_race_goals as u32 <= u16::BITS,
+                "races to more than {} goals are not supported",
+                u16::BITS
+            );
+        }
     }
 
     pub fn requires_team_goal_probs(&self) -> bool {
-        self.ht_score || self.ft_score || self.max_player_goals > 0 || self.first_goalscorer || self.max_player_assists > 0
+        self.ht_score
+            || self.ft_score
+            || self.max_player_goals > 0
+            || self.first_goalscorer
+            || self.last_goalscorer
+            || self.max_player_assists > 0
+            || self.first_goal_time
+            || self.time_bands != 0
+            || self.first_scoring_team
+            || self.last_scoring_team
+            || self.max_race_goals > 0
     }
 
     pub fn requires_team_assist_probs(&self) -> bool {
@@ -119,7 +174,7 @@ impl Expansions {
     }
 
     pub fn requires_player_goal_probs(&self) -> bool {
-        self.max_player_goals > 0 || self.first_goalscorer
+        self.max_player_goals > 0 || self.first_goalscorer || self.last_goalscorer
     }
 
     pub fn requires_player_assist_probs(&self) -> bool {
@@ -134,10 +189,18 @@ impl Expansions {
             player_split_goal_stats: false,
             max_player_assists: 0,
             first_goalscorer: false,
+            last_goalscorer: false,
+            first_goal_time: false,
+            time_bands: 0,
+            first_scoring_team: false,
+            last_scoring_team: false,
+            max_race_goals: 0,
         }
     }
 }
 
+/// Expands everything except the timing of goals, which multiplies the number of prospects and is
+/// only expanded on request.
 impl Default for Expansions {
     fn default() -> Self {
         Self {
@@ -147,6 +210,12 @@ impl Default for Expansions {
             player_split_goal_stats: true,
             max_player_assists: u8::MAX,
             first_goalscorer: true,
+            last_goalscorer: true,
+            first_goal_time: false,
+            time_bands: 0,
+            first_scoring_team: false,
+            last_scoring_team: false,
+            max_race_goals: 0,
         }
     }
 }
@@ -162,6 +231,7 @@ impl Default for Expansions {
 //             player_split_goal_stats: self.player_split_goal_stats || rhs.player_split_goal_stats,
 //             max_player_assists: u8::max(self.max_player_assists, rhs.max_player_assists),
 //             first_goalscorer: self.first_goalscorer || rhs.first_goalscorer,
+//             last_goalscorer: self.last_goalscorer || rhs.last_goalscorer,
 //         }
 //     }
 // }
@@ -174,6 +244,12 @@ impl AddAssign for Expansions {
         self.player_split_goal_stats |= rhs.player_split_goal_stats;
         self.max_player_assists = u8::max(self.max_player_assists, rhs.max_player_assists);
         self.first_goalscorer |= rhs.first_goalscorer;
+        self.last_goalscorer |= rhs.last_goalscorer;
+        self.first_goal_time |= rhs.first_goal_time;
+        self.time_bands |= rhs.time_bands;
+        self.first_scoring_team |= rhs.first_scoring_team;
+        self.last_scoring_team |= rhs.last_scoring_team;
+        self.max_race_goals = u8::max(self.max_race_goals, rhs.max_race_goals);
     }
 }
 
@@ -311,6 +387,7 @@ pub fn explore(config: &Config, include_intervals: Range<u8>) -> Exploration {
             Half::First => &config.team_probs.h1_goals,
             Half::Second => &config.team_probs.h2_goals,
         };
+        let minute = (interval as u16 * TimeBand::MAX_MINUTE as u16 / config.intervals as u16) as u8;
 
         let neither_prob = 1.0 - params.home - params.away - params.common;
         let mut next_prospects = init_prospects((current_prospects.len() as f64 * 1.1) as usize);
@@ -333,6 +410,7 @@ pub fn explore(config: &Config, include_intervals: Range<u8>) -> Exploration {
             merge(
                 &config.expansions,
                 &half,
+                minute,
                 &current_prospect,
                 current_prob,
                 partial,
@@ -351,6 +429,7 @@ pub fn explore(config: &Config, include_intervals: Range<u8>) -> Exploration {
                         merge(
                             &config.expansions,
                             &half,
+                            minute,
                             &current_prospect,
                             current_prob,
                             PartialProspect {
@@ -376,6 +455,7 @@ pub fn explore(config: &Config, include_intervals: Range<u8>) -> Exploration {
                         merge(
                             &config.expansions,
                             &half,
+                            minute,
                             &current_prospect,
                             current_prob,
                             PartialProspect {
@@ -415,6 +495,7 @@ pub fn explore(config: &Config, include_intervals: Range<u8>) -> Exploration {
                                     merge(
                                         &config.expansions,
                                         &half,
+                                        minute,
                                         &current_prospect,
                                         current_prob,
                                         PartialProspect {
@@ -469,6 +550,7 @@ pub fn explore(config: &Config, include_intervals: Range<u8>) -> Exploration {
 fn merge(
     expansions: &Expansions,
     half: &Half,
+    minute: u8,
     current_prospect: &Prospect,
     current_prob: f64,
     partial: PartialProspect,
@@ -531,6 +613,70 @@ fn merge(
         }
     }
 
+    if partial.home_scorer.is_some() || partial.away_scorer.is_some() {
+        if expansions.first_goal_time && merged.first_goal_time.is_none() {
+            merged.first_goal_time = Some(minute);
+        }
+        // the band is marked by the latest boundary at or before the minute of the goal, if any
+        let boundaries = expansions.time_bands & ((2 << minute) - 1);
+        if boundaries != 0 {
+            merged.scoring_bands |= 1 << (u128::BITS - 1 - boundaries.leading_zeros());
+        }
+    }
+
+    if let Some(first_scoring_side) = partial.first_scoring_side {
+        if expansions.first_scoring_team && merged.first_scoring_team.is_none() {
+            merged.first_scoring_team = Some(first_scoring_side.clone());
+        }
+        if expansions.last_scoring_team {
+            // when both sides score in the same interval, the last goal goes to the side that didn't score first
+            merged.last_scoring_team = if partial.home_scorer.is_some() && partial.away_scorer.is_some() {
+                Some(match first_scoring_side {
+                    Side::Home => Side::Away,
+                    Side::Away => Side::Home,
+                })
+            } else {
+                Some(first_scoring_side.clone())
+            };
+        }
+        if expansions.max_race_goals > 0 {
+            let mut home_goals = current_prospect.ft_score.home;
+            let mut away_goals = current_prospect.ft_score.away;
+            let mut race_goal = |side: &Side| match side {
+                Side::Home => {
+                    home_goals += 1;
+                    if home_goals <= expansions.max_race_goals && away_goals < home_goals {
+                        merged.races_won_by_home |= 1 << (home_goals - 1);
+                    }
+                }
+                Side::Away => away_goals += 1,
+            };
+            race_goal(first_scoring_side);
+            if partial.home_scorer.is_some() && partial.away_scorer.is_some() {
+                race_goal(match first_scoring_side {
+                    Side::Home => &Side::Away,
+                    Side::Away => &Side::Home,
+                });
+            }
+        }
+    }
+
+    if expansions.last_goalscorer {
+        match (partial.home_scorer, partial.away_scorer) {
+            (Some(home_player), Some(away_player)) => {
+                // when both sides score in the same interval, the last goal goes to the side that didn't score first
+                merged.last_scorer = match partial.first_scoring_side.unwrap() {
+                    Side::Home => Some(away_player),
+                    Side::Away => Some(home_player),
+                };
+            }
+            (Some(player), None) | (None, Some(player)) => {
+                merged.last_scorer = Some(player);
+            }
+            (None, None) => {}
+        }
+    }
+
     if let Some(player) = partial.home_assister {
         if merged.stats[player].assists < expansions.max_player_assists {
             merged.stats[player].assists += 1;
@@ -1,14 +1,30 @@
 use brumby::hash_lookup::HashLookup;
+use rustc_hash::FxHashMap;
 
-use crate::domain::{OfferType, Outcome, Player};
+use crate::domain::settlement::SettlementProbs;
+use crate::domain::{OfferType, Outcome, Period, Player};
 use crate::interval::{Expansions, Prospect, Prospects};
 
 mod anytime_assist;
 mod anytime_goalscorer;
+mod cast;
 mod correct_score;
+mod double_chance;
+mod events;
+mod first_goal_time;
 mod first_goalscorer;
+mod halves;
+mod half_time_full_time;
+mod last_goalscorer;
+mod player_to_score;
+mod player_to_score_in_half;
+mod race;
+mod scoring_team;
+mod team_goals;
+mod time_band;
 mod total_goals;
 mod win_draw;
+mod winning_margin;
 
 #[derive(Debug)]
 pub enum QuerySpec {
@@ -16,6 +32,7 @@ pub enum QuerySpec {
     PlayerLookup(usize),
     NoFirstGoalscorer,
     NoAnytimeGoalscorer,
+    NoLastGoalscorer,
     NoAnytimeAssist,
 }
 
@@ -24,14 +41,47 @@ pub enum QuerySpec {
 pub fn requirements(offer_type: &OfferType) -> Expansions {
     match offer_type {
         OfferType::HeadToHead(period, _) => win_draw::requirements(period),
-        OfferType::TotalGoals(period, _) => total_goals::requirements(period),
+        OfferType::TotalGoals(period, _)
+        | OfferType::SplitTotalGoals(period, _, _)
+        | OfferType::ExactTotalGoals(period, _)
+        | OfferType::OddEvenGoals(period) => total_goals::requirements(period),
         OfferType::CorrectScore(period) => correct_score::requirements(period),
         OfferType::AsianHandicap(period, _) => win_draw::requirements(period),
         OfferType::FirstGoalscorer => first_goalscorer::requirements(),
         OfferType::AnytimeGoalscorer => anytime_goalscorer::requirements(),
         OfferType::PlayerShotsOnTarget(_) => unimplemented!(),
         OfferType::AnytimeAssist => anytime_assist::requirements(),
-        OfferType::DrawNoBet(_) | OfferType::SplitHandicap(_, _, _) => {
+        OfferType::HalfTimeFullTime => half_time_full_time::requirements(),
+        OfferType::TeamTotalGoals(period, _, _) | OfferType::ExactTeamGoals(period, _, _) => {
+            team_goals::requirements(period)
+        }
+        OfferType::WinningMargin(period) | OfferType::DoubleChance(period) => {
+            win_draw::requirements(period)
+        }
+        OfferType::LastGoalscorer => last_goalscorer::requirements(),
+        OfferType::PlayerToScore(at_least) => player_to_score::requirements(*at_least),
+        OfferType::PlayerToScoreInHalf(_) | OfferType::PlayerToScoreInBothHalves => {
+            player_to_score_in_half::requirements(1)
+        }
+        OfferType::PlayerToScoreMultipleInHalf(_, at_least) => {
+            player_to_score_in_half::requirements(*at_least)
+        }
+        OfferType::TimeOfFirstGoal => first_goal_time::requirements(),
+        OfferType::GoalInTimeBand(time_band) => time_band::requirements(time_band),
+        OfferType::GoalInBothHalves | OfferType::HighestScoringHalf => halves::requirements(),
+        OfferType::FirstTeamToScore => scoring_team::requirements(true),
+        OfferType::LastTeamToScore => scoring_team::requirements(false),
+        OfferType::RaceToGoals(goals) => race::requirements(*goals),
+        OfferType::Scorecast => cast::requirements(true),
+        OfferType::Wincast => cast::requirements(false),
+        OfferType::TotalEvents(_, _)
+        | OfferType::TeamTotalEvents(_, _, _)
+        | OfferType::EventHandicap(_, _)
+        | OfferType::PlayerEvents(_, _) => events::requirements(),
+        OfferType::DrawNoBet(_)
+        | OfferType::SplitHandicap(_, _, _)
+        | OfferType::ToQualify
+        | OfferType::MethodOfVictory => {
             panic!("unsupported auxiliary {offer_type:?}")
         }
     }
@@ -46,14 +96,39 @@ pub fn prepare(
 ) -> QuerySpec {
     match offer_type {
         OfferType::HeadToHead(_, _) => win_draw::prepare(),
-        OfferType::TotalGoals(_, _) => total_goals::prepare(),
+        OfferType::TotalGoals(_, _)
+        | OfferType::ExactTotalGoals(_, _)
+        | OfferType::OddEvenGoals(_) => total_goals::prepare(),
         OfferType::CorrectScore(_) => correct_score::prepare(),
         OfferType::AsianHandicap(_, _) => win_draw::prepare(),
         OfferType::FirstGoalscorer => first_goalscorer::prepare(outcome, player_lookup),
         OfferType::AnytimeGoalscorer => anytime_goalscorer::prepare(outcome, player_lookup),
         OfferType::PlayerShotsOnTarget(_) => unimplemented!(),
         OfferType::AnytimeAssist => anytime_assist::prepare(outcome, player_lookup),
-        OfferType::DrawNoBet(_) | OfferType::SplitHandicap(_, _, _) => {
+        OfferType::HalfTimeFullTime => half_time_full_time::prepare(),
+        OfferType::TeamTotalGoals(_, _, _) | OfferType::ExactTeamGoals(_, _, _) => team_goals::prepare(),
+        OfferType::WinningMargin(_) => winning_margin::prepare(),
+        OfferType::DoubleChance(_) => double_chance::prepare(),
+        OfferType::LastGoalscorer => last_goalscorer::prepare(outcome, player_lookup),
+        OfferType::PlayerToScore(_) => player_to_score::prepare(outcome, player_lookup),
+        OfferType::PlayerToScoreInHalf(_)
+        | OfferType::PlayerToScoreInBothHalves
+        | OfferType::PlayerToScoreMultipleInHalf(_, _) => player_to_score_in_half::prepare(outcome, player_lookup),
+        OfferType::TimeOfFirstGoal => first_goal_time::prepare(),
+        OfferType::GoalInTimeBand(_) => time_band::prepare(),
+        OfferType::GoalInBothHalves | OfferType::HighestScoringHalf => halves::prepare(),
+        OfferType::FirstTeamToScore | OfferType::LastTeamToScore => scoring_team::prepare(),
+        OfferType::RaceToGoals(_) => race::prepare(),
+        OfferType::Scorecast | OfferType::Wincast => cast::prepare(outcome, player_lookup),
+        OfferType::TotalEvents(_, _)
+        | OfferType::TeamTotalEvents(_, _, _)
+        | OfferType::EventHandicap(_, _)
+        | OfferType::PlayerEvents(_, _) => events::prepare(),
+        OfferType::DrawNoBet(_)
+        | OfferType::SplitHandicap(_, _, _)
+        | OfferType::SplitTotalGoals(_, _, _)
+        | OfferType::ToQualify
+        | OfferType::MethodOfVictory => {
             panic!("unsupported auxiliary {offer_type:?}")
         }
     }
@@ -69,14 +144,44 @@ pub fn filter(
 ) -> bool {
     match offer_type {
         OfferType::HeadToHead(period, _) => win_draw::filter(period, outcome, prospect),
-        OfferType::TotalGoals(period, _) => total_goals::filter(period, outcome, prospect),
+        OfferType::TotalGoals(period, _)
+        | OfferType::ExactTotalGoals(period, _)
+        | OfferType::OddEvenGoals(period) => total_goals::filter(period, outcome, prospect),
         OfferType::CorrectScore(period) => correct_score::filter(period, outcome, prospect),
         OfferType::AsianHandicap(period, _) => win_draw::filter(period, outcome, prospect),
         OfferType::AnytimeGoalscorer => anytime_goalscorer::filter(query, prospect),
         OfferType::FirstGoalscorer => first_goalscorer::filter(query, prospect),
         OfferType::PlayerShotsOnTarget(_) => unimplemented!(),
         OfferType::AnytimeAssist => anytime_assist::filter(query, prospect),
-        OfferType::DrawNoBet(_) | OfferType::SplitHandicap(_, _, _) => {
+        OfferType::HalfTimeFullTime => half_time_full_time::filter(outcome, prospect),
+        OfferType::TeamTotalGoals(period, side, _) | OfferType::ExactTeamGoals(period, side, _) => {
+            team_goals::filter(period, side, outcome, prospect)
+        }
+        OfferType::WinningMargin(period) => winning_margin::filter(period, outcome, prospect),
+        OfferType::DoubleChance(period) => double_chance::filter(period, outcome, prospect),
+        OfferType::LastGoalscorer => last_goalscorer::filter(query, prospect),
+        OfferType::PlayerToScore(at_least) => player_to_score::filter(*at_least, query, prospect),
+        OfferType::PlayerToScoreInHalf(period) => player_to_score_in_half::filter(period, 1, query, prospect),
+        OfferType::PlayerToScoreInBothHalves => player_to_score_in_half::filter_both_halves(query, prospect),
+        OfferType::PlayerToScoreMultipleInHalf(period, at_least) => {
+            player_to_score_in_half::filter(period, *at_least, query, prospect)
+        }
+        OfferType::TimeOfFirstGoal => first_goal_time::filter(outcome, prospect),
+        OfferType::GoalInTimeBand(time_band) => time_band::filter(time_band, outcome, prospect),
+        OfferType::GoalInBothHalves | OfferType::HighestScoringHalf => halves::filter(outcome, prospect),
+        OfferType::FirstTeamToScore => scoring_team::filter(&prospect.first_scoring_team, outcome),
+        OfferType::LastTeamToScore => scoring_team::filter(&prospect.last_scoring_team, outcome),
+        OfferType::RaceToGoals(goals) => race::filter(*goals, outcome, prospect),
+        OfferType::Scorecast | OfferType::Wincast => cast::filter(outcome, query, prospect),
+        OfferType::TotalEvents(_, _)
+        | OfferType::TeamTotalEvents(_, _, _)
+        | OfferType::EventHandicap(_, _)
+        | OfferType::PlayerEvents(_, _) => events::filter(),
+        OfferType::DrawNoBet(_)
+        | OfferType::SplitHandicap(_, _, _)
+        | OfferType::SplitTotalGoals(_, _, _)
+        | OfferType::ToQualify
+        | OfferType::MethodOfVictory => {
             panic!("unsupported auxiliary {offer_type:?}")
         }
     }
@@ -104,6 +209,18 @@ pub fn isolate_set(
     selections: &[(OfferType, Outcome)],
     prospects: &Prospects,
     player_lookup: &HashLookup<Player>,
+) -> f64 {
+    isolate_set_weighted(selections, prospects, player_lookup, |_| 1.0)
+}
+
+/// As per [isolate_set], with the probability of each matching prospect further weighted.
+#[must_use]
+#[inline]
+pub fn isolate_set_weighted(
+    selections: &[(OfferType, Outcome)],
+    prospects: &Prospects,
+    player_lookup: &HashLookup<Player>,
+    weight: impl Fn(&Prospect) -> f64,
 ) -> f64 {
     let queries = selections
         .iter()
@@ -122,10 +239,50 @@ pub fn isolate_set(
                 .iter()
                 .any(|(offer_type, outcome, query)| !filter(offer_type, outcome, query, prospect))
         })
-        .map(|(_, prospect_prob)| prospect_prob)
+        .map(|(prospect, prospect_prob)| prospect_prob * weight(prospect))
         .sum()
 }
 
+/// Isolates the probabilities of all scorecast or wincast outcomes of one player in a single pass
+/// over the prospects, keyed by outcome. Outcomes that cannot occur are absent.
+#[must_use]
+pub fn isolate_cast(
+    offer_type: &OfferType,
+    player: &Player,
+    prospects: &Prospects,
+    player_lookup: &HashLookup<Player>,
+) -> FxHashMap<Outcome, f64> {
+    cast::tabulate(offer_type, player, prospects, player_lookup)
+}
+
+/// Isolates the probability of each settlement of an outcome on a whole or quarter line, given
+/// the score in the period that the offer applies to.
+#[must_use]
+pub fn isolate_settlement(
+    offer_type: &OfferType,
+    outcome: &Outcome,
+    prospects: &Prospects,
+) -> SettlementProbs {
+    let period = match offer_type {
+        OfferType::TotalGoals(period, _)
+        | OfferType::SplitTotalGoals(period, _, _)
+        | OfferType::HeadToHead(period, _)
+        | OfferType::AsianHandicap(period, _)
+        | OfferType::SplitHandicap(period, _, _) => period,
+        _ => panic!("{offer_type:?} unsupported"),
+    };
+    let mut settlement_probs = SettlementProbs::default();
+    for (prospect, prob) in prospects.iter() {
+        let score = match period {
+            Period::FirstHalf => prospect.ht_score.clone(),
+            Period::SecondHalf => prospect.h2_score(),
+            Period::FullTime => prospect.ft_score.clone(),
+        };
+        settlement_probs.add(&outcome.settle(&score), *prob);
+    }
+    settlement_probs
+}
+
 #[cfg(test)]
 mod tests {
     use crate::domain::{DrawHandicap, Period, Score, Side, WinHandicap};
@@ -164,6 +321,12 @@ mod tests {
                     player_split_goal_stats: false,
                     max_player_assists: 0,
                     first_goalscorer: false,
+                    last_goalscorer: false,
+                    first_goal_time: false,
+                    time_bands: 0,
+                    first_scoring_team: false,
+                    last_scoring_team: false,
+                    max_race_goals: 0,
                 },
             },
             0..4,
@@ -217,6 +380,12 @@ mod tests {
                     player_split_goal_stats: false,
                     max_player_assists: 0,
                     first_goalscorer: false,
+                    last_goalscorer: false,
+                    first_goal_time: false,
+                    time_bands: 0,
+                    first_scoring_team: false,
+                    last_scoring_team: false,
+                    max_race_goals: 0,
                 },
             },
             0..4,
@@ -290,6 +459,12 @@ mod tests {
                     player_split_goal_stats: false,
                     max_player_assists: 0,
                     first_goalscorer: false,
+                    last_goalscorer: false,
+                    first_goal_time: false,
+                    time_bands: 0,
+                    first_scoring_team: false,
+                    last_scoring_team: false,
+                    max_race_goals: 0,
                 },
             },
             0..4,
@@ -10,6 +10,12 @@ pub(crate) fn requirements() -> Expansions {
         player_split_goal_stats: false,
         max_player_assists: 0,
         first_goalscorer: false,
+        last_goalscorer: false,
+        first_goal_time: false,
+        time_bands: 0,
+        first_scoring_team: false,
+        last_scoring_team: false,
+        max_race_goals: 0,
     }
 }
 
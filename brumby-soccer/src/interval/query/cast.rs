@@ -0,0 +1,155 @@
+use rustc_hash::FxHashMap;
+
+use super::*;
+use crate::domain::{MatchResult, Score};
+
+#[inline]
+#[must_use]
+pub(crate) fn requirements(first_goalscorer: bool) -> Expansions {
+    Expansions {
+        ht_score: false,
+        ft_score: true,
+        max_player_goals: if first_goalscorer { 0 } else { 1 },
+        player_split_goal_stats: false,
+        max_player_assists: 0,
+        first_goalscorer,
+        last_goalscorer: false,
+        first_goal_time: false,
+        time_bands: 0,
+        first_scoring_team: false,
+        last_scoring_team: false,
+        max_race_goals: 0,
+    }
+}
+
+#[inline]
+#[must_use]
+pub(crate) fn prepare(
+    outcome: &Outcome,
+    player_lookup: &HashLookup<Player>,
+) -> QuerySpec {
+    match outcome {
+        Outcome::Scorecast(player, _) | Outcome::Wincast(player, _) => {
+            QuerySpec::PlayerLookup(player_lookup.index_of(player).unwrap())
+        }
+        _ => panic!("{outcome:?} unsupported"),
+    }
+}
+
+#[inline]
+#[must_use]
+pub(crate) fn filter(outcome: &Outcome, query: &QuerySpec, prospect: &Prospect) -> bool {
+    match (outcome, query) {
+        (Outcome::Scorecast(_, score), QuerySpec::PlayerLookup(target_player)) => {
+            prospect.first_scorer == Some(*target_player) && &prospect.ft_score == score
+        }
+        (Outcome::Wincast(_, match_result), QuerySpec::PlayerLookup(target_player)) => {
+            scored(prospect, *target_player)
+                && &MatchResult::from_score(&prospect.ft_score) == match_result
+        }
+        _ => panic!("{outcome:?} unsupported"),
+    }
+}
+
+#[inline]
+fn scored(prospect: &Prospect, player: usize) -> bool {
+    let stats = &prospect.stats[player];
+    stats.h1.goals > 0 || stats.h2.goals > 0
+}
+
+pub(crate) fn tabulate(
+    offer_type: &OfferType,
+    player: &Player,
+    prospects: &Prospects,
+    player_lookup: &HashLookup<Player>,
+) -> FxHashMap<Outcome, f64> {
+    let target_player = player_lookup.index_of(player).unwrap();
+    match offer_type {
+        OfferType::Scorecast => {
+            let mut probs_by_score = FxHashMap::<&Score, f64>::default();
+            for (prospect, prob) in prospects {
+                if prospect.first_scorer == Some(target_player) {
+                    *probs_by_score.entry(&prospect.ft_score).or_default() += prob;
+                }
+            }
+            probs_by_score
+                .into_iter()
+                .map(|(score, prob)| (Outcome::Scorecast(player.clone(), score.clone()), prob))
+                .collect()
+        }
+        OfferType::Wincast => {
+            let mut probs_by_result = [0.0; 3];
+            for (prospect, prob) in prospects {
+                if scored(prospect, target_player) {
+                    let index = MatchResult::ALL
+                        .iter()
+                        .position(|match_result| match_result == &MatchResult::from_score(&prospect.ft_score))
+                        .unwrap();
+                    probs_by_result[index] += prob;
+                }
+            }
+            MatchResult::ALL
+                .iter()
+                .zip(probs_by_result)
+                .map(|(match_result, prob)| (Outcome::Wincast(player.clone(), match_result.clone()), prob))
+                .collect()
+        }
+        _ => panic!("{offer_type:?} unsupported"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_float_eq::*;
+
+    use crate::domain::Side;
+
+    use super::*;
+
+    fn create_test_prospect(first_scorer: usize, ft_score: Score, scorers: &[usize]) -> Prospect {
+        let mut prospect = Prospect::init(3);
+        prospect.first_scorer = Some(first_scorer);
+        prospect.ft_score = ft_score;
+        for &scorer in scorers {
+            prospect.stats[scorer].h2.goals = 1;
+        }
+        prospect
+    }
+
+    #[test]
+    fn tabulate_agrees_with_isolate() {
+        let player_lookup = HashLookup::from(vec![
+            Player::Named(Side::Home, "Markos".into()),
+            Player::Named(Side::Away, "Nikos".into()),
+            Player::Other,
+        ]);
+        let mut prospects = Prospects::default();
+        prospects.insert(create_test_prospect(0, Score::new(1, 0), &[0]), 0.3);
+        prospects.insert(create_test_prospect(0, Score::new(1, 1), &[0, 1]), 0.2);
+        prospects.insert(create_test_prospect(1, Score::new(1, 1), &[0, 1]), 0.1);
+        prospects.insert(create_test_prospect(1, Score::new(0, 1), &[1]), 0.15);
+        prospects.insert(create_test_prospect(2, Score::new(2, 0), &[0, 2]), 0.25);
+
+        for offer_type in [OfferType::Scorecast, OfferType::Wincast] {
+            for player in player_lookup.items() {
+                let table = tabulate(&offer_type, player, &prospects, &player_lookup);
+                for (outcome, prob) in &table {
+                    assert_float_absolute_eq!(
+                        isolate(&offer_type, outcome, &prospects, &player_lookup),
+                        *prob
+                    );
+                }
+            }
+        }
+
+        let markos = Player::Named(Side::Home, "Markos".into());
+        let table = tabulate(&OfferType::Scorecast, &markos, &prospects, &player_lookup);
+        assert_eq!(2, table.len());
+        assert_float_absolute_eq!(0.2, table[&Outcome::Scorecast(markos.clone(), Score::new(1, 1))]);
+
+        let table = tabulate(&OfferType::Wincast, &markos, &prospects, &player_lookup);
+        assert_float_absolute_eq!(0.55, table[&Outcome::Wincast(markos.clone(), MatchResult::Win(Side::Home))]);
+        assert_float_absolute_eq!(0.3, table[&Outcome::Wincast(markos.clone(), MatchResult::Draw)]);
+        assert_float_absolute_eq!(0.0, table[&Outcome::Wincast(markos, MatchResult::Win(Side::Away))]);
+    }
+}
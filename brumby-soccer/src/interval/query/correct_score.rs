@@ -12,6 +12,12 @@ pub(crate) fn requirements(period: &Period) -> Expansions {
             player_split_goal_stats: false,
             max_player_assists: 0,
             first_goalscorer: false,
+            last_goalscorer: false,
+            first_goal_time: false,
+            time_bands: 0,
+            first_scoring_team: false,
+            last_scoring_team: false,
+            max_race_goals: 0,
         },
         Period::SecondHalf => Expansions {
             ht_score: true,
@@ -20,6 +26,12 @@ pub(crate) fn requirements(period: &Period) -> Expansions {
             player_split_goal_stats: false,
             max_player_assists: 0,
             first_goalscorer: false,
+            last_goalscorer: false,
+            first_goal_time: false,
+            time_bands: 0,
+            first_scoring_team: false,
+            last_scoring_team: false,
+            max_race_goals: 0,
         },
         Period::FullTime => Expansions {
             ht_score: false,
@@ -28,6 +40,12 @@ pub(crate) fn requirements(period: &Period) -> Expansions {
             player_split_goal_stats: false,
             max_player_assists: 0,
             first_goalscorer: false,
+            last_goalscorer: false,
+            first_goal_time: false,
+            time_bands: 0,
+            first_scoring_team: false,
+            last_scoring_team: false,
+            max_race_goals: 0,
         },
     }
 }
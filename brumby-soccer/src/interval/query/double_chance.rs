@@ -0,0 +1,87 @@
+use super::*;
+use crate::domain::{MatchResult, Period};
+
+#[inline]
+#[must_use]
+pub(crate) fn prepare() -> QuerySpec {
+    QuerySpec::Stateless
+}
+
+#[inline]
+#[must_use]
+pub(crate) fn filter(period: &Period, outcome: &Outcome, prospect: &Prospect) -> bool {
+    match outcome {
+        Outcome::DoubleChance(first, second) => {
+            let result = match period {
+                Period::FirstHalf => MatchResult::from_score(&prospect.ht_score),
+                Period::SecondHalf => MatchResult::from_score(&prospect.h2_score()),
+                Period::FullTime => MatchResult::from_score(&prospect.ft_score),
+            };
+            result == *first || result == *second
+        }
+        _ => panic!("{outcome:?} unsupported"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Score, Side};
+    use crate::interval::Exploration;
+    use assert_float_eq::*;
+
+    fn create_test_exploration() -> Exploration {
+        let mut prospects = Prospects::default();
+        for (ht_score, ft_score, prob) in [
+            (Score::new(0, 0), Score::new(0, 0), 0.1),
+            (Score::new(0, 0), Score::new(1, 0), 0.2),
+            (Score::new(1, 0), Score::new(1, 0), 0.3),
+            (Score::new(1, 0), Score::new(1, 1), 0.15),
+            (Score::new(0, 1), Score::new(0, 2), 0.25),
+        ] {
+            prospects.insert(
+                Prospect {
+                    ht_score,
+                    ft_score,
+                    stats: Default::default(),
+                    first_scorer: None,
+                    last_scorer: None,
+                    first_goal_time: None,
+                    scoring_bands: 0,
+                    first_scoring_team: None,
+                    last_scoring_team: None,
+                    races_won_by_home: 0,
+                },
+                prob,
+            );
+        }
+        Exploration {
+            player_lookup: HashLookup::default(),
+            prospects,
+            pruned: 0.0,
+        }
+    }
+
+    #[test]
+    pub fn gather() {
+        let exploration = create_test_exploration();
+        let expected = [
+            (Period::FullTime, MatchResult::Win(Side::Home), MatchResult::Draw, 0.75),
+            (Period::FullTime, MatchResult::Win(Side::Home), MatchResult::Win(Side::Away), 0.75),
+            (Period::FullTime, MatchResult::Draw, MatchResult::Win(Side::Away), 0.5),
+            (Period::FirstHalf, MatchResult::Draw, MatchResult::Win(Side::Away), 0.55),
+            (Period::SecondHalf, MatchResult::Win(Side::Home), MatchResult::Win(Side::Away), 0.6),
+        ];
+        for (period, first, second, expected_prob) in expected {
+            assert_float_absolute_eq!(
+                expected_prob,
+                isolate(
+                    &OfferType::DoubleChance(period),
+                    &Outcome::DoubleChance(first, second),
+                    &exploration.prospects,
+                    &exploration.player_lookup
+                )
+            );
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use super::*;
+
+/// Offers on corners and bookings are resolved by the event model rather than the prospects. The
+/// full-time score is required so that the events may be coupled to the total goals in a multi.
+#[inline]
+#[must_use]
+pub(crate) fn requirements() -> Expansions {
+    Expansions {
+        ft_score: true,
+        ..Expansions::empty()
+    }
+}
+
+#[inline]
+#[must_use]
+pub(crate) fn prepare() -> QuerySpec {
+    QuerySpec::Stateless
+}
+
+/// Admits every prospect, leaving the event outcome to be weighed separately.
+#[inline]
+#[must_use]
+pub(crate) fn filter() -> bool {
+    true
+}
@@ -0,0 +1,39 @@
+use super::*;
+
+#[inline]
+#[must_use]
+pub(crate) fn requirements() -> Expansions {
+    Expansions {
+        ht_score: false,
+        ft_score: false,
+        max_player_goals: 0,
+        player_split_goal_stats: false,
+        max_player_assists: 0,
+        first_goalscorer: false,
+        last_goalscorer: false,
+        first_goal_time: true,
+        time_bands: 0,
+        first_scoring_team: false,
+        last_scoring_team: false,
+        max_race_goals: 0,
+    }
+}
+
+#[inline]
+#[must_use]
+pub(crate) fn prepare() -> QuerySpec {
+    QuerySpec::Stateless
+}
+
+#[inline]
+#[must_use]
+pub(crate) fn filter(outcome: &Outcome, prospect: &Prospect) -> bool {
+    match outcome {
+        Outcome::FirstGoalIn(time_band) => prospect
+            .first_goal_time
+            .map(|minute| time_band.contains(minute))
+            .unwrap_or(false),
+        Outcome::None => prospect.first_goal_time.is_none(),
+        _ => panic!("{outcome:?} unsupported"),
+    }
+}
@@ -0,0 +1,107 @@
+use super::*;
+use crate::domain::MatchResult;
+
+#[inline]
+#[must_use]
+pub(crate) fn requirements() -> Expansions {
+    Expansions {
+        ht_score: true,
+        ft_score: true,
+        max_player_goals: 0,
+        player_split_goal_stats: false,
+        max_player_assists: 0,
+        first_goalscorer: false,
+        last_goalscorer: false,
+        first_goal_time: false,
+        time_bands: 0,
+        first_scoring_team: false,
+        last_scoring_team: false,
+        max_race_goals: 0,
+    }
+}
+
+#[inline]
+#[must_use]
+pub(crate) fn prepare() -> QuerySpec {
+    QuerySpec::Stateless
+}
+
+#[inline]
+#[must_use]
+pub(crate) fn filter(outcome: &Outcome, prospect: &Prospect) -> bool {
+    match outcome {
+        Outcome::HalfTimeFullTime(ht_result, ft_result) => {
+            MatchResult::from_score(&prospect.ht_score) == *ht_result
+                && MatchResult::from_score(&prospect.ft_score) == *ft_result
+        }
+        _ => panic!("{outcome:?} unsupported"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Score, Side};
+    use crate::interval::Exploration;
+    use assert_float_eq::*;
+
+    fn create_test_exploration() -> Exploration {
+        let mut prospects = Prospects::default();
+        for (ht_score, ft_score, prob) in [
+            (Score::new(0, 0), Score::new(0, 0), 0.1),
+            (Score::new(0, 0), Score::new(1, 0), 0.2),
+            (Score::new(1, 0), Score::new(1, 0), 0.3),
+            (Score::new(1, 0), Score::new(1, 1), 0.15),
+            (Score::new(1, 0), Score::new(1, 2), 0.05),
+            (Score::new(0, 1), Score::new(0, 2), 0.2),
+        ] {
+            prospects.insert(
+                Prospect {
+                    ht_score,
+                    ft_score,
+                    stats: Default::default(),
+                    first_scorer: None,
+                    last_scorer: None,
+                    first_goal_time: None,
+                    scoring_bands: 0,
+                    first_scoring_team: None,
+                    last_scoring_team: None,
+                    races_won_by_home: 0,
+                },
+                prob,
+            );
+        }
+        Exploration {
+            player_lookup: HashLookup::default(),
+            prospects,
+            pruned: 0.0,
+        }
+    }
+
+    #[test]
+    pub fn gather() {
+        let exploration = create_test_exploration();
+        let expected = [
+            (MatchResult::Draw, MatchResult::Draw, 0.1),
+            (MatchResult::Draw, MatchResult::Win(Side::Home), 0.2),
+            (MatchResult::Draw, MatchResult::Win(Side::Away), 0.0),
+            (MatchResult::Win(Side::Home), MatchResult::Win(Side::Home), 0.3),
+            (MatchResult::Win(Side::Home), MatchResult::Draw, 0.15),
+            (MatchResult::Win(Side::Home), MatchResult::Win(Side::Away), 0.05),
+            (MatchResult::Win(Side::Away), MatchResult::Win(Side::Away), 0.2),
+            (MatchResult::Win(Side::Away), MatchResult::Draw, 0.0),
+            (MatchResult::Win(Side::Away), MatchResult::Win(Side::Home), 0.0),
+        ];
+        for (ht_result, ft_result, expected_prob) in expected {
+            assert_float_absolute_eq!(
+                expected_prob,
+                isolate(
+                    &OfferType::HalfTimeFullTime,
+                    &Outcome::HalfTimeFullTime(ht_result, ft_result),
+                    &exploration.prospects,
+                    &exploration.player_lookup
+                )
+            );
+        }
+    }
+}
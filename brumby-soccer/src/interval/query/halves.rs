@@ -0,0 +1,104 @@
+//! Comparisons of the goals scored in each half, common to `GoalInBothHalves` and
+//! `HighestScoringHalf` offer types.
+
+use std::cmp::Ordering;
+
+use super::*;
+
+#[inline]
+#[must_use]
+pub(crate) fn requirements() -> Expansions {
+    Expansions {
+        ht_score: true,
+        ft_score: true,
+        max_player_goals: 0,
+        player_split_goal_stats: false,
+        max_player_assists: 0,
+        first_goalscorer: false,
+        last_goalscorer: false,
+        first_goal_time: false,
+        time_bands: 0,
+        first_scoring_team: false,
+        last_scoring_team: false,
+        max_race_goals: 0,
+    }
+}
+
+#[inline]
+#[must_use]
+pub(crate) fn prepare() -> QuerySpec {
+    QuerySpec::Stateless
+}
+
+#[inline]
+#[must_use]
+pub(crate) fn filter(outcome: &Outcome, prospect: &Prospect) -> bool {
+    let h1_goals = prospect.ht_score.total();
+    let h2_goals = prospect.h2_score().total();
+    match outcome {
+        Outcome::Yes => h1_goals > 0 && h2_goals > 0,
+        Outcome::No => h1_goals == 0 || h2_goals == 0,
+        Outcome::Half(Period::FirstHalf) => h1_goals > h2_goals,
+        Outcome::Half(Period::SecondHalf) => h2_goals > h1_goals,
+        Outcome::None => h1_goals.cmp(&h2_goals) == Ordering::Equal,
+        _ => panic!("{outcome:?} unsupported"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{OfferType, Score};
+    use crate::interval::Exploration;
+    use assert_float_eq::*;
+
+    fn create_test_exploration() -> Exploration {
+        let mut prospects = Prospects::default();
+        for (ht_score, ft_score, prob) in [
+            (Score::new(0, 0), Score::new(0, 0), 0.1),
+            (Score::new(0, 0), Score::new(1, 0), 0.2),
+            (Score::new(1, 0), Score::new(1, 0), 0.3),
+            (Score::new(1, 0), Score::new(1, 1), 0.15),
+            (Score::new(1, 0), Score::new(1, 2), 0.05),
+            (Score::new(0, 1), Score::new(0, 2), 0.2),
+        ] {
+            prospects.insert(
+                Prospect {
+                    ht_score,
+                    ft_score,
+                    stats: Default::default(),
+                    first_scorer: None,
+                    last_scorer: None,
+                    first_goal_time: None,
+                    scoring_bands: 0,
+                    first_scoring_team: None,
+                    last_scoring_team: None,
+                    races_won_by_home: 0,
+                },
+                prob,
+            );
+        }
+        Exploration {
+            player_lookup: HashLookup::default(),
+            prospects,
+            pruned: 0.0,
+        }
+    }
+
+    #[test]
+    fn goal_in_both_halves() {
+        let exploration = create_test_exploration();
+        let isolate = |outcome| isolate(&OfferType::GoalInBothHalves, &outcome, &exploration.prospects, &exploration.player_lookup);
+        assert_float_absolute_eq!(0.4, isolate(Outcome::Yes));
+        assert_float_absolute_eq!(0.6, isolate(Outcome::No));
+    }
+
+    #[test]
+    fn highest_scoring_half() {
+        let exploration = create_test_exploration();
+        let isolate = |outcome| isolate(&OfferType::HighestScoringHalf, &outcome, &exploration.prospects, &exploration.player_lookup);
+        assert_float_absolute_eq!(0.3, isolate(Outcome::Half(Period::FirstHalf)));
+        assert_float_absolute_eq!(0.25, isolate(Outcome::Half(Period::SecondHalf)));
+        assert_float_absolute_eq!(0.45, isolate(Outcome::None));
+    }
+}
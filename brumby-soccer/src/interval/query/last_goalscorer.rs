@@ -0,0 +1,48 @@
+use super::*;
+
+#[inline]
+#[must_use]
+pub(crate) fn requirements() -> Expansions {
+    Expansions {
+        ht_score: false,
+        ft_score: false,
+        max_player_goals: 0,
+        player_split_goal_stats: false,
+        max_player_assists: 0,
+        first_goalscorer: false,
+        last_goalscorer: true,
+        first_goal_time: false,
+        time_bands: 0,
+        first_scoring_team: false,
+        last_scoring_team: false,
+        max_race_goals: 0,
+    }
+}
+
+#[inline]
+#[must_use]
+pub(crate) fn prepare(
+    outcome: &Outcome,
+    player_lookup: &HashLookup<Player>,
+) -> QuerySpec {
+    match outcome {
+        Outcome::Player(player) => {
+            QuerySpec::PlayerLookup(player_lookup.index_of(player).unwrap())
+        }
+        Outcome::None => QuerySpec::NoLastGoalscorer,
+        _ => panic!("{outcome:?} unsupported"),
+    }
+}
+
+#[inline]
+#[must_use]
+pub(crate) fn filter(query: &QuerySpec, prospect: &Prospect) -> bool {
+    match query {
+        QuerySpec::PlayerLookup(target_player) => match prospect.last_scorer {
+            None => false,
+            Some(scorer) => scorer == *target_player,
+        },
+        QuerySpec::NoLastGoalscorer => prospect.last_scorer.is_none(),
+        _ => panic!("{query:?} unsupported"),
+    }
+}
@@ -0,0 +1,67 @@
+//! Goals scored by a player in each half, common to `PlayerToScoreInHalf`,
+//! `PlayerToScoreInBothHalves` and `PlayerToScoreMultipleInHalf`.
+
+use super::*;
+use crate::domain::Period;
+
+#[inline]
+#[must_use]
+pub(crate) fn requirements(at_least: u8) -> Expansions {
+    Expansions {
+        ht_score: false,
+        ft_score: false,
+        max_player_goals: at_least,
+        player_split_goal_stats: true,
+        max_player_assists: 0,
+        first_goalscorer: false,
+        last_goalscorer: false,
+        first_goal_time: false,
+        time_bands: 0,
+        first_scoring_team: false,
+        last_scoring_team: false,
+        max_race_goals: 0,
+    }
+}
+
+#[inline]
+#[must_use]
+pub(crate) fn prepare(
+    outcome: &Outcome,
+    player_lookup: &HashLookup<Player>,
+) -> QuerySpec {
+    match outcome {
+        Outcome::Player(player) => {
+            QuerySpec::PlayerLookup(player_lookup.index_of(player).unwrap())
+        }
+        _ => panic!("{outcome:?} unsupported"),
+    }
+}
+
+#[inline]
+#[must_use]
+pub(crate) fn filter(period: &Period, at_least: u8, query: &QuerySpec, prospect: &Prospect) -> bool {
+    match query {
+        QuerySpec::PlayerLookup(target_player) => {
+            let stats = &prospect.stats[*target_player];
+            let goals = match period {
+                Period::FirstHalf => stats.h1.goals,
+                Period::SecondHalf => stats.h2.goals,
+                Period::FullTime => stats.h1.goals + stats.h2.goals,
+            };
+            goals >= at_least
+        }
+        _ => panic!("{query:?} unsupported"),
+    }
+}
+
+#[inline]
+#[must_use]
+pub(crate) fn filter_both_halves(query: &QuerySpec, prospect: &Prospect) -> bool {
+    match query {
+        QuerySpec::PlayerLookup(target_player) => {
+            let stats = &prospect.stats[*target_player];
+            stats.h1.goals > 0 && stats.h2.goals > 0
+        }
+        _ => panic!("{query:?} unsupported"),
+    }
+}
@@ -0,0 +1,40 @@
+use super::*;
+use crate::domain::Side;
+
+#[inline]
+#[must_use]
+pub(crate) fn requirements(goals: u8) -> Expansions {
+    Expansions {
+        ht_score: false,
+        ft_score: true,
+        max_player_goals: 0,
+        player_split_goal_stats: false,
+        max_player_assists: 0,
+        first_goalscorer: false,
+        last_goalscorer: false,
+        first_goal_time: false,
+        time_bands: 0,
+        first_scoring_team: false,
+        last_scoring_team: false,
+        max_race_goals: goals,
+    }
+}
+
+#[inline]
+#[must_use]
+pub(crate) fn prepare() -> QuerySpec {
+    QuerySpec::Stateless
+}
+
+#[inline]
+#[must_use]
+pub(crate) fn filter(goals: u8, outcome: &Outcome, prospect: &Prospect) -> bool {
+    let decided = u8::max(prospect.ft_score.home, prospect.ft_score.away) >= goals;
+    let won_by_home = prospect.races_won_by_home & 1 << (goals - 1) != 0;
+    match outcome {
+        Outcome::Team(Side::Home) => decided && won_by_home,
+        Outcome::Team(Side::Away) => decided && !won_by_home,
+        Outcome::None => !decided,
+        _ => panic!("{outcome:?} unsupported"),
+    }
+}
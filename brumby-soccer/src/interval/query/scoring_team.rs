@@ -0,0 +1,40 @@
+//! The first and last sides to score, as per the `FirstTeamToScore` and `LastTeamToScore` offer
+//! types.
+
+use super::*;
+use crate::domain::Side;
+
+#[inline]
+#[must_use]
+pub(crate) fn requirements(first: bool) -> Expansions {
+    Expansions {
+        ht_score: false,
+        ft_score: false,
+        max_player_goals: 0,
+        player_split_goal_stats: false,
+        max_player_assists: 0,
+        first_goalscorer: false,
+        last_goalscorer: false,
+        first_goal_time: false,
+        time_bands: 0,
+        first_scoring_team: first,
+        last_scoring_team: !first,
+        max_race_goals: 0,
+    }
+}
+
+#[inline]
+#[must_use]
+pub(crate) fn prepare() -> QuerySpec {
+    QuerySpec::Stateless
+}
+
+#[inline]
+#[must_use]
+pub(crate) fn filter(scoring_team: &Option<Side>, outcome: &Outcome) -> bool {
+    match outcome {
+        Outcome::Team(side) => scoring_team.as_ref() == Some(side),
+        Outcome::None => scoring_team.is_none(),
+        _ => panic!("{outcome:?} unsupported"),
+    }
+}
@@ -0,0 +1,165 @@
+//! Handling of goals scored by one side, common to `TeamTotalGoals` and `ExactTeamGoals`
+//! offer types.
+
+use super::*;
+use crate::domain::{Period, Side};
+
+#[inline]
+#[must_use]
+pub(crate) fn requirements(period: &Period) -> Expansions {
+    match period {
+        Period::FirstHalf => Expansions {
+            ht_score: true,
+            ft_score: false,
+            max_player_goals: 0,
+            player_split_goal_stats: false,
+            max_player_assists: 0,
+            first_goalscorer: false,
+            last_goalscorer: false,
+            first_goal_time: false,
+            time_bands: 0,
+            first_scoring_team: false,
+            last_scoring_team: false,
+            max_race_goals: 0,
+        },
+        Period::SecondHalf => Expansions {
+            ht_score: true,
+            ft_score: true,
+            max_player_goals: 0,
+            player_split_goal_stats: false,
+            max_player_assists: 0,
+            first_goalscorer: false,
+            last_goalscorer: false,
+            first_goal_time: false,
+            time_bands: 0,
+            first_scoring_team: false,
+            last_scoring_team: false,
+            max_race_goals: 0,
+        },
+        Period::FullTime => Expansions {
+            ht_score: false,
+            ft_score: true,
+            max_player_goals: 0,
+            player_split_goal_stats: false,
+            max_player_assists: 0,
+            first_goalscorer: false,
+            last_goalscorer: false,
+            first_goal_time: false,
+            time_bands: 0,
+            first_scoring_team: false,
+            last_scoring_team: false,
+            max_race_goals: 0,
+        },
+    }
+}
+
+#[inline]
+#[must_use]
+pub(crate) fn prepare() -> QuerySpec {
+    QuerySpec::Stateless
+}
+
+#[inline]
+#[must_use]
+pub(crate) fn filter(period: &Period, side: &Side, outcome: &Outcome, prospect: &Prospect) -> bool {
+    let score = match period {
+        Period::FirstHalf => prospect.ht_score.clone(),
+        Period::SecondHalf => prospect.h2_score(),
+        Period::FullTime => prospect.ft_score.clone(),
+    };
+    let goals = match side {
+        Side::Home => score.home,
+        Side::Away => score.away,
+    };
+
+    match outcome {
+        Outcome::Over(limit) => goals > *limit,
+        Outcome::Under(limit) => goals < *limit,
+        Outcome::Exactly(count) => goals == *count,
+        _ => panic!("{outcome:?} unsupported"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Over, Score};
+    use crate::interval::Exploration;
+    use assert_float_eq::*;
+
+    fn create_test_exploration() -> Exploration {
+        let mut prospects = Prospects::default();
+        for (ht_score, ft_score, prob) in [
+            (Score::new(0, 0), Score::new(0, 0), 0.1),
+            (Score::new(0, 0), Score::new(1, 0), 0.2),
+            (Score::new(1, 0), Score::new(2, 1), 0.3),
+            (Score::new(1, 1), Score::new(3, 1), 0.15),
+            (Score::new(2, 0), Score::new(4, 2), 0.05),
+            (Score::new(0, 1), Score::new(0, 2), 0.2),
+        ] {
+            prospects.insert(
+                Prospect {
+                    ht_score,
+                    ft_score,
+                    stats: Default::default(),
+                    first_scorer: None,
+                    last_scorer: None,
+                    first_goal_time: None,
+                    scoring_bands: 0,
+                    first_scoring_team: None,
+                    last_scoring_team: None,
+                    races_won_by_home: 0,
+                },
+                prob,
+            );
+        }
+        Exploration {
+            player_lookup: HashLookup::default(),
+            prospects,
+            pruned: 0.0,
+        }
+    }
+
+    #[test]
+    pub fn team_total_gather() {
+        let exploration = create_test_exploration();
+        let offer_type = OfferType::TeamTotalGoals(Period::FullTime, Side::Home, Over(1));
+        assert_float_absolute_eq!(
+            0.5,
+            isolate(&offer_type, &Outcome::Over(1), &exploration.prospects, &exploration.player_lookup)
+        );
+        assert_float_absolute_eq!(
+            0.5,
+            isolate(&offer_type, &Outcome::Under(2), &exploration.prospects, &exploration.player_lookup)
+        );
+
+        let offer_type = OfferType::TeamTotalGoals(Period::SecondHalf, Side::Away, Over(0));
+        assert_float_absolute_eq!(
+            0.55,
+            isolate(&offer_type, &Outcome::Over(0), &exploration.prospects, &exploration.player_lookup)
+        );
+    }
+
+    #[test]
+    pub fn exact_team_gather() {
+        let exploration = create_test_exploration();
+        let offer_type = OfferType::ExactTeamGoals(Period::FullTime, Side::Away, Over(1));
+        let expected = [
+            (Outcome::Exactly(0), 0.3),
+            (Outcome::Exactly(1), 0.45),
+            (Outcome::Over(1), 0.25),
+        ];
+        for (outcome, expected_prob) in expected {
+            assert_float_absolute_eq!(
+                expected_prob,
+                isolate(&offer_type, &outcome, &exploration.prospects, &exploration.player_lookup)
+            );
+        }
+
+        let offer_type = OfferType::ExactTeamGoals(Period::FirstHalf, Side::Home, Over(1));
+        assert_float_absolute_eq!(
+            0.5,
+            isolate(&offer_type, &Outcome::Exactly(0), &exploration.prospects, &exploration.player_lookup)
+        );
+    }
+}
@@ -0,0 +1,39 @@
+use super::*;
+use crate::domain::TimeBand;
+
+#[inline]
+#[must_use]
+pub(crate) fn requirements(time_band: &TimeBand) -> Expansions {
+    Expansions {
+        ht_score: false,
+        ft_score: false,
+        max_player_goals: 0,
+        player_split_goal_stats: false,
+        max_player_assists: 0,
+        first_goalscorer: false,
+        last_goalscorer: false,
+        first_goal_time: false,
+        time_bands: 1 << time_band.from | 1 << time_band.to,
+        first_scoring_team: false,
+        last_scoring_team: false,
+        max_race_goals: 0,
+    }
+}
+
+#[inline]
+#[must_use]
+pub(crate) fn prepare() -> QuerySpec {
+    QuerySpec::Stateless
+}
+
+#[inline]
+#[must_use]
+pub(crate) fn filter(time_band: &TimeBand, outcome: &Outcome, prospect: &Prospect) -> bool {
+    let mask = (1u128 << time_band.to) - (1u128 << time_band.from);
+    let scored = prospect.scoring_bands & mask != 0;
+    match outcome {
+        Outcome::Yes => scored,
+        Outcome::No => !scored,
+        _ => panic!("{outcome:?} unsupported"),
+    }
+}
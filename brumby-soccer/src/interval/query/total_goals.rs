@@ -12,6 +12,12 @@ pub(crate) fn requirements(period: &Period) -> Expansions {
             player_split_goal_stats: false,
             max_player_assists: 0,
             first_goalscorer: false,
+            last_goalscorer: false,
+            first_goal_time: false,
+            time_bands: 0,
+            first_scoring_team: false,
+            last_scoring_team: false,
+            max_race_goals: 0,
         },
         Period::SecondHalf => Expansions {
             ht_score: true,
@@ -20,6 +26,12 @@ pub(crate) fn requirements(period: &Period) -> Expansions {
             player_split_goal_stats: false,
             max_player_assists: 0,
             first_goalscorer: false,
+            last_goalscorer: false,
+            first_goal_time: false,
+            time_bands: 0,
+            first_scoring_team: false,
+            last_scoring_team: false,
+            max_race_goals: 0,
         },
         Period::FullTime => Expansions {
             ht_score: false,
@@ -28,6 +40,12 @@ pub(crate) fn requirements(period: &Period) -> Expansions {
             player_split_goal_stats: false,
             max_player_assists: 0,
             first_goalscorer: false,
+            last_goalscorer: false,
+            first_goal_time: false,
+            time_bands: 0,
+            first_scoring_team: false,
+            last_scoring_team: false,
+            max_race_goals: 0,
         },
     }
 }
@@ -47,9 +65,99 @@ pub(crate) fn filter(period: &Period, outcome: &Outcome, prospect: &Prospect) ->
         Period::FullTime => (prospect.ft_score.home, prospect.ft_score.away),
     };
 
+    let goals = home_goals + away_goals;
     match outcome {
-        Outcome::Over(limit) => home_goals + away_goals > *limit,
-        Outcome::Under(limit) => away_goals + home_goals < *limit,
+        Outcome::Over(limit) => goals > *limit,
+        Outcome::Under(limit) => goals < *limit,
+        Outcome::Exactly(count) => goals == *count,
+        Outcome::Odd => goals % 2 == 1,
+        Outcome::Even => goals % 2 == 0,
         _ => panic!("{outcome:?} unsupported"),
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{OfferType, Over, Score};
+    use crate::interval::Exploration;
+    use assert_float_eq::*;
+
+    fn create_test_exploration() -> Exploration {
+        let mut prospects = Prospects::default();
+        for (ht_score, ft_score, prob) in [
+            (Score::new(0, 0), Score::new(0, 0), 0.1),
+            (Score::new(0, 0), Score::new(1, 0), 0.2),
+            (Score::new(1, 0), Score::new(2, 1), 0.3),
+            (Score::new(1, 1), Score::new(3, 1), 0.15),
+            (Score::new(2, 0), Score::new(4, 2), 0.05),
+            (Score::new(0, 1), Score::new(0, 2), 0.2),
+        ] {
+            prospects.insert(
+                Prospect {
+                    ht_score,
+                    ft_score,
+                    stats: Default::default(),
+                    first_scorer: None,
+                    last_scorer: None,
+                    first_goal_time: None,
+                    scoring_bands: 0,
+                    first_scoring_team: None,
+                    last_scoring_team: None,
+                    races_won_by_home: 0,
+                },
+                prob,
+            );
+        }
+        Exploration {
+            player_lookup: HashLookup::default(),
+            prospects,
+            pruned: 0.0,
+        }
+    }
+
+    #[test]
+    pub fn exact_total_gather() {
+        let exploration = create_test_exploration();
+        let offer_type = OfferType::ExactTotalGoals(Period::FullTime, Over(3));
+        let expected = [
+            (Outcome::Exactly(0), 0.1),
+            (Outcome::Exactly(1), 0.2),
+            (Outcome::Exactly(2), 0.2),
+            (Outcome::Exactly(3), 0.3),
+            (Outcome::Over(3), 0.2),
+        ];
+        for (outcome, expected_prob) in expected {
+            assert_float_absolute_eq!(
+                expected_prob,
+                isolate(&offer_type, &outcome, &exploration.prospects, &exploration.player_lookup)
+            );
+        }
+
+        let offer_type = OfferType::ExactTotalGoals(Period::SecondHalf, Over(1));
+        assert_float_absolute_eq!(
+            0.4,
+            isolate(&offer_type, &Outcome::Exactly(1), &exploration.prospects, &exploration.player_lookup)
+        );
+    }
+
+    #[test]
+    pub fn odd_even_gather() {
+        let exploration = create_test_exploration();
+        let offer_type = OfferType::OddEvenGoals(Period::FullTime);
+        assert_float_absolute_eq!(
+            0.5,
+            isolate(&offer_type, &Outcome::Odd, &exploration.prospects, &exploration.player_lookup)
+        );
+        assert_float_absolute_eq!(
+            0.5,
+            isolate(&offer_type, &Outcome::Even, &exploration.prospects, &exploration.player_lookup)
+        );
+
+        let offer_type = OfferType::OddEvenGoals(Period::SecondHalf);
+        assert_float_absolute_eq!(
+            0.6,
+            isolate(&offer_type, &Outcome::Even, &exploration.prospects, &exploration.player_lookup)
+        );
+    }
 }
\ No newline at end of file
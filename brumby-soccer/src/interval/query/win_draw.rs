@@ -15,6 +15,12 @@ pub(crate) fn requirements(period: &Period) -> Expansions {
             player_split_goal_stats: false,
             max_player_assists: 0,
             first_goalscorer: false,
+            last_goalscorer: false,
+            first_goal_time: false,
+            time_bands: 0,
+            first_scoring_team: false,
+            last_scoring_team: false,
+            max_race_goals: 0,
         },
         Period::SecondHalf => Expansions {
             ht_score: true,
@@ -23,6 +29,12 @@ pub(crate) fn requirements(period: &Period) -> Expansions {
             player_split_goal_stats: false,
             max_player_assists: 0,
             first_goalscorer: false,
+            last_goalscorer: false,
+            first_goal_time: false,
+            time_bands: 0,
+            first_scoring_team: false,
+            last_scoring_team: false,
+            max_race_goals: 0,
         },
         Period::FullTime => Expansions {
             ht_score: false,
@@ -31,6 +43,12 @@ pub(crate) fn requirements(period: &Period) -> Expansions {
             player_split_goal_stats: false,
             max_player_assists: 0,
             first_goalscorer: false,
+            last_goalscorer: false,
+            first_goal_time: false,
+            time_bands: 0,
+            first_scoring_team: false,
+            last_scoring_team: false,
+            max_race_goals: 0,
         },
     }
 }
@@ -106,6 +124,12 @@ mod tests {
                         },
                         stats: Default::default(),
                         first_scorer: None,
+                        last_scorer: None,
+                        first_goal_time: None,
+                        scoring_bands: 0,
+                        first_scoring_team: None,
+                        last_scoring_team: None,
+                        races_won_by_home: 0,
                     },
                     prob,
                 );
@@ -0,0 +1,103 @@
+use super::*;
+use crate::domain::{Period, Side};
+
+#[inline]
+#[must_use]
+pub(crate) fn prepare() -> QuerySpec {
+    QuerySpec::Stateless
+}
+
+#[inline]
+#[must_use]
+pub(crate) fn filter(period: &Period, outcome: &Outcome, prospect: &Prospect) -> bool {
+    match outcome {
+        Outcome::WinBy(side, margin) => {
+            let (home_goals, away_goals) = match period {
+                Period::FirstHalf => (prospect.ht_score.home, prospect.ht_score.away),
+                Period::SecondHalf => {
+                    let h2_score = prospect.h2_score();
+                    (h2_score.home, h2_score.away)
+                }
+                Period::FullTime => (prospect.ft_score.home, prospect.ft_score.away),
+            };
+            match side {
+                Side::Home => home_goals > away_goals && home_goals - away_goals == *margin,
+                Side::Away => away_goals > home_goals && away_goals - home_goals == *margin,
+            }
+        }
+        // the draw and the open-ended margins are plain handicap outcomes
+        _ => win_draw::filter(period, outcome, prospect),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{DrawHandicap, Score, WinHandicap};
+    use crate::interval::Exploration;
+    use assert_float_eq::*;
+    use brumby::linear::matrix::Matrix;
+
+    fn create_test_4x4_exploration() -> Exploration {
+        let mut scoregrid = Matrix::allocate(4, 4);
+        scoregrid[0].copy_from_slice(&[0.04, 0.03, 0.02, 0.01]);
+        scoregrid[1].copy_from_slice(&[0.08, 0.06, 0.04, 0.02]);
+        scoregrid[2].copy_from_slice(&[0.12, 0.09, 0.06, 0.03]);
+        scoregrid[3].copy_from_slice(&[0.16, 0.12, 0.08, 0.04]);
+
+        let mut prospects = Prospects::default();
+        for home_goals in 0..scoregrid.rows() {
+            for away_goals in 0..scoregrid.cols() {
+                let prob = scoregrid[(home_goals, away_goals)];
+                prospects.insert(
+                    Prospect {
+                        ht_score: Score::nil_all(),
+                        ft_score: Score {
+                            home: home_goals as u8,
+                            away: away_goals as u8,
+                        },
+                        stats: Default::default(),
+                        first_scorer: None,
+                        last_scorer: None,
+                        first_goal_time: None,
+                        scoring_bands: 0,
+                        first_scoring_team: None,
+                        last_scoring_team: None,
+                        races_won_by_home: 0,
+                    },
+                    prob,
+                );
+            }
+        }
+        Exploration {
+            player_lookup: HashLookup::default(),
+            prospects,
+            pruned: 0.0,
+        }
+    }
+
+    #[test]
+    pub fn gather() {
+        let exploration = create_test_4x4_exploration();
+        let expected = [
+            (Outcome::WinBy(Side::Home, 1), 0.25),
+            (Outcome::WinBy(Side::Home, 2), 0.24),
+            (Outcome::Win(Side::Home, WinHandicap::AheadOver(2)), 0.16),
+            (Outcome::Draw(DrawHandicap::Ahead(0)), 0.2),
+            (Outcome::WinBy(Side::Away, 1), 0.1),
+            (Outcome::WinBy(Side::Away, 2), 0.04),
+            (Outcome::Win(Side::Away, WinHandicap::AheadOver(2)), 0.01),
+        ];
+        for (outcome, expected_prob) in expected {
+            assert_float_absolute_eq!(
+                expected_prob,
+                isolate(
+                    &OfferType::WinningMargin(Period::FullTime),
+                    &outcome,
+                    &exploration.prospects,
+                    &exploration.player_lookup
+                )
+            );
+        }
+    }
+}
@@ -1,8 +1,8 @@
 use assert_float_eq::*;
 use brumby::sv;
 
-use crate::domain::{OfferType, Outcome, Player};
-use crate::interval::query::isolate;
+use crate::domain::{OfferType, Outcome, Period, Player, TimeBand};
+use crate::interval::query::{isolate, isolate_set};
 
 use super::*;
 
@@ -50,6 +50,12 @@ fn explore_2x2() {
                 ft_score: Score { home: 0, away: 0 },
                 stats:sv![PlayerStats { h1: PeriodStats { goals: 0 }, h2: PeriodStats { goals: 0 }, assists: 0 }],
                 first_scorer: None,
+                last_scorer: None,
+                first_goal_time: None,
+                scoring_bands: 0,
+                first_scoring_team: None,
+                last_scoring_team: None,
+                races_won_by_home: 0,
             },
             0.0625f64,
         ),
@@ -59,6 +65,12 @@ fn explore_2x2() {
                 ft_score: Score { home: 2, away: 0 },
                 stats:sv![PlayerStats { h1: PeriodStats { goals: 1 }, h2: PeriodStats { goals: 1 }, assists: 2 }],
                 first_scorer: Some(0),
+                last_scorer: Some(0),
+                first_goal_time: None,
+                scoring_bands: 0,
+                first_scoring_team: None,
+                last_scoring_team: None,
+                races_won_by_home: 0,
             },
             0.0625,
         ),
@@ -68,6 +80,12 @@ fn explore_2x2() {
                 ft_score: Score { home: 1, away: 1 },
                 stats:sv![PlayerStats { h1: PeriodStats { goals: 1 }, h2: PeriodStats { goals: 1 }, assists: 2 }],
                 first_scorer: Some(0),
+                last_scorer: Some(0),
+                first_goal_time: None,
+                scoring_bands: 0,
+                first_scoring_team: None,
+                last_scoring_team: None,
+                races_won_by_home: 0,
             },
             0.0625,
         ),
@@ -77,6 +95,12 @@ fn explore_2x2() {
                 ft_score: Score { home: 1, away: 1 },
                 stats:sv![PlayerStats { h1: PeriodStats { goals: 1 }, h2: PeriodStats { goals: 1 }, assists: 2 }],
                 first_scorer: Some(0),
+                last_scorer: Some(0),
+                first_goal_time: None,
+                scoring_bands: 0,
+                first_scoring_team: None,
+                last_scoring_team: None,
+                races_won_by_home: 0,
             },
             0.0625,
         ),
@@ -86,6 +110,12 @@ fn explore_2x2() {
                 ft_score: Score { home: 1, away: 1 },
                 stats:sv![PlayerStats { h1: PeriodStats { goals: 2 }, h2: PeriodStats { goals: 0 }, assists: 2 }],
                 first_scorer: Some(0),
+                last_scorer: Some(0),
+                first_goal_time: None,
+                scoring_bands: 0,
+                first_scoring_team: None,
+                last_scoring_team: None,
+                races_won_by_home: 0,
             },
             0.0625,
         ),
@@ -95,6 +125,12 @@ fn explore_2x2() {
                 ft_score: Score { home: 1, away: 1 },
                 stats:sv![PlayerStats { h1: PeriodStats { goals: 0}, h2: PeriodStats { goals: 2 }, assists: 2 }],
                 first_scorer: Some(0),
+                last_scorer: Some(0),
+                first_goal_time: None,
+                scoring_bands: 0,
+                first_scoring_team: None,
+                last_scoring_team: None,
+                races_won_by_home: 0,
             },
             0.0625,
         ),
@@ -104,6 +140,12 @@ fn explore_2x2() {
                 ft_score: Score { home: 1, away: 2 },
                 stats:sv![PlayerStats { h1: PeriodStats { goals: 2 }, h2: PeriodStats { goals: 1 }, assists: 3 }],
                 first_scorer: Some(0),
+                last_scorer: Some(0),
+                first_goal_time: None,
+                scoring_bands: 0,
+                first_scoring_team: None,
+                last_scoring_team: None,
+                races_won_by_home: 0,
             },
             0.0625,
         ),
@@ -113,6 +155,12 @@ fn explore_2x2() {
                 ft_score: Score { home: 1, away: 2 },
                 stats:sv![PlayerStats { h1: PeriodStats { goals: 1 }, h2: PeriodStats { goals: 2 }, assists: 3 }],
                 first_scorer: Some(0),
+                last_scorer: Some(0),
+                first_goal_time: None,
+                scoring_bands: 0,
+                first_scoring_team: None,
+                last_scoring_team: None,
+                races_won_by_home: 0,
             },
             0.0625,
         ),
@@ -122,6 +170,12 @@ fn explore_2x2() {
                 ft_score: Score { home: 0, away: 2 },
                 stats:sv![PlayerStats { h1: PeriodStats { goals: 1 }, h2: PeriodStats { goals: 1 }, assists: 2 }],
                 first_scorer: Some(0),
+                last_scorer: Some(0),
+                first_goal_time: None,
+                scoring_bands: 0,
+                first_scoring_team: None,
+                last_scoring_team: None,
+                races_won_by_home: 0,
             },
             0.0625,
         ),
@@ -131,6 +185,12 @@ fn explore_2x2() {
                 ft_score: Score { home: 2, away: 2 },
                 stats:sv![PlayerStats { h1: PeriodStats { goals: 2 }, h2: PeriodStats { goals: 2 }, assists: 4 }],
                 first_scorer: Some(0),
+                last_scorer: Some(0),
+                first_goal_time: None,
+                scoring_bands: 0,
+                first_scoring_team: None,
+                last_scoring_team: None,
+                races_won_by_home: 0,
             },
             0.0625,
         ),
@@ -140,6 +200,12 @@ fn explore_2x2() {
                 ft_score: Score { home: 1, away: 0 },
                 stats:sv![PlayerStats { h1: PeriodStats { goals: 1 }, h2: PeriodStats { goals: 0 }, assists: 1 }],
                 first_scorer: Some(0),
+                last_scorer: Some(0),
+                first_goal_time: None,
+                scoring_bands: 0,
+                first_scoring_team: None,
+                last_scoring_team: None,
+                races_won_by_home: 0,
             },
             0.0625,
         ),
@@ -149,6 +215,12 @@ fn explore_2x2() {
                 ft_score: Score { home: 1, away: 0 },
                 stats:sv![PlayerStats { h1: PeriodStats { goals: 0 }, h2: PeriodStats { goals: 1 }, assists: 1 }],
                 first_scorer: Some(0),
+                last_scorer: Some(0),
+                first_goal_time: None,
+                scoring_bands: 0,
+                first_scoring_team: None,
+                last_scoring_team: None,
+                races_won_by_home: 0,
             },
             0.0625,
         ),
@@ -158,6 +230,12 @@ fn explore_2x2() {
                 ft_score: Score { home: 0, away: 1 },
                 stats:sv![PlayerStats { h1: PeriodStats { goals: 1 }, h2: PeriodStats { goals: 0 }, assists: 1 }],
                 first_scorer: Some(0),
+                last_scorer: Some(0),
+                first_goal_time: None,
+                scoring_bands: 0,
+                first_scoring_team: None,
+                last_scoring_team: None,
+                races_won_by_home: 0,
             },
             0.0625,
         ),
@@ -167,6 +245,12 @@ fn explore_2x2() {
                 ft_score: Score { home: 0, away: 1 },
                 stats:sv![PlayerStats { h1: PeriodStats { goals: 0 }, h2: PeriodStats { goals: 1 }, assists: 1 }],
                 first_scorer: Some(0),
+                last_scorer: Some(0),
+                first_goal_time: None,
+                scoring_bands: 0,
+                first_scoring_team: None,
+                last_scoring_team: None,
+                races_won_by_home: 0,
             },
             0.0625,
         ),
@@ -176,6 +260,12 @@ fn explore_2x2() {
                 ft_score: Score { home: 2, away: 1 },
                 stats:sv![PlayerStats { h1: PeriodStats { goals: 2 }, h2: PeriodStats { goals: 1 }, assists: 3 }],
                 first_scorer: Some(0),
+                last_scorer: Some(0),
+                first_goal_time: None,
+                scoring_bands: 0,
+                first_scoring_team: None,
+                last_scoring_team: None,
+                races_won_by_home: 0,
             },
             0.0625,
         ),
@@ -185,6 +275,12 @@ fn explore_2x2() {
                 ft_score: Score { home: 2, away: 1 },
                 stats:sv![PlayerStats { h1: PeriodStats { goals: 1 }, h2: PeriodStats { goals: 2 }, assists: 3 }],
                 first_scorer: Some(0),
+                last_scorer: Some(0),
+                first_goal_time: None,
+                scoring_bands: 0,
+                first_scoring_team: None,
+                last_scoring_team: None,
+                races_won_by_home: 0,
             },
             0.0625,
         ),
@@ -268,6 +364,12 @@ fn explore_2x2_pruned_2_goals() {
                 ft_score: Score { home: 0, away: 0 },
                 stats:sv![PlayerStats { h1: PeriodStats { goals: 0 }, h2: PeriodStats { goals: 0 }, assists: 0 }],
                 first_scorer: None,
+                last_scorer: None,
+                first_goal_time: None,
+                scoring_bands: 0,
+                first_scoring_team: None,
+                last_scoring_team: None,
+                races_won_by_home: 0,
             },
             0.0625f64,
         ),
@@ -277,6 +379,12 @@ fn explore_2x2_pruned_2_goals() {
                 ft_score: Score { home: 2, away: 0 },
                 stats:sv![PlayerStats { h1: PeriodStats { goals: 1 }, h2: PeriodStats { goals: 1 }, assists: 2 }],
                 first_scorer: Some(0),
+                last_scorer: Some(0),
+                first_goal_time: None,
+                scoring_bands: 0,
+                first_scoring_team: None,
+                last_scoring_team: None,
+                races_won_by_home: 0,
             },
             0.0625,
         ),
@@ -286,6 +394,12 @@ fn explore_2x2_pruned_2_goals() {
                 ft_score: Score { home: 1, away: 1 },
                 stats:sv![PlayerStats { h1: PeriodStats { goals: 1 }, h2: PeriodStats { goals: 1 }, assists: 2 }],
                 first_scorer: Some(0),
+                last_scorer: Some(0),
+                first_goal_time: None,
+                scoring_bands: 0,
+                first_scoring_team: None,
+                last_scoring_team: None,
+                races_won_by_home: 0,
             },
             0.0625,
         ),
@@ -295,6 +409,12 @@ fn explore_2x2_pruned_2_goals() {
                 ft_score: Score { home: 1, away: 1 },
                 stats:sv![PlayerStats { h1: PeriodStats { goals: 1 }, h2: PeriodStats { goals: 1 }, assists: 2 }],
                 first_scorer: Some(0),
+                last_scorer: Some(0),
+                first_goal_time: None,
+                scoring_bands: 0,
+                first_scoring_team: None,
+                last_scoring_team: None,
+                races_won_by_home: 0,
             },
             0.0625,
         ),
@@ -304,6 +424,12 @@ fn explore_2x2_pruned_2_goals() {
                 ft_score: Score { home: 1, away: 1 },
                 stats:sv![PlayerStats { h1: PeriodStats { goals: 2 }, h2: PeriodStats { goals: 0 }, assists: 2 }],
                 first_scorer: Some(0),
+                last_scorer: Some(0),
+                first_goal_time: None,
+                scoring_bands: 0,
+                first_scoring_team: None,
+                last_scoring_team: None,
+                races_won_by_home: 0,
             },
             0.25,
         ),
@@ -313,6 +439,12 @@ fn explore_2x2_pruned_2_goals() {
                 ft_score: Score { home: 1, away: 1 },
                 stats:sv![PlayerStats { h1: PeriodStats { goals: 0}, h2: PeriodStats { goals: 2 }, assists: 2 }],
                 first_scorer: Some(0),
+                last_scorer: Some(0),
+                first_goal_time: None,
+                scoring_bands: 0,
+                first_scoring_team: None,
+                last_scoring_team: None,
+                races_won_by_home: 0,
             },
             0.0625,
         ),
@@ -322,6 +454,12 @@ fn explore_2x2_pruned_2_goals() {
                 ft_score: Score { home: 0, away: 2 },
                 stats:sv![PlayerStats { h1: PeriodStats { goals: 1 }, h2: PeriodStats { goals: 1 }, assists: 2 }],
                 first_scorer: Some(0),
+                last_scorer: Some(0),
+                first_goal_time: None,
+                scoring_bands: 0,
+                first_scoring_team: None,
+                last_scoring_team: None,
+                races_won_by_home: 0,
             },
             0.0625,
         ),
@@ -331,6 +469,12 @@ fn explore_2x2_pruned_2_goals() {
                 ft_score: Score { home: 1, away: 0 },
                 stats:sv![PlayerStats { h1: PeriodStats { goals: 1 }, h2: PeriodStats { goals: 0 }, assists: 1 }],
                 first_scorer: Some(0),
+                last_scorer: Some(0),
+                first_goal_time: None,
+                scoring_bands: 0,
+                first_scoring_team: None,
+                last_scoring_team: None,
+                races_won_by_home: 0,
             },
             0.125,
         ),
@@ -340,6 +484,12 @@ fn explore_2x2_pruned_2_goals() {
                 ft_score: Score { home: 1, away: 0 },
                 stats:sv![PlayerStats { h1: PeriodStats { goals: 0 }, h2: PeriodStats { goals: 1 }, assists: 1 }],
                 first_scorer: Some(0),
+                last_scorer: Some(0),
+                first_goal_time: None,
+                scoring_bands: 0,
+                first_scoring_team: None,
+                last_scoring_team: None,
+                races_won_by_home: 0,
             },
             0.0625,
         ),
@@ -349,6 +499,12 @@ fn explore_2x2_pruned_2_goals() {
                 ft_score: Score { home: 0, away: 1 },
                 stats:sv![PlayerStats { h1: PeriodStats { goals: 1 }, h2: PeriodStats { goals: 0 }, assists: 1 }],
                 first_scorer: Some(0),
+                last_scorer: Some(0),
+                first_goal_time: None,
+                scoring_bands: 0,
+                first_scoring_team: None,
+                last_scoring_team: None,
+                races_won_by_home: 0,
             },
             0.125,
         ),
@@ -358,6 +514,12 @@ fn explore_2x2_pruned_2_goals() {
                 ft_score: Score { home: 0, away: 1 },
                 stats:sv![PlayerStats { h1: PeriodStats { goals: 0 }, h2: PeriodStats { goals: 1 }, assists: 1 }],
                 first_scorer: Some(0),
+                last_scorer: Some(0),
+                first_goal_time: None,
+                scoring_bands: 0,
+                first_scoring_team: None,
+                last_scoring_team: None,
+                races_won_by_home: 0,
             },
             0.0625,
         ),
@@ -456,6 +618,12 @@ fn explore_1x1_player_goal() {
                     PlayerStats { h1: PeriodStats { goals: 0 }, h2: PeriodStats { goals: 0 }, assists: 0 }
                 ],
                 first_scorer: None,
+                last_scorer: None,
+                first_goal_time: None,
+                scoring_bands: 0,
+                first_scoring_team: None,
+                last_scoring_team: None,
+                races_won_by_home: 0,
             },
             0.25,
         ),
@@ -468,6 +636,12 @@ fn explore_1x1_player_goal() {
                     PlayerStats { h1: PeriodStats { goals: 0 }, h2: PeriodStats { goals: 2 }, assists: 2 }
                 ],
                 first_scorer: Some(1),
+                last_scorer: Some(1),
+                first_goal_time: None,
+                scoring_bands: 0,
+                first_scoring_team: None,
+                last_scoring_team: None,
+                races_won_by_home: 0,
             },
             0.1875,
         ),
@@ -480,6 +654,12 @@ fn explore_1x1_player_goal() {
                     PlayerStats { h1: PeriodStats { goals: 0 }, h2: PeriodStats { goals: 1 }, assists: 2 }
                 ],
                 first_scorer: Some(1),
+                last_scorer: Some(0),
+                first_goal_time: None,
+                scoring_bands: 0,
+                first_scoring_team: None,
+                last_scoring_team: None,
+                races_won_by_home: 0,
             },
             0.03125,
         ),
@@ -492,6 +672,12 @@ fn explore_1x1_player_goal() {
                     PlayerStats { h1: PeriodStats { goals: 0 }, h2: PeriodStats { goals: 1 }, assists: 2 }
                 ],
                 first_scorer: Some(0),
+                last_scorer: Some(1),
+                first_goal_time: None,
+                scoring_bands: 0,
+                first_scoring_team: None,
+                last_scoring_team: None,
+                races_won_by_home: 0,
             },
             0.03125,
         ),
@@ -504,6 +690,12 @@ fn explore_1x1_player_goal() {
                     PlayerStats { h1: PeriodStats { goals: 0 }, h2: PeriodStats { goals: 1 }, assists: 1 }
                 ],
                 first_scorer: Some(1),
+                last_scorer: Some(1),
+                first_goal_time: None,
+                scoring_bands: 0,
+                first_scoring_team: None,
+                last_scoring_team: None,
+                races_won_by_home: 0,
             },
             0.1875,
         ),
@@ -516,6 +708,12 @@ fn explore_1x1_player_goal() {
                     PlayerStats { h1: PeriodStats { goals: 0 }, h2: PeriodStats { goals: 0 }, assists: 1 }
                 ],
                 first_scorer: Some(0),
+                last_scorer: Some(0),
+                first_goal_time: None,
+                scoring_bands: 0,
+                first_scoring_team: None,
+                last_scoring_team: None,
+                races_won_by_home: 0,
             },
             0.0625,
         ),
@@ -528,6 +726,12 @@ fn explore_1x1_player_goal() {
                     PlayerStats { h1: PeriodStats { goals: 0 }, h2: PeriodStats { goals: 1 }, assists: 1 }
                 ],
                 first_scorer: Some(1),
+                last_scorer: Some(1),
+                first_goal_time: None,
+                scoring_bands: 0,
+                first_scoring_team: None,
+                last_scoring_team: None,
+                races_won_by_home: 0,
             },
             0.25,
         ),
@@ -699,3 +903,248 @@ fn explore_2x2_player_goal_asymmetric() {
     );
     assert_float_relative_eq!(1.0 - 0.16 - 0.1225, first_goalscorer_other);
 }
+
+#[test]
+fn explore_2x2_last_goalscorer_and_player_to_score() {
+    let player = Player::Named(Side::Home, "Markos".into());
+    let exploration = explore(
+        &Config {
+            intervals: 2,
+            team_probs: TeamProbs {
+                h1_goals: BivariateProbs { home: 0.25, away: 0.25, common: 0.25 },
+                h2_goals: BivariateProbs { home: 0.25, away: 0.25, common: 0.25 },
+                assists: UnivariateProbs { home: 1.0, away: 1.0 },
+            },
+            player_probs: sv![(player.clone(), PlayerProbs { goal: Some(0.25), assist: None })],
+            prune_thresholds: Default::default(),
+            expansions: Default::default(),
+        },
+        0..2,
+    );
+    print_prospects(&exploration.prospects);
+    assert_eq!(1.0, exploration.prospects.values().sum::<f64>());
+    assert_eq!(0.0, exploration.pruned);
+
+    let last_goalscorer_none = isolate(
+        &OfferType::LastGoalscorer,
+        &Outcome::None,
+        &exploration.prospects,
+        &exploration.player_lookup,
+    );
+    assert_eq!(0.0625, last_goalscorer_none);
+
+    // with identical intervals, the last goalscorer mirrors the first
+    let last_goalscorer_player = isolate(
+        &OfferType::LastGoalscorer,
+        &Outcome::Player(player.clone()),
+        &exploration.prospects,
+        &exploration.player_lookup,
+    );
+    assert_eq!(0.1171875, last_goalscorer_player);
+
+    let last_goalscorer_other = isolate(
+        &OfferType::LastGoalscorer,
+        &Outcome::Player(Player::Other),
+        &exploration.prospects,
+        &exploration.player_lookup,
+    );
+    assert_eq!(1.0 - 0.0625 - 0.1171875, last_goalscorer_other);
+
+    let anytime_goalscorer_player = isolate(
+        &OfferType::AnytimeGoalscorer,
+        &Outcome::Player(player.clone()),
+        &exploration.prospects,
+        &exploration.player_lookup,
+    );
+    let player_to_score_1_player = isolate(
+        &OfferType::PlayerToScore(1),
+        &Outcome::Player(player.clone()),
+        &exploration.prospects,
+        &exploration.player_lookup,
+    );
+    assert_eq!(anytime_goalscorer_player, player_to_score_1_player);
+
+    // the player scores in both intervals
+    let player_to_score_2_player = isolate(
+        &OfferType::PlayerToScore(2),
+        &Outcome::Player(player.clone()),
+        &exploration.prospects,
+        &exploration.player_lookup,
+    );
+    assert_eq!(0.015625, player_to_score_2_player);
+
+    let player_to_score_3_player = isolate(
+        &OfferType::PlayerToScore(3),
+        &Outcome::Player(player.clone()),
+        &exploration.prospects,
+        &exploration.player_lookup,
+    );
+    assert_eq!(0.0, player_to_score_3_player);
+
+    for period in [Period::FirstHalf, Period::SecondHalf] {
+        let player_to_score_in_half_player = isolate(
+            &OfferType::PlayerToScoreInHalf(period),
+            &Outcome::Player(player.clone()),
+            &exploration.prospects,
+            &exploration.player_lookup,
+        );
+        assert_eq!(0.125, player_to_score_in_half_player);
+    }
+}
+
+#[test]
+fn explore_4x4_player_goals_by_half() {
+    let player = Player::Named(Side::Home, "Markos".into());
+    let exploration = explore(
+        &Config {
+            intervals: 4,
+            team_probs: TeamProbs {
+                h1_goals: BivariateProbs { home: 0.5, away: 0.0, common: 0.0 },
+                h2_goals: BivariateProbs { home: 0.5, away: 0.0, common: 0.0 },
+                assists: UnivariateProbs { home: 0.0, away: 0.0 },
+            },
+            player_probs: sv![(player.clone(), PlayerProbs { goal: Some(0.5), assist: None })],
+            prune_thresholds: Default::default(),
+            expansions: Expansions {
+                max_player_goals: 2,
+                player_split_goal_stats: true,
+                ..Expansions::empty()
+            },
+        },
+        0..4,
+    );
+    print_prospects(&exploration.prospects);
+    assert_float_absolute_eq!(1.0, exploration.prospects.values().sum::<f64>());
+
+    // the player scores in each interval with probability 0.25, each half spanning two intervals
+    let player_prob = |offer_type: OfferType| {
+        isolate(&offer_type, &Outcome::Player(player.clone()), &exploration.prospects, &exploration.player_lookup)
+    };
+    for period in [Period::FirstHalf, Period::SecondHalf] {
+        assert_float_absolute_eq!(0.4375, player_prob(OfferType::PlayerToScoreInHalf(period.clone())));
+        assert_float_absolute_eq!(0.0625, player_prob(OfferType::PlayerToScoreMultipleInHalf(period, 2)));
+    }
+    assert_float_absolute_eq!(0.19140625, player_prob(OfferType::PlayerToScoreInBothHalves));
+    assert_float_absolute_eq!(0.26171875, player_prob(OfferType::PlayerToScore(2)));
+}
+
+#[test]
+fn explore_6x6_goal_times() {
+    let exploration = explore(
+        &Config {
+            intervals: 6,
+            team_probs: TeamProbs {
+                h1_goals: BivariateProbs { home: 0.1, away: 0.1, common: 0.0 },
+                h2_goals: BivariateProbs { home: 0.1, away: 0.1, common: 0.0 },
+                assists: UnivariateProbs { home: 0.0, away: 0.0 },
+            },
+            player_probs: sv![],
+            prune_thresholds: Default::default(),
+            expansions: Expansions {
+                first_goal_time: true,
+                time_bands: [0, 15, 30, 45, 75, 90].iter().map(|minute| 1 << minute).sum(),
+                ..Expansions::empty()
+            },
+        },
+        0..6,
+    );
+    assert_float_absolute_eq!(1.0, exploration.prospects.values().sum::<f64>());
+
+    let isolate = |offer_type, outcome| isolate(&offer_type, &outcome, &exploration.prospects, &exploration.player_lookup);
+    assert_float_absolute_eq!(0.2, isolate(OfferType::TimeOfFirstGoal, Outcome::FirstGoalIn(TimeBand::new(0, 15))));
+    assert_float_absolute_eq!(0.16, isolate(OfferType::TimeOfFirstGoal, Outcome::FirstGoalIn(TimeBand::new(15, 30))));
+    assert_float_absolute_eq!(1.0 - 0.64, isolate(OfferType::TimeOfFirstGoal, Outcome::FirstGoalIn(TimeBand::new(0, 30))));
+    assert_float_absolute_eq!(0.8f64.powi(6), isolate(OfferType::TimeOfFirstGoal, Outcome::None));
+
+    // goals in each band are independent of goals in the others
+    assert_float_absolute_eq!(0.2, isolate(OfferType::GoalInTimeBand(TimeBand::new(15, 30)), Outcome::Yes));
+    assert_float_absolute_eq!(0.8, isolate(OfferType::GoalInTimeBand(TimeBand::new(75, 90)), Outcome::No));
+    assert_float_absolute_eq!(1.0 - 0.8f64.powi(3), isolate(OfferType::GoalInTimeBand(TimeBand::new(0, 45)), Outcome::Yes));
+    assert_float_absolute_eq!(
+        0.04,
+        isolate_set(
+            &[
+                (OfferType::GoalInTimeBand(TimeBand::new(0, 15)), Outcome::Yes),
+                (OfferType::GoalInTimeBand(TimeBand::new(75, 90)), Outcome::Yes),
+            ],
+            &exploration.prospects,
+            &exploration.player_lookup,
+        )
+    );
+}
+
+#[test]
+fn explore_6x6_goal_in_first_band() {
+    let exploration = explore(
+        &Config {
+            intervals: 6,
+            team_probs: TeamProbs {
+                h1_goals: BivariateProbs { home: 0.1, away: 0.1, common: 0.0 },
+                h2_goals: BivariateProbs { home: 0.1, away: 0.1, common: 0.0 },
+                assists: UnivariateProbs { home: 0.0, away: 0.0 },
+            },
+            player_probs: sv![],
+            prune_thresholds: Default::default(),
+            expansions: Expansions {
+                time_bands: 1 << 0 | 1 << 15,
+                ..Expansions::empty()
+            },
+        },
+        0..6,
+    );
+    // only two bands are tracked — the first 15 minutes and the remainder — irrespective of the intervals
+    assert_eq!(4, exploration.prospects.len());
+    assert_float_absolute_eq!(
+        0.2,
+        isolate(
+            &OfferType::GoalInTimeBand(TimeBand::new(0, 15)),
+            &Outcome::Yes,
+            &exploration.prospects,
+            &exploration.player_lookup
+        )
+    );
+}
+
+#[test]
+fn explore_2x2_scoring_team() {
+    let exploration = explore(
+        &Config {
+            intervals: 2,
+            team_probs: TeamProbs {
+                h1_goals: BivariateProbs { home: 0.2, away: 0.1, common: 0.0 },
+                h2_goals: BivariateProbs { home: 0.1, away: 0.2, common: 0.1 },
+                assists: UnivariateProbs { home: 0.0, away: 0.0 },
+            },
+            player_probs: sv![],
+            prune_thresholds: Default::default(),
+            expansions: Expansions {
+                ft_score: true,
+                first_scoring_team: true,
+                last_scoring_team: true,
+                max_race_goals: 2,
+                ..Expansions::empty()
+            },
+        },
+        0..2,
+    );
+    assert_float_absolute_eq!(1.0, exploration.prospects.values().sum::<f64>());
+
+    let isolate = |offer_type, outcome| isolate(&offer_type, &outcome, &exploration.prospects, &exploration.player_lookup);
+    assert_float_absolute_eq!(0.305, isolate(OfferType::FirstTeamToScore, Outcome::Team(Side::Home)));
+    assert_float_absolute_eq!(0.275, isolate(OfferType::FirstTeamToScore, Outcome::Team(Side::Away)));
+    assert_float_absolute_eq!(0.42, isolate(OfferType::FirstTeamToScore, Outcome::None));
+
+    // when both sides score in the same interval, either may score last
+    assert_float_absolute_eq!(0.27, isolate(OfferType::LastTeamToScore, Outcome::Team(Side::Home)));
+    assert_float_absolute_eq!(0.31, isolate(OfferType::LastTeamToScore, Outcome::Team(Side::Away)));
+    assert_float_absolute_eq!(0.42, isolate(OfferType::LastTeamToScore, Outcome::None));
+
+    // the race to one goal is the same as the first team to score
+    assert_float_absolute_eq!(0.305, isolate(OfferType::RaceToGoals(1), Outcome::Team(Side::Home)));
+    assert_float_absolute_eq!(0.275, isolate(OfferType::RaceToGoals(1), Outcome::Team(Side::Away)));
+
+    // a side leading by a goal at half time reaches two first if it scores again, even as the other side replies
+    assert_float_absolute_eq!(0.04, isolate(OfferType::RaceToGoals(2), Outcome::Team(Side::Home)));
+    assert_float_absolute_eq!(0.03, isolate(OfferType::RaceToGoals(2), Outcome::Team(Side::Away)));
+    assert_float_absolute_eq!(0.93, isolate(OfferType::RaceToGoals(2), Outcome::None));
+}
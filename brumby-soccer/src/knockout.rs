@@ -0,0 +1,257 @@
+//! Knockout ties, which are decided by extra time and then a penalty shootout if the sides are
+//! level at the end of regular time. In a two-legged tie, the aggregate score from the first leg
+//! is carried into the second, and only the second leg may go to extra time.
+
+use std::cmp::Ordering;
+
+use brumby::factorial;
+use brumby::multinomial::binomial;
+
+use crate::domain::{Outcome, Score, Side, VictoryMethod};
+use crate::interval::Prospects;
+
+/// A penalty shootout, modelled as best-of-five kicks per side followed by sudden death, with each
+/// kick converted independently of the others.
+#[derive(Debug, Clone)]
+pub struct Shootout {
+    pub home_conversion: f64,
+    pub away_conversion: f64,
+}
+impl Shootout {
+    pub const KICKS: u8 = 5;
+
+    /// The probability of the home side winning the shootout. The order of kicks does not affect
+    /// this probability as the conversions are independent; an early finish merely leaves the
+    /// remaining kicks untaken.
+    pub fn home_win_prob(&self) -> f64 {
+        let factorial = factorial::Calculator;
+        let home_goals = (0..=Self::KICKS)
+            .map(|goals| binomial(Self::KICKS, goals, self.home_conversion, &factorial))
+            .collect::<Vec<_>>();
+        let away_goals = (0..=Self::KICKS)
+            .map(|goals| binomial(Self::KICKS, goals, self.away_conversion, &factorial))
+            .collect::<Vec<_>>();
+
+        let mut home_win_prob = 0.0;
+        let mut level_prob = 0.0;
+        for (home, home_prob) in home_goals.iter().enumerate() {
+            for (away, away_prob) in away_goals.iter().enumerate() {
+                if home > away {
+                    home_win_prob += home_prob * away_prob;
+                } else if home == away {
+                    level_prob += home_prob * away_prob;
+                }
+            }
+        }
+
+        // sudden death: rounds are repeated until exactly one side converts
+        let home_round_prob = self.home_conversion * (1.0 - self.away_conversion);
+        let away_round_prob = self.away_conversion * (1.0 - self.home_conversion);
+        let sudden_death_prob = if home_round_prob + away_round_prob > 0.0 {
+            home_round_prob / (home_round_prob + away_round_prob)
+        } else {
+            0.5
+        };
+        home_win_prob + level_prob * sudden_death_prob
+    }
+}
+
+impl Default for Shootout {
+    fn default() -> Self {
+        Self {
+            home_conversion: 0.75,
+            away_conversion: 0.75,
+        }
+    }
+}
+
+/// The probability of each side progressing by each [VictoryMethod].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VictoryProbs {
+    pub home: [f64; 3],
+    pub away: [f64; 3],
+}
+impl VictoryProbs {
+    pub fn get(&self, side: &Side, method: &VictoryMethod) -> f64 {
+        let probs = match side {
+            Side::Home => &self.home,
+            Side::Away => &self.away,
+        };
+        probs[method_index(method)]
+    }
+
+    pub fn qualify(&self, side: &Side) -> f64 {
+        VictoryMethod::ALL
+            .iter()
+            .map(|method| self.get(side, method))
+            .sum()
+    }
+
+    pub fn isolate(&self, outcome: &Outcome) -> f64 {
+        match outcome {
+            Outcome::Qualify(side) => self.qualify(side),
+            Outcome::Victory(side, method) => self.get(side, method),
+            _ => panic!("{outcome:?} unsupported"),
+        }
+    }
+}
+
+fn method_index(method: &VictoryMethod) -> usize {
+    match method {
+        VictoryMethod::RegularTime => 0,
+        VictoryMethod::ExtraTime => 1,
+        VictoryMethod::Penalties => 2,
+    }
+}
+
+/// Combines the regular time and extra time explorations of a tie into the probabilities of each
+/// side progressing by each method. The aggregate score is that of the first leg (nil-all for a
+/// single-leg tie), as seen by the home side of the match being priced; ties level on aggregate go
+/// to extra time irrespective of away goals.
+pub fn isolate_victory(
+    aggregate: &Score,
+    regular_time: &Prospects,
+    extra_time: &Prospects,
+    shootout: &Shootout,
+) -> VictoryProbs {
+    let aggregate_margin = aggregate.home as i16 - aggregate.away as i16;
+    let mut victory_probs = VictoryProbs::default();
+    let mut level_prob = 0.0;
+    for (prospect, prob) in regular_time {
+        let margin = aggregate_margin + prospect.ft_score.home as i16 - prospect.ft_score.away as i16;
+        if margin > 0 {
+            victory_probs.home[method_index(&VictoryMethod::RegularTime)] += prob;
+        } else if margin < 0 {
+            victory_probs.away[method_index(&VictoryMethod::RegularTime)] += prob;
+        } else {
+            level_prob += prob;
+        }
+    }
+
+    let mut extra_time_level_prob = 0.0;
+    for (prospect, prob) in extra_time {
+        match prospect.ft_score.home.cmp(&prospect.ft_score.away) {
+            Ordering::Greater => {
+                victory_probs.home[method_index(&VictoryMethod::ExtraTime)] += level_prob * prob
+            }
+            Ordering::Less => {
+                victory_probs.away[method_index(&VictoryMethod::ExtraTime)] += level_prob * prob
+            }
+            Ordering::Equal => extra_time_level_prob += level_prob * prob,
+        }
+    }
+
+    let shootout_home_win_prob = shootout.home_win_prob();
+    victory_probs.home[method_index(&VictoryMethod::Penalties)] =
+        extra_time_level_prob * shootout_home_win_prob;
+    victory_probs.away[method_index(&VictoryMethod::Penalties)] =
+        extra_time_level_prob * (1.0 - shootout_home_win_prob);
+    victory_probs
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_float_eq::*;
+
+    use crate::interval::Prospect;
+
+    use super::*;
+
+    fn create_test_prospects(scores: &[(Score, f64)]) -> Prospects {
+        scores
+            .iter()
+            .map(|(score, prob)| {
+                (
+                    Prospect {
+                        ht_score: Score::nil_all(),
+                        ft_score: score.clone(),
+                        stats: Default::default(),
+                        first_scorer: None,
+                        last_scorer: None,
+                        first_goal_time: None,
+                        scoring_bands: 0,
+                        first_scoring_team: None,
+                        last_scoring_team: None,
+                        races_won_by_home: 0,
+                    },
+                    *prob,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn shootout_home_win_prob() {
+        let shootout = Shootout::default();
+        assert_float_absolute_eq!(0.5, shootout.home_win_prob());
+
+        let shootout = Shootout {
+            home_conversion: 1.0,
+            away_conversion: 1.0,
+        };
+        assert_float_absolute_eq!(0.5, shootout.home_win_prob());
+
+        let shootout = Shootout {
+            home_conversion: 1.0,
+            away_conversion: 0.0,
+        };
+        assert_float_absolute_eq!(1.0, shootout.home_win_prob());
+
+        let shootout = Shootout {
+            home_conversion: 0.8,
+            away_conversion: 0.7,
+        };
+        let home_win_prob = shootout.home_win_prob();
+        assert!(home_win_prob > 0.5 && home_win_prob < 0.8, "{home_win_prob}");
+    }
+
+    #[test]
+    fn isolate_victory_single_leg() {
+        let regular_time = create_test_prospects(&[
+            (Score::new(1, 0), 0.4),
+            (Score::new(0, 0), 0.3),
+            (Score::new(0, 1), 0.3),
+        ]);
+        let extra_time = create_test_prospects(&[
+            (Score::new(1, 0), 0.2),
+            (Score::new(0, 0), 0.7),
+            (Score::new(0, 1), 0.1),
+        ]);
+        let probs = isolate_victory(&Score::nil_all(), &regular_time, &extra_time, &Shootout::default());
+        assert_float_absolute_eq!(0.4, probs.get(&Side::Home, &VictoryMethod::RegularTime));
+        assert_float_absolute_eq!(0.3, probs.get(&Side::Away, &VictoryMethod::RegularTime));
+        assert_float_absolute_eq!(0.06, probs.get(&Side::Home, &VictoryMethod::ExtraTime));
+        assert_float_absolute_eq!(0.03, probs.get(&Side::Away, &VictoryMethod::ExtraTime));
+        assert_float_absolute_eq!(0.105, probs.get(&Side::Home, &VictoryMethod::Penalties));
+        assert_float_absolute_eq!(0.105, probs.get(&Side::Away, &VictoryMethod::Penalties));
+        assert_float_absolute_eq!(0.565, probs.isolate(&Outcome::Qualify(Side::Home)));
+        assert_float_absolute_eq!(0.435, probs.isolate(&Outcome::Qualify(Side::Away)));
+    }
+
+    #[test]
+    fn isolate_victory_with_aggregate() {
+        let regular_time = create_test_prospects(&[
+            (Score::new(2, 0), 0.1),
+            (Score::new(1, 0), 0.3),
+            (Score::new(0, 0), 0.3),
+            (Score::new(0, 1), 0.3),
+        ]);
+        let extra_time = create_test_prospects(&[
+            (Score::new(1, 0), 0.2),
+            (Score::new(0, 0), 0.7),
+            (Score::new(0, 1), 0.1),
+        ]);
+
+        // the home side trails by a goal from the first leg
+        let probs = isolate_victory(&Score::new(0, 1), &regular_time, &extra_time, &Shootout::default());
+        assert_float_absolute_eq!(0.1, probs.get(&Side::Home, &VictoryMethod::RegularTime));
+        assert_float_absolute_eq!(0.6, probs.get(&Side::Away, &VictoryMethod::RegularTime));
+        assert_float_absolute_eq!(0.06, probs.get(&Side::Home, &VictoryMethod::ExtraTime));
+        assert_float_absolute_eq!(0.03, probs.get(&Side::Away, &VictoryMethod::ExtraTime));
+        assert_float_absolute_eq!(0.21, probs.get(&Side::Home, &VictoryMethod::Penalties) * 2.0);
+        assert_float_absolute_eq!(
+            1.0,
+            probs.qualify(&Side::Home) + probs.qualify(&Side::Away)
+        );
+    }
+}
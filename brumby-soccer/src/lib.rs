@@ -2,8 +2,10 @@
 
 pub mod data;
 pub mod domain;
+pub mod events;
 pub mod fit;
 pub mod interval;
+pub mod knockout;
 pub mod model;
 pub mod print;
 pub mod scoregrid;
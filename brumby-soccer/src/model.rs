@@ -1,9 +1,10 @@
 use std::collections::hash_map::Entry;
 use std::error::Error;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use anyhow::anyhow;
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
 use thiserror::Error;
 use tracing::{debug, trace};
 
@@ -16,19 +17,26 @@ use brumby::stack_vec::FromIteratorResult;
 use brumby::sv;
 use brumby::timed::Timed;
 
+use crate::domain::settlement::SettlementProbs;
 use crate::domain::validation::{InvalidOffer, InvalidOfferType, InvalidOutcome, MissingOutcome, UnvalidatedOffer};
 use crate::domain::{
-    DrawHandicap, Offer, OfferCategory, OfferType, Outcome, Over, Period, Player, Side, WinHandicap,
+    DrawHandicap, EventStream, Offer, OfferCategory, OfferType, Outcome, Over, Period, Player,
+    Score, Side, WinHandicap,
 };
+use crate::events::EventModel;
 use crate::interval;
-use crate::interval::query::{isolate, requirements};
+use crate::interval::query::{isolate, isolate_cast, isolate_set_weighted, requirements};
 use crate::interval::{
     query, BivariateProbs, Expansions, Exploration, PlayerProbs, PruneThresholds, TeamProbs,
     UnivariateProbs,
 };
-use crate::model::cache::{CacheStats, CacheableIntervalArgs, CachingContext};
+use crate::knockout::{isolate_victory, Shootout};
+use crate::model::cache::{CacheStats, CacheableIntervalArgs, CachingContext, ExplorationCache};
+use crate::model::multi_pricing::{MultiPricingPolicy, RedundancyHandling};
 
-mod cache;
+pub mod cache;
+pub mod event_fitter;
+pub mod multi_pricing;
 pub mod player_assist_fitter;
 pub mod player_goal_fitter;
 pub mod score_fitter;
@@ -87,6 +95,19 @@ fn most_balanced_goals<'a>(
     most_balanced.map(|(offer, over)| (UnvalidatedOffer::from(Capture::Borrowed(offer)), over))
 }
 
+fn team_goals<'a>(
+    offers: impl Iterator<Item = &'a Offer>,
+    period: &Period,
+) -> Vec<UnvalidatedOffer<'a>> {
+    offers
+        .filter(|offer| match &offer.offer_type {
+            OfferType::TeamTotalGoals(p, _, _) | OfferType::ExactTeamGoals(p, _, _) => p == period,
+            _ => false,
+        })
+        .map(|offer| UnvalidatedOffer::from(Capture::Borrowed(offer)))
+        .collect()
+}
+
 fn get_or_create_player(
     player_probs: &mut FxHashMap<Player, PlayerProbs>,
     player: Player,
@@ -134,6 +155,9 @@ pub enum MultiDerivationError {
 
     #[error("{0}")]
     InvalidOfferType(#[from] InvalidOfferType),
+
+    #[error("{0}")]
+    InvalidPolicy(#[from] ValidationError),
 }
 
 #[derive(Debug, Error, PartialEq, Eq)]
@@ -172,6 +196,15 @@ pub enum UnmetRequirement {
 
     #[error("missing assist probability for {0:?}")]
     PlayerAssistProbability(Player),
+
+    #[error("missing knockout parameters")]
+    Knockout,
+
+    #[error("missing {0:?} model")]
+    EventModel(EventStream),
+
+    #[error("missing {0:?} share for {1:?}")]
+    PlayerEventShare(EventStream, Player),
 }
 
 #[derive(Debug, Error)]
@@ -190,6 +223,36 @@ pub struct GoalProbs {
     pub h2: BivariateProbs,
 }
 
+/// Extra time in a knockout tie, explored separately from regular time with its own intervals and
+/// goal probabilities for each of its two periods.
+#[derive(Debug, Clone)]
+pub struct ExtraTime {
+    pub intervals: u8,
+    pub goal_probs: GoalProbs,
+}
+impl ExtraTime {
+    /// Extends the second half scoring rate over the 30 minutes of extra time, given the number of
+    /// intervals in regular time.
+    pub fn from_regular_time(intervals: u8, goal_probs: &GoalProbs) -> Self {
+        Self {
+            intervals: u8::max(2, intervals / 3 / 2 * 2),
+            goal_probs: GoalProbs {
+                h1: goal_probs.h2.clone(),
+                h2: goal_probs.h2.clone(),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Knockout {
+    /// The aggregate score from the first leg of a two-legged tie, as seen by the home side of the
+    /// match being priced. Nil-all for a single-leg tie.
+    pub aggregate: Score,
+    pub extra_time: ExtraTime,
+    pub shootout: Shootout,
+}
+
 #[derive(Debug)]
 pub struct Stub {
     pub offer_type: OfferType,
@@ -228,6 +291,50 @@ pub struct MultiDerivation {
     pub fringes: FxHashMap<OfferType, Vec<Fringe>>,
 }
 
+/// A breakdown of how the price of a multi departs from the product of its single prices.
+#[derive(Debug)]
+pub struct MultiExplanation {
+    /// The product of the single probabilities of the legs that were kept.
+    pub independent_prob: f64,
+    /// The modelled probability of the kept legs occurring together.
+    pub joint_prob: f64,
+    /// The legs, in increasing order of single probability.
+    pub legs: Vec<LegExplanation>,
+    /// Every pairing of the legs.
+    pub pairs: Vec<PairExplanation>,
+    /// The overround composed from the charged legs, before correlation loading.
+    pub composed_overround: f64,
+    /// The overround of the quotation, after correlation loading.
+    pub overround: f64,
+    /// The probability mass that was pruned when exploring the legs.
+    pub pruned: f64,
+}
+
+#[derive(Debug)]
+pub struct LegExplanation {
+    pub offer_type: OfferType,
+    pub outcome: Outcome,
+    /// The fair probability of the single.
+    pub single_prob: f64,
+    /// The probability of the single, isolated from the same exploration as the multi.
+    pub model_prob: f64,
+    pub single_overround: f64,
+    /// Whether the leg contributes to the overround of the multi. A redundant leg is only
+    /// charged under [RedundancyHandling::Charge].
+    pub charged: bool,
+}
+
+#[derive(Debug)]
+pub struct PairExplanation {
+    /// The indices of the paired legs.
+    pub legs: (usize, usize),
+    /// The modelled probability of both legs occurring.
+    pub joint_prob: f64,
+    /// The ratio of the joint probability to the product of the model probabilities of the legs.
+    /// An uplift above 1 indicates positively correlated legs; below 1, negatively correlated.
+    pub uplift: f64,
+}
+
 #[derive(Debug)]
 pub struct Fringe {
     pub outcome: Outcome,
@@ -250,9 +357,45 @@ pub struct Model {
     pub goal_probs: Option<GoalProbs>,
     pub assist_probs: Option<UnivariateProbs>,
     pub player_probs: FxHashMap<Player, PlayerProbs>,
+    pub knockout: Option<Knockout>,
+    pub events: FxHashMap<EventStream, EventModel>,
     pub offers: FxHashMap<OfferType, Offer>,
+    exploration_cache: Arc<ExplorationCache>,
 }
 impl Model {
+    /// Replaces the model's exploration cache, e.g., with one of a different capacity. The cache is
+    /// cleared whenever the goal, assist or player probabilities of the model change, so a cache
+    /// should only be shared among models with the same probabilities.
+    pub fn with_exploration_cache(self, exploration_cache: Arc<ExplorationCache>) -> Self {
+        Self {
+            exploration_cache,
+            ..self
+        }
+    }
+
+    pub fn exploration_cache(&self) -> &Arc<ExplorationCache> {
+        &self.exploration_cache
+    }
+
+    fn caching_context(&self) -> CachingContext {
+        let mut player_probs = self
+            .player_probs
+            .iter()
+            .map(|(player, player_probs)| (player.clone(), player_probs.clone()))
+            .collect::<Vec<_>>();
+        player_probs.sort_by(|(a, _), (b, _)| a.cmp(b));
+        let goal_probs = self
+            .goal_probs
+            .as_ref()
+            .map(|goal_probs| (goal_probs.h1.clone(), goal_probs.h2.clone()));
+        let fingerprint = bincode::encode_to_vec(
+            (goal_probs, self.assist_probs.clone(), player_probs),
+            bincode::config::standard(),
+        )
+        .unwrap();
+        CachingContext::new(self.exploration_cache.clone(), &fingerprint)
+    }
+
     pub fn offers(&self) -> &FxHashMap<OfferType, Offer> {
         &self.offers
     }
@@ -273,7 +416,7 @@ impl Model {
         price_bounds: &PriceBounds,
     ) -> Result<Timed<CacheStats>, SingleDerivationError> {
         Timed::result(|| {
-            let mut caching_context = CachingContext::default();
+            let mut caching_context = self.caching_context();
             let mut auxiliary_stubs = vec![];
             for stub in stubs {
                 stub.offer_type.validate()?;
@@ -287,7 +430,10 @@ impl Model {
                         stub.outcomes.len()
                     );
                     let start = Instant::now();
-                    let offer = self.derive_offer(stub, price_bounds, &mut caching_context)?;
+                    let offer = match stub.offer_type.event_stream() {
+                        Some(stream) => self.derive_event_offer(stream, stub, price_bounds)?,
+                        None => self.derive_offer(stub, price_bounds, &mut caching_context)?,
+                    };
                     debug!(
                         "... took {:?}, progressive {:?}",
                         start.elapsed(),
@@ -307,6 +453,12 @@ impl Model {
                     OfferType::SplitHandicap(_, _, _) => {
                         self.derive_split_handicap(stub, price_bounds)?
                     }
+                    OfferType::SplitTotalGoals(_, _, _) => {
+                        self.derive_split_total_goals(stub, price_bounds, &mut caching_context)?
+                    }
+                    OfferType::ToQualify | OfferType::MethodOfVictory => {
+                        self.derive_knockout(stub, price_bounds, &mut caching_context)?
+                    }
                     _ => unreachable!(),
                 };
                 self.insert_offer(offer);
@@ -337,7 +489,51 @@ impl Model {
             min_prob: 0.0,
         };
 
-        let offer = if requires_player_goal_probs || requires_player_assist_probs {
+        let offer = if matches!(stub.offer_type, OfferType::Scorecast | OfferType::Wincast) {
+            // player combination grids — explored once per player, and tabulated in a single pass
+            let mut explored_players = FxHashSet::default();
+            let mut probs_by_outcome = FxHashMap::default();
+            for outcome in &stub.outcomes {
+                let player = outcome.get_player().unwrap();
+                if !explored_players.insert(player) {
+                    continue;
+                }
+                let player_probs = PlayerProbs {
+                    goal: Some(self.require_player_goal_prob(player)?),
+                    assist: None,
+                };
+                let exploration = caching_context.explore(CacheableIntervalArgs {
+                    config: interval::Config {
+                        intervals: self.config.intervals,
+                        team_probs: team_probs.clone(),
+                        player_probs: sv![(player.clone(), player_probs)],
+                        prune_thresholds: prune_thresholds.clone(),
+                        expansions: reqs.clone(),
+                    },
+                    include_intervals: 0..self.config.intervals,
+                });
+                probs_by_outcome.extend(isolate_cast(
+                    &stub.offer_type,
+                    player,
+                    &exploration.prospects,
+                    &exploration.player_lookup,
+                ));
+            }
+            let mut probs = stub
+                .outcomes
+                .items()
+                .iter()
+                .map(|outcome| probs_by_outcome.get(outcome).copied().unwrap_or_default())
+                .collect::<Vec<_>>();
+            debug!("... normalizing: {} -> {}", probs.sum(), stub.normal);
+            probs.normalise(stub.normal);
+            let market = Market::frame(&stub.overround, probs, price_bounds);
+            Offer {
+                offer_type: stub.offer_type.clone(),
+                outcomes: stub.outcomes.clone(),
+                market,
+            }
+        } else if requires_player_goal_probs || requires_player_assist_probs {
             // requires player probabilities — must be explored individually for each outcome
             let mut probs = Vec::with_capacity(stub.outcomes.len());
             for outcome in &stub.outcomes {
@@ -417,7 +613,7 @@ impl Model {
             // }
 
             frame_prices_from_exploration(
-                exploration,
+                &exploration,
                 &stub.offer_type,
                 stub.outcomes.items(),
                 stub.normal,
@@ -428,6 +624,31 @@ impl Model {
         Ok(offer)
     }
 
+    fn derive_event_offer(
+        &self,
+        stream: &EventStream,
+        stub: &Stub,
+        price_bounds: &PriceBounds,
+    ) -> Result<Offer, SingleDerivationError> {
+        let event_model = self.require_event_model(stream)?;
+        for outcome in stub.outcomes.items() {
+            self.require_player_event_share(stream, outcome)?;
+        }
+        let mut probs = stub
+            .outcomes
+            .items()
+            .iter()
+            .map(|outcome| event_model.isolate(&stub.offer_type, outcome))
+            .collect::<Vec<_>>();
+        probs.normalise(stub.normal);
+        let market = Market::frame(&stub.overround, probs, price_bounds);
+        Ok(Offer {
+            offer_type: stub.offer_type.clone(),
+            outcomes: stub.outcomes.clone(),
+            market,
+        })
+    }
+
     #[inline(always)]
     fn derive_draw_no_bet(
         &mut self,
@@ -481,23 +702,31 @@ impl Model {
         let draw_prob = euro_offer
             .get_probability(&Outcome::Draw(draw_handicap.clone()))
             .unwrap();
-        let (home_prob, away_prob) = match (draw_handicap, win_handicap) {
+        let home_settlement_probs = match (draw_handicap, win_handicap) {
             (DrawHandicap::Ahead(ahead), WinHandicap::AheadOver(ahead_over)) => {
                 if ahead == ahead_over {
                     // -x.25 case
                     let asian_win_prob = asian_offer
                         .get_probability(&Outcome::Win(Side::Home, win_handicap.clone()))
                         .unwrap();
-                    let home_prob = asian_win_prob / (1.0 - 0.5 * draw_prob);
-                    (home_prob, 1.0 - home_prob)
+                    SettlementProbs {
+                        win: asian_win_prob,
+                        half_loss: draw_prob,
+                        loss: 1.0 - asian_win_prob - draw_prob,
+                        ..SettlementProbs::default()
+                    }
                 } else {
                     // -x.75 case
                     assert_eq!(*ahead, ahead_over + 1);
                     let euro_win_prob = euro_offer
                         .get_probability(&Outcome::Win(Side::Home, draw_handicap.to_win_handicap()))
                         .unwrap();
-                    let home_prob = (euro_win_prob + 0.5 * draw_prob) / (1.0 - 0.5 * draw_prob);
-                    (home_prob, 1.0 - home_prob)
+                    SettlementProbs {
+                        win: euro_win_prob,
+                        half_win: draw_prob,
+                        loss: 1.0 - euro_win_prob - draw_prob,
+                        ..SettlementProbs::default()
+                    }
                 }
             }
             (_, WinHandicap::BehindUnder(behind_under)) => {
@@ -511,20 +740,40 @@ impl Model {
                     let euro_win_prob = euro_offer
                         .get_probability(&Outcome::Win(Side::Away, draw_handicap.to_win_handicap().flip_european()))
                         .unwrap();
-                    let away_prob = (euro_win_prob + 0.5 * draw_prob) / (1.0 - 0.5 * draw_prob);
-                    (1.0 - away_prob, away_prob)
+                    SettlementProbs {
+                        win: 1.0 - euro_win_prob - draw_prob,
+                        half_loss: draw_prob,
+                        loss: euro_win_prob,
+                        ..SettlementProbs::default()
+                    }
                 } else {
                     // +x.25 case
                     assert_eq!(behind + 1, *behind_under);
                     let asian_win_prob = asian_offer
                         .get_probability(&Outcome::Win(Side::Away, win_handicap.flip_asian()))
                         .unwrap();
-                    let away_prob = asian_win_prob / (1.0 - 0.5 * draw_prob);
-                    (1.0 - away_prob, away_prob)
+                    SettlementProbs {
+                        win: 1.0 - asian_win_prob - draw_prob,
+                        half_win: draw_prob,
+                        loss: asian_win_prob,
+                        ..SettlementProbs::default()
+                    }
                 }
             }
             _ => unreachable!(),
         };
+        // the away side settles inversely to the home side
+        let away_settlement_probs = SettlementProbs {
+            win: home_settlement_probs.loss,
+            half_win: home_settlement_probs.half_loss,
+            push: home_settlement_probs.push,
+            half_loss: home_settlement_probs.half_win,
+            loss: home_settlement_probs.win,
+        };
+        let (home_prob, away_prob) = (
+            home_settlement_probs.effective_probability(),
+            away_settlement_probs.effective_probability(),
+        );
 
         let home_outcome = Outcome::SplitWin(Side::Home, draw_handicap.clone(), win_handicap.clone());
         let away_outcome = Outcome::SplitWin(Side::Away, draw_handicap.flip(), win_handicap.flip_asian());
@@ -540,169 +789,226 @@ impl Model {
         })
     }
 
-    pub fn derive_multi(
+    #[inline(always)]
+    fn derive_split_total_goals(
+        &mut self,
+        stub: &Stub,
+        price_bounds: &PriceBounds,
+        caching_context: &mut CachingContext,
+    ) -> Result<Offer, SingleDerivationError> {
+        let reqs = requirements(&stub.offer_type);
+        self.ensure_team_requirements(&reqs)?;
+        let exploration = caching_context.explore(CacheableIntervalArgs {
+            config: interval::Config {
+                intervals: self.config.intervals,
+                team_probs: TeamProbs {
+                    h1_goals: self.goal_probs.clone().unwrap_or_default().h1,
+                    h2_goals: self.goal_probs.clone().unwrap_or_default().h2,
+                    assists: self.assist_probs.clone().unwrap_or_default(),
+                },
+                player_probs: sv![],
+                prune_thresholds: PruneThresholds {
+                    max_total_goals: self.config.max_total_goals,
+                    min_prob: 0.0,
+                },
+                expansions: reqs,
+            },
+            include_intervals: 0..self.config.intervals,
+        });
+
+        // pushes and half-settlements are priced by the effective probability of each outcome
+        let mut probs = stub
+            .outcomes
+            .items()
+            .iter()
+            .map(|outcome| {
+                query::isolate_settlement(&stub.offer_type, outcome, &exploration.prospects)
+                    .effective_probability()
+            })
+            .collect::<Vec<_>>();
+        probs.normalise(stub.normal);
+        let market = Market::frame(&stub.overround, probs, price_bounds);
+        Ok(Offer {
+            offer_type: stub.offer_type.clone(),
+            outcomes: stub.outcomes.clone(),
+            market,
+        })
+    }
+
+    #[inline(always)]
+    fn derive_knockout(
+        &mut self,
+        stub: &Stub,
+        price_bounds: &PriceBounds,
+        caching_context: &mut CachingContext,
+    ) -> Result<Offer, SingleDerivationError> {
+        let goal_probs = self.require_team_goal_probs()?.clone();
+        let knockout = self.require_knockout()?.clone();
+        let reqs = requirements(&OfferType::HeadToHead(Period::FullTime, DrawHandicap::Ahead(0)));
+        let prune_thresholds = PruneThresholds {
+            max_total_goals: self.config.max_total_goals,
+            min_prob: 0.0,
+        };
+        let regular_time = caching_context.explore(CacheableIntervalArgs {
+            config: interval::Config {
+                intervals: self.config.intervals,
+                team_probs: TeamProbs {
+                    h1_goals: goal_probs.h1,
+                    h2_goals: goal_probs.h2,
+                    assists: Default::default(),
+                },
+                player_probs: sv![],
+                prune_thresholds: prune_thresholds.clone(),
+                expansions: reqs.clone(),
+            },
+            include_intervals: 0..self.config.intervals,
+        });
+        let regular_time = regular_time.prospects.clone();
+        let extra_time = caching_context.explore(CacheableIntervalArgs {
+            config: interval::Config {
+                intervals: knockout.extra_time.intervals,
+                team_probs: TeamProbs {
+                    h1_goals: knockout.extra_time.goal_probs.h1,
+                    h2_goals: knockout.extra_time.goal_probs.h2,
+                    assists: Default::default(),
+                },
+                player_probs: sv![],
+                prune_thresholds,
+                expansions: reqs,
+            },
+            include_intervals: 0..knockout.extra_time.intervals,
+        });
+        let victory_probs = isolate_victory(
+            &knockout.aggregate,
+            &regular_time,
+            &extra_time.prospects,
+            &knockout.shootout,
+        );
+
+        let mut probs = stub
+            .outcomes
+            .items()
+            .iter()
+            .map(|outcome| victory_probs.isolate(outcome))
+            .collect::<Vec<_>>();
+        probs.normalise(stub.normal);
+        let market = Market::frame(&stub.overround, probs, price_bounds);
+        Ok(Offer {
+            offer_type: stub.offer_type.clone(),
+            outcomes: stub.outcomes.clone(),
+            market,
+        })
+    }
+
+    /// Collects the requirements and player probabilities of a multi, and details its selections,
+    /// sorted by increasing probability.
+    fn resolve_multi(
         &self,
         selections: &[(OfferType, Outcome)],
-    ) -> Result<Timed<MultiDerivation>, MultiDerivationError> {
+    ) -> Result<ResolvedMulti, MultiDerivationError> {
         if selections.is_empty() {
             return Err(MultiDerivationError::NoSelections(NoSelections));
         }
-        const PRUNE_MIN_PROB: f64 = 1e-4;
-
-        #[inline(always)]
-        fn product_of_overrounds(selections: &[DetailedSelection]) -> f64 {
-            selections
-                .iter()
-                .map(|selection| selection.single_overround)
-                .product()
-        }
-
-        #[inline(always)]
-        fn product_of_unrelated_probs(selections: &[DetailedSelection]) -> f64 {
-            selections
-                .iter()
-                .map(|selection| selection.single_prob)
-                .product()
-        }
-
-        #[inline(always)]
-        fn resolve_selection(
-            offer: &Offer,
-            outcome: &Outcome,
-            outcome_index: usize,
-        ) -> DetailedSelection {
-            let single_prob = offer.market.probs[outcome_index];
-            let single_price = offer.market.prices[outcome_index];
-            let single_overround = 1.0 / single_prob / single_price;
-            DetailedSelection {
-                offer_type: offer.offer_type.clone(),
-                outcome: outcome.clone(),
-                single_overround,
-                single_prob,
+        let mut player_probs = FxHashMap::<Player, PlayerProbs>::with_capacity_and_hasher(
+            interval::NUM_PLAYERS,
+            Default::default(),
+        );
+        let mut reqs = Expansions::empty();
+        let mut sorted_selections = Vec::with_capacity(selections.len());
+        for (offer_type, outcome) in selections {
+            if offer_type.is_auxiliary() {
+                return Err(MultiDerivationError::AuxiliaryOffer(AuxiliaryOffer {
+                    offer_type: offer_type.clone(),
+                }));
             }
-        }
 
-        #[inline(always)]
-        fn strip_details(selections: Vec<DetailedSelection>) -> Vec<(OfferType, Outcome)> {
-            selections
-                .into_iter()
-                .map(|selection| (selection.offer_type, selection.outcome))
-                .collect()
-        }
+            self.collect_requirements(offer_type, outcome, &mut reqs, &mut player_probs)?;
 
-        #[inline(always)]
-        fn sort_selections_by_increasing_prob(selections: &mut [DetailedSelection]) {
-            selections.sort_by(|s1, s2| s1.single_prob.total_cmp(&s2.single_prob))
+            let offer =
+                self.offers
+                    .get(offer_type)
+                    .ok_or(MultiDerivationError::MissingDerivative(MissingDerivative {
+                        offer_type: offer_type.clone(),
+                        outcome: outcome.clone(),
+                    }))?;
+            let outcome_index = offer.outcomes.index_of(outcome).ok_or(
+                MultiDerivationError::MissingDerivative(MissingDerivative {
+                    offer_type: offer_type.clone(),
+                    outcome: outcome.clone(),
+                }),
+            )?;
+            let selection = resolve_selection(offer, outcome, outcome_index);
+            sorted_selections.push(selection);
         }
+        trace!("agg_reqs: {reqs:?}, agg_player_probs: {player_probs:?}");
+        sort_selections_by_increasing_prob(&mut sorted_selections);
+        trace!("sorted selections: {sorted_selections:?}");
+        Ok(ResolvedMulti {
+            reqs,
+            player_probs,
+            sorted_selections,
+        })
+    }
 
-        struct ScanPrefixResult {
-            keep: Vec<DetailedSelection>,
-            drop: Vec<DetailedSelection>,
-            lowest_prob: f64,
-        }
+    /// The exploration config for a multi. Players are ordered, so that multis involving the same
+    /// players share the same config.
+    fn multi_config(
+        &self,
+        reqs: &Expansions,
+        player_probs: &FxHashMap<Player, PlayerProbs>,
+        policy: &MultiPricingPolicy,
+    ) -> Result<interval::Config, TooManyPlayers> {
+        let mut sorted_player_probs = player_probs
+            .iter()
+            .map(|(player, player_probs)| (player.clone(), player_probs.clone()))
+            .collect::<Vec<_>>();
+        sorted_player_probs.sort_by(|(a, _), (b, _)| a.cmp(b));
+        let FromIteratorResult(player_probs) = sorted_player_probs.into_iter().collect();
+        let player_probs = player_probs.map_err(|err| TooManyPlayers {
+            capacity: err.capacity,
+        })?;
+        Ok(interval::Config {
+            intervals: self.config.intervals,
+            team_probs: TeamProbs {
+                h1_goals: self.goal_probs.clone().unwrap_or_default().h1,
+                h2_goals: self.goal_probs.clone().unwrap_or_default().h2,
+                assists: self.assist_probs.clone().unwrap_or_default(),
+            },
+            player_probs,
+            prune_thresholds: PruneThresholds {
+                max_total_goals: self.config.max_total_goals,
+                min_prob: policy.prune_min_prob,
+            },
+            expansions: reqs.clone(),
+        })
+    }
 
-        #[inline(always)]
-        fn scan_prefix(
-            sorted_selections: &[DetailedSelection],
-            exploration: &Exploration,
-        ) -> ScanPrefixResult {
-            let mut keep = Vec::with_capacity(sorted_selections.len());
-            let mut drop = vec![];
-            let mut lowest_prob = f64::MAX;
-
-            for end_index in 1..=sorted_selections.len() {
-                let prefix = sorted_selections[0..end_index]
-                    .iter()
-                    .map(|selection| (selection.offer_type.clone(), selection.outcome.clone()))
-                    .collect::<Vec<_>>();
-                let prob =
-                    query::isolate_set(&prefix, &exploration.prospects, &exploration.player_lookup);
-                // if LOG { trace!("fringe prefix: {prefix:?}, prob: {prob:.3}"); }
-                let tail = &sorted_selections[end_index - 1];
-                if prob < lowest_prob {
-                    lowest_prob = prob;
-                    keep.push(tail.clone());
-                } else {
-                    drop.push(tail.clone());
-                }
-            }
+    /// Derives a multi under the default [MultiPricingPolicy].
+    pub fn derive_multi(
+        &self,
+        selections: &[(OfferType, Outcome)],
+    ) -> Result<Timed<MultiDerivation>, MultiDerivationError> {
+        self.derive_multi_with_policy(selections, &MultiPricingPolicy::default())
+    }
 
-            ScanPrefixResult {
-                keep,
-                drop,
-                lowest_prob,
-            }
-        }
+    pub fn derive_multi_with_policy(
+        &self,
+        selections: &[(OfferType, Outcome)],
+        policy: &MultiPricingPolicy,
+    ) -> Result<Timed<MultiDerivation>, MultiDerivationError> {
+        policy.validate()?;
 
         Timed::result(|| {
-            let mut caching_context = CachingContext::default();
-            let mut agg_player_probs = FxHashMap::<Player, PlayerProbs>::with_capacity_and_hasher(
-                interval::NUM_PLAYERS,
-                Default::default(),
-            );
-            let mut agg_reqs = Expansions::empty();
-            let mut sorted_selections = Vec::with_capacity(selections.len());
+            let mut caching_context = self.caching_context();
+            let ResolvedMulti {
+                reqs: agg_reqs,
+                player_probs: agg_player_probs,
+                sorted_selections,
+            } = self.resolve_multi(selections)?;
+            let config = self.multi_config(&agg_reqs, &agg_player_probs, policy)?;
 
             let mut exploration_elapsed = Duration::default();
             let mut query_elapsed = Duration::default();
-            for (offer_type, outcome) in selections {
-                if offer_type.is_auxiliary() {
-                    return Err(MultiDerivationError::AuxiliaryOffer(AuxiliaryOffer {
-                        offer_type: offer_type.clone(),
-                    }));
-                }
-
-                self.collect_requirements(
-                    offer_type,
-                    outcome,
-                    &mut agg_reqs,
-                    &mut agg_player_probs,
-                )?;
-
-                let offer =
-                    self.offers
-                        .get(offer_type)
-                        .ok_or(MultiDerivationError::MissingDerivative(MissingDerivative {
-                            offer_type: offer_type.clone(),
-                            outcome: outcome.clone(),
-                        }))?;
-                let outcome_index = offer.outcomes.index_of(outcome).ok_or(
-                    MultiDerivationError::MissingDerivative(MissingDerivative {
-                        offer_type: offer_type.clone(),
-                        outcome: outcome.clone(),
-                    }),
-                )?;
-                let selection = resolve_selection(offer, outcome, outcome_index);
-                sorted_selections.push(selection);
-            }
-            trace!("agg_reqs: {agg_reqs:?}, agg_player_probs: {agg_player_probs:?}");
-            sort_selections_by_increasing_prob(&mut sorted_selections);
-            trace!("sorted selections: {sorted_selections:?}");
-
-            let FromIteratorResult(player_probs) = agg_player_probs
-                .iter()
-                .map(|(player, player_probs)| (player.clone(), player_probs.clone()))
-                .collect();
-            let player_probs = player_probs.map_err(|err| TooManyPlayers {
-                capacity: err.capacity,
-            })?;
-            let team_probs = TeamProbs {
-                h1_goals: self.goal_probs.clone().unwrap_or_default().h1,
-                h2_goals: self.goal_probs.clone().unwrap_or_default().h2,
-                assists: self.assist_probs.clone().unwrap_or_default(),
-            };
-            let prune_thresholds = PruneThresholds {
-                max_total_goals: self.config.max_total_goals,
-                min_prob: PRUNE_MIN_PROB,
-            };
-            let config = interval::Config {
-                intervals: self.config.intervals,
-                team_probs: team_probs.clone(),
-                player_probs,
-                prune_thresholds: prune_thresholds.clone(),
-                expansions: agg_reqs.clone(),
-            };
-
             let exploration_start = Instant::now();
             let exploration = caching_context.explore(CacheableIntervalArgs {
                 config,
@@ -716,7 +1022,12 @@ impl Model {
             );
             let pruned = exploration.pruned;
             let query_start = Instant::now();
-            let scan_result = scan_prefix(&sorted_selections, exploration);
+            let scan_result = scan_prefix(
+                &sorted_selections,
+                &exploration,
+                &self.events,
+                &policy.redundancies,
+            );
             query_elapsed += query_start.elapsed();
 
             let mut fringes =
@@ -773,22 +1084,19 @@ impl Model {
                     //     trace!("fringe_sorted selections: {fringe_sorted_selections:?}");
                     // }
 
-                    let FromIteratorResult(fringe_player_probs) =
-                        fringe_agg_player_probs.into_iter().collect();
-                    let player_probs = match fringe_player_probs {
-                        Ok(player_probs) => player_probs,
+                    // the players are ordered as for the multi, so that identical fringe
+                    // explorations are served from the cache
+                    let config = match self.multi_config(
+                        &fringe_agg_reqs,
+                        &fringe_agg_player_probs,
+                        policy,
+                    ) {
+                        Ok(config) => config,
                         Err(_) => {
                             trace!("skipping {offer_type:?}/{outcome:?}: too many players");
                             continue;
                         }
                     };
-                    let config = interval::Config {
-                        intervals: self.config.intervals,
-                        team_probs: team_probs.clone(),
-                        player_probs,
-                        prune_thresholds: prune_thresholds.clone(),
-                        expansions: fringe_agg_reqs.clone(),
-                    };
 
                     if fringe_exploration.is_none() || !reuse_exploration {
                         let exploration_start = Instant::now();
@@ -799,23 +1107,27 @@ impl Model {
                         exploration_elapsed += exploration_start.elapsed();
                         trace!(
                             "fringe {offer_type:?}/{outcome:?}, prospects: {}, took {:?}",
-                            fringe_exploration.unwrap().prospects.len(),
+                            fringe_exploration.as_ref().unwrap().prospects.len(),
                             exploration_start.elapsed()
                         );
                     }
-                    let fringe_exploration = fringe_exploration.unwrap();
+                    let fringe_exploration = fringe_exploration.as_ref().unwrap();
 
                     let query_start = Instant::now();
                     let fringe_scan_result =
-                        scan_prefix(&fringe_sorted_selections, fringe_exploration);
+                        scan_prefix(
+                            &fringe_sorted_selections,
+                            fringe_exploration,
+                            &self.events,
+                            &policy.redundancies,
+                        );
                     query_elapsed += query_start.elapsed();
 
                     let probability = fringe_scan_result.lowest_prob;
-                    let overround = product_of_overrounds(&fringe_scan_result.keep);
-                    let price = 1.0 / probability / overround;
                     let unrelated_prob = product_of_unrelated_probs(&fringe_scan_result.keep);
                     let relatedness = unrelated_prob / probability;
-                    let redundancies = strip_details(fringe_scan_result.drop);
+                    let quotation = quote(policy, &fringe_scan_result, relatedness);
+                    let redundancies = strip_details(fringe_scan_result.redundant);
 
                     // if LOG {
                     //     trace!("probability: {probability:.3}, overround: {overround:.3}, price: {price:.3}, pruned: {:.3}", exploration.pruned);
@@ -825,7 +1137,7 @@ impl Model {
                     // }
                     fringes_vec.push(Fringe {
                         outcome: outcome.clone(),
-                        quotation: DerivedPrice { probability, price },
+                        quotation,
                         redundancies,
                         relatedness,
                     });
@@ -839,13 +1151,12 @@ impl Model {
             debug!("elapsed: exploration: {exploration_elapsed:?}, query: {query_elapsed:?}");
 
             let probability = scan_result.lowest_prob;
-            let overround = product_of_overrounds(&scan_result.keep);
-            let price = 1.0 / probability / overround;
             let unrelated_prob = product_of_unrelated_probs(&scan_result.keep);
             let relatedness = unrelated_prob / probability;
-            let redundancies = strip_details(scan_result.drop);
+            let quotation = quote(policy, &scan_result, relatedness);
+            let redundancies = strip_details(scan_result.redundant);
             Ok(MultiDerivation {
-                quotation: DerivedPrice { probability, price },
+                quotation,
                 redundancies,
                 relatedness,
                 fringes,
@@ -853,6 +1164,128 @@ impl Model {
         })
     }
 
+    /// Explains the price of a multi, as derived by [Model::derive_multi_with_policy], leg by leg
+    /// and pair by pair. Isolating every pairing of the legs adds to the cost of pricing, so the
+    /// explanation is requested separately to the derivation, where it is needed.
+    pub fn explain_multi(
+        &self,
+        selections: &[(OfferType, Outcome)],
+        policy: &MultiPricingPolicy,
+    ) -> Result<Timed<MultiExplanation>, MultiDerivationError> {
+        policy.validate()?;
+
+        Timed::result(|| {
+            let mut caching_context = self.caching_context();
+            let resolved = self.resolve_multi(selections)?;
+            let config = self.multi_config(&resolved.reqs, &resolved.player_probs, policy)?;
+            let exploration = caching_context.explore(CacheableIntervalArgs {
+                config,
+                include_intervals: 0..self.config.intervals,
+            });
+            let scan_result = scan_prefix(
+                &resolved.sorted_selections,
+                &exploration,
+                &self.events,
+                &policy.redundancies,
+            );
+            let unrelated_prob = product_of_unrelated_probs(&scan_result.keep);
+            let relatedness = unrelated_prob / scan_result.lowest_prob;
+            let quotation = quote(policy, &scan_result, relatedness);
+            Ok(explain(
+                policy,
+                &scan_result,
+                &quotation,
+                &exploration,
+                &self.events,
+            ))
+        })
+    }
+
+    /// Derives many multis at once, without fringes. Multis that require the same exploration are
+    /// priced from a single exploration of it, and are quoted exactly as by [Model::derive_multi].
+    /// Each multi is derived (or fails) independently of the others.
+    pub fn derive_multi_batch(
+        &self,
+        selection_sets: &[Vec<(OfferType, Outcome)>],
+        policy: &MultiPricingPolicy,
+    ) -> Result<Timed<Vec<Result<MultiDerivation, MultiDerivationError>>>, MultiDerivationError>
+    {
+        policy.validate()?;
+
+        Timed::result(|| {
+            let mut caching_context = self.caching_context();
+            let mut derivations = Vec::with_capacity(selection_sets.len());
+            derivations.resize_with(selection_sets.len(), || None);
+
+            // group the multis by their exploration config; merging multis with differing configs
+            // would alter the pruning of the exploration, and with it the price of each member
+            let mut group_indexes =
+                FxHashMap::<Vec<u8>, usize>::with_capacity_and_hasher(0, Default::default());
+            let mut groups: Vec<ExplorationGroup> = vec![];
+            for (set_index, selections) in selection_sets.iter().enumerate() {
+                let resolved = self.resolve_multi(selections).and_then(|resolved| {
+                    let config =
+                        self.multi_config(&resolved.reqs, &resolved.player_probs, policy)?;
+                    Ok((config, resolved.sorted_selections))
+                });
+                match resolved {
+                    Ok((config, sorted_selections)) => {
+                        let key =
+                            bincode::encode_to_vec(&config, bincode::config::standard()).unwrap();
+                        let group_index = *group_indexes.entry(key).or_insert_with(|| {
+                            groups.push(ExplorationGroup {
+                                config,
+                                members: vec![],
+                            });
+                            groups.len() - 1
+                        });
+                        groups[group_index]
+                            .members
+                            .push((set_index, sorted_selections));
+                    }
+                    Err(err) => derivations[set_index] = Some(Err(err)),
+                }
+            }
+            debug!(
+                "multis: {}, explorations: {}",
+                selection_sets.len(),
+                groups.len()
+            );
+
+            for ExplorationGroup { config, members } in groups {
+                let exploration = caching_context.explore(CacheableIntervalArgs {
+                    config,
+                    include_intervals: 0..self.config.intervals,
+                });
+                for (set_index, sorted_selections) in members {
+                    let scan_result = scan_prefix(
+                        &sorted_selections,
+                        &exploration,
+                        &self.events,
+                        &policy.redundancies,
+                    );
+                    let probability = scan_result.lowest_prob;
+                    let unrelated_prob = product_of_unrelated_probs(&scan_result.keep);
+                    let relatedness = unrelated_prob / probability;
+                    let quotation = quote(policy, &scan_result, relatedness);
+                    let redundancies = strip_details(scan_result.redundant);
+                    derivations[set_index] = Some(Ok(MultiDerivation {
+                        quotation,
+                        redundancies,
+                        relatedness,
+                        fringes: FxHashMap::default(),
+                    }));
+                }
+            }
+            debug!("cache stats: {:?}", caching_context.stats);
+
+            Ok(derivations
+                .into_iter()
+                .map(|derivation| derivation.unwrap())
+                .collect())
+        })
+    }
+
     fn collect_requirements(
         &self,
         offer_type: &OfferType,
@@ -862,6 +1295,10 @@ impl Model {
     ) -> Result<(), MultiDerivationError> {
         offer_type.validate()?;
         offer_type.validate_outcome(outcome)?;
+        if let Some(stream) = offer_type.event_stream() {
+            self.require_event_model(stream)?;
+            self.require_player_event_share(stream, outcome)?;
+        }
         let reqs = requirements(offer_type);
         let requires_player_goal_probs = reqs.requires_player_goal_probs();
         let requires_player_assist_probs = reqs.requires_player_assist_probs();
@@ -907,6 +1344,33 @@ impl Model {
             .ok_or(UnmetRequirement::TeamAssistProbabilities)
     }
 
+    fn require_knockout(&self) -> Result<&Knockout, UnmetRequirement> {
+        self.knockout.as_ref().ok_or(UnmetRequirement::Knockout)
+    }
+
+    fn require_event_model(&self, stream: &EventStream) -> Result<&EventModel, UnmetRequirement> {
+        self.events
+            .get(stream)
+            .ok_or_else(|| UnmetRequirement::EventModel(stream.clone()))
+    }
+
+    /// Ensures that the event model of the stream carries the share of the player in a player
+    /// outcome. Other outcomes need no share.
+    fn require_player_event_share(
+        &self,
+        stream: &EventStream,
+        outcome: &Outcome,
+    ) -> Result<(), UnmetRequirement> {
+        match outcome {
+            Outcome::Player(player) => self
+                .require_event_model(stream)?
+                .player_share(player)
+                .map(|_| ())
+                .ok_or_else(|| UnmetRequirement::PlayerEventShare(stream.clone(), player.clone())),
+            _ => Ok(()),
+        }
+    }
+
     fn require_player_goal_prob(&self, player: &Player) -> Result<f64, UnmetRequirement> {
         self.player_probs
             .get(player)
@@ -922,10 +1386,248 @@ impl Model {
     }
 }
 
+#[inline(always)]
+fn single_overrounds(selections: &[DetailedSelection]) -> Vec<f64> {
+    selections
+        .iter()
+        .map(|selection| selection.single_overround)
+        .collect()
+}
+
+#[inline(always)]
+fn quote(
+    policy: &MultiPricingPolicy,
+    scan_result: &ScanPrefixResult,
+    relatedness: f64,
+) -> DerivedPrice {
+    policy.quote(
+        scan_result.lowest_prob,
+        &single_overrounds(&scan_result.keep),
+        relatedness,
+    )
+}
+
+#[inline(always)]
+fn product_of_unrelated_probs(selections: &[DetailedSelection]) -> f64 {
+    selections
+        .iter()
+        .map(|selection| selection.single_prob)
+        .product()
+}
+
+#[inline(always)]
+fn resolve_selection(
+    offer: &Offer,
+    outcome: &Outcome,
+    outcome_index: usize,
+) -> DetailedSelection {
+    let single_prob = offer.market.probs[outcome_index];
+    let single_price = offer.market.prices[outcome_index];
+    let single_overround = 1.0 / single_prob / single_price;
+    DetailedSelection {
+        offer_type: offer.offer_type.clone(),
+        outcome: outcome.clone(),
+        single_overround,
+        single_prob,
+    }
+}
+
+#[inline(always)]
+fn strip_details(selections: Vec<DetailedSelection>) -> Vec<(OfferType, Outcome)> {
+    selections
+        .into_iter()
+        .map(|selection| (selection.offer_type, selection.outcome))
+        .collect()
+}
+
+#[inline(always)]
+fn sort_selections_by_increasing_prob(selections: &mut [DetailedSelection]) {
+    selections.sort_by(|s1, s2| s1.single_prob.total_cmp(&s2.single_prob))
+}
+
+struct ResolvedMulti {
+    reqs: Expansions,
+    player_probs: FxHashMap<Player, PlayerProbs>,
+    sorted_selections: Vec<DetailedSelection>,
+}
+
+/// Multis that are priced from the same exploration, by their index in the batch.
+struct ExplorationGroup {
+    config: interval::Config,
+    members: Vec<(usize, Vec<DetailedSelection>)>,
+}
+
+struct ScanPrefixResult {
+    /// The legs that are priced, including any redundant legs under [RedundancyHandling::Charge].
+    keep: Vec<DetailedSelection>,
+    /// The legs that do not lower the probability of the legs before them.
+    redundant: Vec<DetailedSelection>,
+    lowest_prob: f64,
+}
+
+/// Explains the price of a multi from its scanned legs and the exploration they were isolated from.
+fn explain(
+    policy: &MultiPricingPolicy,
+    scan_result: &ScanPrefixResult,
+    quotation: &DerivedPrice,
+    exploration: &Exploration,
+    events: &FxHashMap<EventStream, EventModel>,
+) -> MultiExplanation {
+    let mut selections = scan_result
+        .keep
+        .iter()
+        .map(|selection| (selection, true))
+        .chain(
+            scan_result
+                .redundant
+                .iter()
+                .filter(|_| policy.redundancies == RedundancyHandling::Drop)
+                .map(|selection| (selection, false)),
+        )
+        .collect::<Vec<_>>();
+    selections.sort_by(|(s1, _), (s2, _)| s1.single_prob.total_cmp(&s2.single_prob));
+
+    let legs = selections
+        .iter()
+        .map(|(selection, charged)| {
+            let leg = [(selection.offer_type.clone(), selection.outcome.clone())];
+            LegExplanation {
+                offer_type: selection.offer_type.clone(),
+                outcome: selection.outcome.clone(),
+                single_prob: selection.single_prob,
+                model_prob: isolate_prefix(&leg, exploration, events),
+                single_overround: selection.single_overround,
+                charged: *charged,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let mut pairs = Vec::with_capacity(legs.len() * legs.len().saturating_sub(1) / 2);
+    for (first_index, first) in legs.iter().enumerate() {
+        for (second_index, second) in legs.iter().enumerate().skip(first_index + 1) {
+            let pair = [
+                (first.offer_type.clone(), first.outcome.clone()),
+                (second.offer_type.clone(), second.outcome.clone()),
+            ];
+            let joint_prob = isolate_prefix(&pair, exploration, events);
+            pairs.push(PairExplanation {
+                legs: (first_index, second_index),
+                joint_prob,
+                uplift: joint_prob / (first.model_prob * second.model_prob),
+            });
+        }
+    }
+
+    MultiExplanation {
+        independent_prob: product_of_unrelated_probs(&scan_result.keep),
+        joint_prob: scan_result.lowest_prob,
+        legs,
+        pairs,
+        composed_overround: policy.overround.compose(&single_overrounds(&scan_result.keep)),
+        overround: quotation.overround(),
+        pruned: exploration.pruned,
+    }
+}
+
+#[inline(always)]
+fn scan_prefix(
+    sorted_selections: &[DetailedSelection],
+    exploration: &Exploration,
+    events: &FxHashMap<EventStream, EventModel>,
+    redundancies: &RedundancyHandling,
+) -> ScanPrefixResult {
+    let mut keep = Vec::with_capacity(sorted_selections.len());
+    let mut redundant = vec![];
+    let mut lowest_prob = f64::MAX;
+
+    for end_index in 1..=sorted_selections.len() {
+        let prefix = sorted_selections[0..end_index]
+            .iter()
+            .map(|selection| (selection.offer_type.clone(), selection.outcome.clone()))
+            .collect::<Vec<_>>();
+        let prob = isolate_prefix(&prefix, exploration, events);
+        // if LOG { trace!("fringe prefix: {prefix:?}, prob: {prob:.3}"); }
+        let tail = &sorted_selections[end_index - 1];
+        if prob < lowest_prob {
+            lowest_prob = prob;
+            keep.push(tail.clone());
+        } else {
+            if *redundancies == RedundancyHandling::Charge {
+                keep.push(tail.clone());
+            }
+            redundant.push(tail.clone());
+        }
+    }
+
+    ScanPrefixResult {
+        keep,
+        redundant,
+        lowest_prob,
+    }
+}
+
+/// Isolates the probability of the selections, weighing each prospect by the probability
+/// of the event selections conditional on its total goals.
+fn isolate_prefix(
+    prefix: &[(OfferType, Outcome)],
+    exploration: &Exploration,
+    events: &FxHashMap<EventStream, EventModel>,
+) -> f64 {
+    let event_selections = prefix
+        .iter()
+        .filter_map(|(offer_type, outcome)| {
+            offer_type
+                .event_stream()
+                .map(|stream| (stream, (offer_type, outcome)))
+        })
+        .collect::<Vec<_>>();
+    if event_selections.is_empty() {
+        return query::isolate_set(prefix, &exploration.prospects, &exploration.player_lookup);
+    }
+
+    let mut goal_probs = vec![];
+    for (prospect, prob) in &exploration.prospects {
+        let goals = prospect.ft_score.total() as usize;
+        if goals >= goal_probs.len() {
+            goal_probs.resize(goals + 1, 0.0);
+        }
+        goal_probs[goals] += prob;
+    }
+    goal_probs.normalise(1.0);
+
+    // the streams are independent of one another given the goals
+    let mut weights = vec![1.0; goal_probs.len()];
+    for (stream, event_model) in events {
+        let stream_selections = event_selections
+            .iter()
+            .filter(|(selection_stream, _)| *selection_stream == stream)
+            .map(|(_, selection)| *selection)
+            .collect::<Vec<_>>();
+        if !stream_selections.is_empty() {
+            let conditional_probs =
+                event_model.isolate_given_goals(&stream_selections, &goal_probs);
+            for (weight, conditional_prob) in weights.iter_mut().zip(conditional_probs) {
+                *weight *= conditional_prob;
+            }
+        }
+    }
+    isolate_set_weighted(
+        prefix,
+        &exploration.prospects,
+        &exploration.player_lookup,
+        |prospect| weights[prospect.ft_score.total() as usize],
+    )
+}
+
 fn is_fringe_supported(offer_type: &OfferType) -> bool {
     matches!(
         offer_type,
-        OfferType::AnytimeAssist | OfferType::AnytimeGoalscorer
+        OfferType::AnytimeAssist
+            | OfferType::AnytimeGoalscorer
+            | OfferType::PlayerToScore(_)
+            | OfferType::PlayerToScoreInHalf(_)
+            | OfferType::PlayerToScoreInBothHalves
+            | OfferType::PlayerToScoreMultipleInHalf(_, _)
     )
 }
 
@@ -958,7 +1660,10 @@ impl TryFrom<Config> for Model {
             goal_probs: None,
             assist_probs: None,
             player_probs: Default::default(),
+            knockout: None,
+            events: Default::default(),
             offers: Default::default(),
+            exploration_cache: Default::default(),
         })
     }
 }
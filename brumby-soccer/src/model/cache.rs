@@ -1,7 +1,15 @@
-use std::collections::hash_map::Entry;
+//! Caching of interval explorations. An [ExplorationCache] is long-lived, shared by successive
+//! derivations on the same model, and bounded by the number of explorations it holds, evicting the
+//! least recently used. A [CachingContext] fronts the cache for the duration of a single
+//! derivation, tallying the statistics of that derivation alone.
+
+use std::collections::BTreeMap;
 use std::ops::{Add, AddAssign, Range};
+use std::sync::{Arc, Mutex};
+
 use bincode::Encode;
 use rustc_hash::FxHashMap;
+
 use crate::interval;
 use crate::interval::Exploration;
 
@@ -9,6 +17,27 @@ use crate::interval::Exploration;
 pub struct CacheStats {
     hits: usize,
     misses: usize,
+    evictions: usize,
+    invalidations: usize,
+}
+impl CacheStats {
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    pub fn misses(&self) -> usize {
+        self.misses
+    }
+
+    /// Explorations evicted to keep the cache within its capacity.
+    pub fn evictions(&self) -> usize {
+        self.evictions
+    }
+
+    /// The number of times the cache was cleared following a change to the model's probabilities.
+    pub fn invalidations(&self) -> usize {
+        self.invalidations
+    }
 }
 
 impl Add<bool> for CacheStats {
@@ -36,6 +65,8 @@ impl Add for CacheStats {
         Self {
             hits: self.hits + rhs.hits,
             misses: self.misses + rhs.misses,
+            evictions: self.evictions + rhs.evictions,
+            invalidations: self.invalidations + rhs.invalidations,
         }
     }
 }
@@ -54,6 +85,8 @@ impl AddAssign for CacheStats {
     fn add_assign(&mut self, rhs: Self) {
         self.hits += rhs.hits;
         self.misses += rhs.misses;
+        self.evictions += rhs.evictions;
+        self.invalidations += rhs.invalidations;
     }
 }
 
@@ -65,59 +98,268 @@ pub struct CacheableIntervalArgs {
     pub include_intervals: Range<u8>,
 }
 
+#[derive(Debug)]
+struct CacheEntry {
+    exploration: Arc<Exploration>,
+    last_used: u64,
+}
+
 #[derive(Debug, Default)]
+struct CacheState {
+    entries: FxHashMap<Bytes, CacheEntry>,
+    /// Keys of the entries, ordered from the least to the most recently used.
+    recency: BTreeMap<u64, Bytes>,
+    clock: u64,
+    /// The encoded probabilities of the model that the cached explorations were derived from.
+    fingerprint: Option<Bytes>,
+    stats: CacheStats,
+}
+impl CacheState {
+    fn touch(&mut self, key: &Bytes) -> Option<Arc<Exploration>> {
+        let entry = self.entries.get_mut(key)?;
+        self.recency.remove(&entry.last_used);
+        self.clock += 1;
+        entry.last_used = self.clock;
+        self.recency.insert(self.clock, key.clone());
+        Some(entry.exploration.clone())
+    }
+
+    fn insert(&mut self, key: Bytes, exploration: Arc<Exploration>, capacity: usize) -> usize {
+        if self.entries.contains_key(&key) {
+            return 0;
+        }
+        let mut evictions = 0;
+        while self.entries.len() >= capacity {
+            let Some((_, evicted_key)) = self.recency.pop_first() else {
+                break;
+            };
+            self.entries.remove(&evicted_key);
+            evictions += 1;
+        }
+        self.clock += 1;
+        self.recency.insert(self.clock, key.clone());
+        self.entries.insert(
+            key,
+            CacheEntry {
+                exploration,
+                last_used: self.clock,
+            },
+        );
+        evictions
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.recency.clear();
+    }
+}
+
+/// A size-bounded cache of explorations, keyed by the arguments to [interval::explore].
+///
+/// The cache is safe to share between threads; explorations are returned as [Arc]s, so that an
+/// eviction never invalidates an exploration that is still in use.
+#[derive(Debug)]
+pub struct ExplorationCache {
+    capacity: usize,
+    state: Mutex<CacheState>,
+}
+impl ExplorationCache {
+    pub const DEFAULT_CAPACITY: usize = 128;
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        assert!(capacity > 0, "capacity must be positive");
+        Self {
+            capacity,
+            state: Mutex::default(),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.state.lock().unwrap().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Cumulative statistics over the lifetime of the cache.
+    pub fn stats(&self) -> CacheStats {
+        self.state.lock().unwrap().stats.clone()
+    }
+
+    pub fn clear(&self) {
+        self.state.lock().unwrap().clear();
+    }
+
+    /// Clears the cache if the given fingerprint differs from the one that the cached explorations
+    /// were derived under, returning `true` if the cache was cleared.
+    pub fn invalidate_unless(&self, fingerprint: &[u8]) -> bool {
+        let mut state = self.state.lock().unwrap();
+        if state.fingerprint.as_deref() == Some(fingerprint) {
+            return false;
+        }
+        let invalidated = state.fingerprint.is_some() && !state.entries.is_empty();
+        if invalidated {
+            state.clear();
+            state.stats.invalidations += 1;
+        }
+        state.fingerprint = Some(fingerprint.to_vec());
+        invalidated
+    }
+
+    /// Obtains the exploration for the given arguments, exploring and caching it if absent. The
+    /// returned statistics pertain to this call only.
+    pub fn explore(&self, args: CacheableIntervalArgs) -> (Arc<Exploration>, CacheStats) {
+        let key = bincode::encode_to_vec(&args, bincode::config::standard()).unwrap();
+        {
+            let mut state = self.state.lock().unwrap();
+            if let Some(exploration) = state.touch(&key) {
+                state.stats += true;
+                return (exploration, CacheStats::default() + true);
+            }
+        }
+
+        // explore without holding the lock, so that other threads may use the cache meanwhile
+        let exploration = Arc::new(interval::explore(&args.config, args.include_intervals));
+        let mut state = self.state.lock().unwrap();
+        let evictions = state.insert(key, exploration.clone(), self.capacity);
+        let stats = CacheStats {
+            evictions,
+            ..CacheStats::default() + false
+        };
+        state.stats += stats.clone();
+        (exploration, stats)
+    }
+}
+
+impl Default for ExplorationCache {
+    fn default() -> Self {
+        Self::with_capacity(Self::DEFAULT_CAPACITY)
+    }
+}
+
+/// Fronts an [ExplorationCache] for the duration of one derivation.
+#[derive(Debug)]
 pub struct CachingContext {
-    pub cache: FxHashMap<Bytes, Exploration>,
-    pub stats: CacheStats
+    cache: Arc<ExplorationCache>,
+    pub stats: CacheStats,
 }
 impl CachingContext {
+    /// Fronts the given cache, first invalidating it if the fingerprint of the model's
+    /// probabilities has changed since the cache was last used.
+    pub fn new(cache: Arc<ExplorationCache>, fingerprint: &[u8]) -> Self {
+        let mut stats = CacheStats::default();
+        if cache.invalidate_unless(fingerprint) {
+            stats.invalidations += 1;
+        }
+        Self { cache, stats }
+    }
+
     #[inline(always)]
-    pub fn explore(
-        &mut self,
-        args: CacheableIntervalArgs,
-    ) -> &Exploration {
-        let encoded = bincode::encode_to_vec(&args, bincode::config::standard()).unwrap();
-        let (exploration, cache_hit) = match self.cache.entry(encoded) {
-            Entry::Occupied(entry) => (entry.into_mut(), true),
-            Entry::Vacant(entry) => {
-                let exploration = interval::explore(&args.config, args.include_intervals);
-                (entry.insert(exploration), false)
-            }
-        };
-        self.stats += cache_hit;
+    pub fn explore(&mut self, args: CacheableIntervalArgs) -> Arc<Exploration> {
+        let (exploration, stats) = self.cache.explore(args);
+        self.stats += stats;
         exploration
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use brumby::sv;
+
+    use crate::interval::{BivariateProbs, Expansions, TeamProbs};
+
     use super::*;
 
+    fn create_test_args(home_prob: f64) -> CacheableIntervalArgs {
+        CacheableIntervalArgs {
+            config: interval::Config {
+                intervals: 2,
+                team_probs: TeamProbs {
+                    h1_goals: BivariateProbs { home: home_prob, away: 0.1, common: 0.0 },
+                    h2_goals: BivariateProbs { home: home_prob, away: 0.1, common: 0.0 },
+                    assists: Default::default(),
+                },
+                player_probs: sv![],
+                prune_thresholds: Default::default(),
+                expansions: Expansions {
+                    ft_score: true,
+                    ..Expansions::empty()
+                },
+            },
+            include_intervals: 0..2,
+        }
+    }
+
     #[test]
     fn add_bool() {
-        assert_eq!(CacheStats { hits: 1, misses: 0}, CacheStats::default() + true);
-        assert_eq!(CacheStats { hits: 0, misses: 1}, CacheStats::default() + false);
+        assert_eq!(CacheStats { hits: 1, misses: 0, ..CacheStats::default() }, CacheStats::default() + true);
+        assert_eq!(CacheStats { hits: 0, misses: 1, ..CacheStats::default() }, CacheStats::default() + false);
     }
 
     #[test]
     fn add_assign_bool() {
-        let mut cs = CacheStats { hits: 1, misses: 0};
+        let mut cs = CacheStats { hits: 1, misses: 0, ..CacheStats::default() };
         cs += false;
-        assert_eq!(CacheStats { hits: 1, misses: 1}, cs);
+        assert_eq!(CacheStats { hits: 1, misses: 1, ..CacheStats::default() }, cs);
         cs += true;
-        assert_eq!(CacheStats { hits: 2, misses: 1}, cs);
+        assert_eq!(CacheStats { hits: 2, misses: 1, ..CacheStats::default() }, cs);
     }
 
     #[test]
     fn add_self() {
-        let cs = CacheStats { hits: 4, misses: 5};
-        assert_eq!(CacheStats { hits: 7, misses: 6}, cs + CacheStats { hits: 3, misses: 1});
+        let cs = CacheStats { hits: 4, misses: 5, ..CacheStats::default() };
+        assert_eq!(CacheStats { hits: 7, misses: 6, ..CacheStats::default() }, cs + CacheStats { hits: 3, misses: 1, ..CacheStats::default() });
     }
 
     #[test]
     fn add_assign_self() {
-        let mut cs = CacheStats { hits: 4, misses: 5};
-        cs += CacheStats { hits: 3, misses: 1};
-        assert_eq!(CacheStats { hits: 7, misses: 6}, cs);
+        let mut cs = CacheStats { hits: 4, misses: 5, ..CacheStats::default() };
+        cs += CacheStats { hits: 3, misses: 1, ..CacheStats::default() };
+        assert_eq!(CacheStats { hits: 7, misses: 6, ..CacheStats::default() }, cs);
+    }
+
+    #[test]
+    fn hit_after_miss() {
+        let cache = ExplorationCache::default();
+        let (first, stats) = cache.explore(create_test_args(0.2));
+        assert_eq!(CacheStats { misses: 1, ..CacheStats::default() }, stats);
+        let (second, stats) = cache.explore(create_test_args(0.2));
+        assert_eq!(CacheStats { hits: 1, ..CacheStats::default() }, stats);
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(CacheStats { hits: 1, misses: 1, ..CacheStats::default() }, cache.stats());
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let cache = ExplorationCache::with_capacity(2);
+        cache.explore(create_test_args(0.1));
+        cache.explore(create_test_args(0.2));
+        cache.explore(create_test_args(0.1)); // 0.2 is now the least recently used
+        let (_, stats) = cache.explore(create_test_args(0.3));
+        assert_eq!(CacheStats { misses: 1, evictions: 1, ..CacheStats::default() }, stats);
+        assert_eq!(2, cache.len());
+
+        let (_, stats) = cache.explore(create_test_args(0.1));
+        assert_eq!(1, stats.hits());
+        let (_, stats) = cache.explore(create_test_args(0.2));
+        assert_eq!(1, stats.misses());
+        assert_eq!(2, cache.stats().evictions());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn invalidates_on_changed_fingerprint() {
+        let cache = ExplorationCache::default();
+        assert!(!cache.invalidate_unless(&[1]));
+        cache.explore(create_test_args(0.1));
+        assert!(!cache.invalidate_unless(&[1]));
+        assert_eq!(1, cache.len());
+        assert!(cache.invalidate_unless(&[2]));
+        assert!(cache.is_empty());
+        assert_eq!(1, cache.stats().invalidations());
+    }
+}
@@ -0,0 +1,170 @@
+use std::ops::RangeInclusive;
+
+use anyhow::anyhow;
+use rustc_hash::FxHashMap;
+use tracing::debug;
+
+use brumby::capture::Capture;
+
+use crate::domain::validation::{InvalidOffer, OfferCapture, UnvalidatedOffer};
+use crate::domain::{EventStream, Offer, OfferCategory, OfferType, Outcome, Player, Side};
+use crate::events::EventModel;
+use crate::fit;
+use crate::model::{FitError, MissingOffer, Model, ValidationError};
+
+pub struct Config {
+    /// The maximum events of each side, beyond which the counts are truncated.
+    pub max_events: usize,
+    /// The dependence of the events on the goals, as per [EventModel::goal_correlation].
+    pub goal_correlation: f64,
+}
+impl Config {
+    fn validate(&self) -> Result<(), ValidationError> {
+        const GOAL_CORRELATION_RANGE: RangeInclusive<f64> = -1.0..=1.0;
+        if !GOAL_CORRELATION_RANGE.contains(&self.goal_correlation) {
+            return Err(anyhow!(
+                "goal correlation ({}) outside of allowable range ({GOAL_CORRELATION_RANGE:?})",
+                self.goal_correlation
+            )
+            .into());
+        }
+        if self.max_events == 0 || self.max_events > u8::MAX as usize / 2 {
+            return Err(anyhow!("max events ({}) outside of allowable range", self.max_events).into());
+        }
+        Ok(())
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            max_events: 24,
+            goal_correlation: 0.0,
+        }
+    }
+}
+
+/// Fits the event model of one stream to its sourced totals and handicaps, and the shares of the
+/// players to any sourced player offers. At least one total must be present, from which the
+/// initial estimate of the total events is taken.
+pub struct EventFitter {
+    config: Config,
+}
+impl EventFitter {
+    pub fn fit(
+        &self,
+        model: &mut Model,
+        stream: &EventStream,
+        offers: &FxHashMap<OfferType, Offer>,
+    ) -> Result<(), FitError> {
+        let stream_offers = offers
+            .values()
+            .filter(|offer| offer.offer_type.event_stream() == Some(stream))
+            .map(|offer| OfferCapture::try_from(UnvalidatedOffer::from(Capture::Borrowed(offer))))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let (_, init_total_events) = stream_offers
+            .iter()
+            .filter_map(|offer| match offer.offer_type {
+                OfferType::TotalEvents(_, ref over) => {
+                    let over_prob = offer.get_probability(&Outcome::Over(over.0))?;
+                    let line = (over.0 as f64 + 0.5) / stream.points() as f64;
+                    Some(((over_prob - 0.5).abs(), line))
+                }
+                _ => None,
+            })
+            .min_by(|(a, _), (b, _)| a.total_cmp(b))
+            .ok_or(MissingOffer::Category(OfferCategory::TotalEvents))?;
+
+        let (player_offers, team_offers): (Vec<_>, Vec<_>) = stream_offers
+            .iter()
+            .map(|offer| &**offer)
+            .partition(|offer| matches!(offer.offer_type, OfferType::PlayerEvents(_, _)));
+        let (search_outcome, counts) =
+            fit::fit_event_counts(&team_offers, init_total_events, self.config.max_events);
+        debug!("fitted {stream:?}: {:?}", search_outcome.optimal_values);
+        let mut event_model = EventModel {
+            counts,
+            player_shares: Default::default(),
+            goal_correlation: self.config.goal_correlation,
+        };
+        fit_player_shares(&mut event_model, stream, &player_offers)?;
+        model.events.insert(stream.clone(), event_model);
+        Ok(())
+    }
+}
+
+/// Fits the share of each player in the player offers to the probabilities of their outcomes,
+/// given the fitted counts. The shares of the players of each side must not sum to more than 1.
+fn fit_player_shares(
+    event_model: &mut EventModel,
+    stream: &EventStream,
+    offers: &[&Offer],
+) -> Result<(), FitError> {
+    const BISECTIONS: usize = 30;
+
+    let mut player_samples: FxHashMap<&Player, Vec<(&OfferType, &Outcome, f64)>> =
+        FxHashMap::default();
+    for offer in offers {
+        for (index, outcome) in offer.outcomes.items().iter().enumerate() {
+            if let Outcome::Player(player) = outcome {
+                player_samples.entry(player).or_default().push((
+                    &offer.offer_type,
+                    outcome,
+                    offer.market.probs[index],
+                ));
+            }
+        }
+    }
+
+    // the probability of a player exceeding their line increases with their share, so the share
+    // that balances the residuals is bracketed by bisection
+    for (player, samples) in player_samples {
+        let (mut low, mut high) = (0.0, 1.0);
+        for _ in 0..BISECTIONS {
+            let share = (low + high) / 2.0;
+            event_model.player_shares.insert(player.clone(), share);
+            let residual = samples
+                .iter()
+                .map(|(offer_type, outcome, sample_prob)| {
+                    event_model.isolate(offer_type, outcome) - sample_prob
+                })
+                .sum::<f64>();
+            if residual < 0.0 {
+                low = share;
+            } else {
+                high = share;
+            }
+        }
+        let share = (low + high) / 2.0;
+        debug!("fitted {stream:?} share of {player:?}: {share:.3}");
+        event_model.player_shares.insert(player.clone(), share);
+    }
+
+    for side in [Side::Home, Side::Away] {
+        let side_share = event_model
+            .player_shares
+            .iter()
+            .filter(|(player, _)| {
+                matches!(player, Player::Named(player_side, _) if *player_side == side)
+            })
+            .map(|(_, share)| share)
+            .sum::<f64>();
+        if side_share > 1.0 {
+            return Err(InvalidOffer::InvalidMarket(anyhow!(
+                "{stream:?} shares of {side:?} players sum to {side_share:.3}, exceeding 1"
+            ))
+            .into());
+        }
+    }
+    Ok(())
+}
+
+impl TryFrom<Config> for EventFitter {
+    type Error = ValidationError;
+
+    fn try_from(config: Config) -> Result<Self, Self::Error> {
+        config.validate()?;
+        Ok(Self { config })
+    }
+}
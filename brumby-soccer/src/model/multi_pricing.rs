@@ -0,0 +1,243 @@
+//! The policy for pricing a multi from the probability of its legs occurring together and the
+//! overrounds of the singles that it comprises.
+
+use anyhow::anyhow;
+
+use brumby::derived_price::DerivedPrice;
+
+use crate::model::ValidationError;
+
+/// How the overrounds of the single legs are combined into the overround of the multi.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OverroundComposition {
+    /// The product of the single overrounds.
+    Product,
+    /// The product of the single overrounds, capped at the given overround.
+    Capped(f64),
+    /// A fixed overround by the number of legs, starting with a single leg. Multis with more legs
+    /// than there are elements take the last element.
+    ByLegCount(Vec<f64>),
+}
+impl OverroundComposition {
+    pub fn compose(&self, single_overrounds: &[f64]) -> f64 {
+        match self {
+            OverroundComposition::Product => single_overrounds.iter().product(),
+            OverroundComposition::Capped(cap) => {
+                f64::min(single_overrounds.iter().product(), *cap)
+            }
+            OverroundComposition::ByLegCount(overrounds) => {
+                let index = usize::min(single_overrounds.len(), overrounds.len()).saturating_sub(1);
+                overrounds[index]
+            }
+        }
+    }
+}
+
+/// Adjusts the composed overround according to the relatedness of the legs, being the ratio of the
+/// product of the single probabilities to the joint probability. A relatedness below 1 indicates
+/// positively correlated legs; above 1, negatively correlated.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CorrelationLoading {
+    /// The composed overround is applied as is.
+    None,
+    /// The composed overround is raised to the power `coefficient * log10(scale * relatedness)`.
+    /// E.g., with a coefficient of 0.5 and a scale of 100, unrelated legs take the composed
+    /// overround in full, while strongly correlated legs take progressively less of it. The loaded
+    /// overround is never less than 1, however strongly correlated the legs.
+    LogRelatedness { coefficient: f64, scale: f64 },
+}
+impl CorrelationLoading {
+    pub fn load(&self, overround: f64, relatedness: f64) -> f64 {
+        match self {
+            CorrelationLoading::None => overround,
+            CorrelationLoading::LogRelatedness { coefficient, scale } => {
+                if relatedness.is_finite() && relatedness > 0.0 {
+                    let exponent = coefficient * f64::log10(scale * relatedness);
+                    f64::max(1.0, overround.powf(exponent))
+                } else {
+                    overround
+                }
+            }
+        }
+    }
+}
+
+/// What becomes of legs that do not lower the probability of the legs before them, such as an
+/// anytime goalscorer leg alongside a brace by the same player.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RedundancyHandling {
+    /// Redundant legs are reported and dropped from the multi: their overrounds are excluded, they
+    /// do not count toward the relatedness, and they are not carried into the fringes.
+    Drop,
+    /// Redundant legs are reported, but are otherwise kept in the multi like any other leg.
+    Charge,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultiPricingPolicy {
+    pub overround: OverroundComposition,
+    pub correlation_loading: CorrelationLoading,
+    pub redundancies: RedundancyHandling,
+    /// Prospects less likely than this are pruned when exploring the legs.
+    pub prune_min_prob: f64,
+    /// Attainable multis less likely than this are priced as if they had this probability.
+    pub min_probability: f64,
+}
+impl MultiPricingPolicy {
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        match &self.overround {
+            OverroundComposition::Product => {}
+            OverroundComposition::Capped(cap) => {
+                if *cap < 1.0 {
+                    return Err(anyhow!("overround cap cannot be less than 1").into());
+                }
+            }
+            OverroundComposition::ByLegCount(overrounds) => {
+                if overrounds.is_empty() {
+                    return Err(anyhow!("overrounds by leg count cannot be empty").into());
+                }
+                if overrounds.iter().any(|&overround| overround < 1.0) {
+                    return Err(anyhow!("overrounds by leg count cannot be less than 1").into());
+                }
+            }
+        }
+        if let CorrelationLoading::LogRelatedness { scale, .. } = &self.correlation_loading {
+            if *scale <= 0.0 {
+                return Err(anyhow!("correlation loading scale must be positive").into());
+            }
+        }
+        if !(0.0..1.0).contains(&self.prune_min_prob) {
+            return Err(anyhow!("prune min prob must lie in [0, 1)").into());
+        }
+        if !(0.0..1.0).contains(&self.min_probability) {
+            return Err(anyhow!("min probability must lie in [0, 1)").into());
+        }
+        Ok(())
+    }
+
+    /// Prices a multi given its joint probability, the single overrounds of the legs that were
+    /// kept (as per [RedundancyHandling]), and the relatedness of the kept legs.
+    pub fn quote(
+        &self,
+        probability: f64,
+        kept_overrounds: &[f64],
+        relatedness: f64,
+    ) -> DerivedPrice {
+        let overround = self.overround.compose(kept_overrounds);
+        let overround = self.correlation_loading.load(overround, relatedness);
+        let probability = if probability > 0.0 {
+            f64::max(probability, self.min_probability)
+        } else {
+            probability
+        };
+        DerivedPrice {
+            probability,
+            price: 1.0 / probability / overround,
+        }
+    }
+}
+
+impl Default for MultiPricingPolicy {
+    fn default() -> Self {
+        Self {
+            overround: OverroundComposition::Product,
+            correlation_loading: CorrelationLoading::None,
+            redundancies: RedundancyHandling::Drop,
+            prune_min_prob: 1e-4,
+            min_probability: 0.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_float_eq::*;
+
+    use super::*;
+
+    #[test]
+    fn compose_overrounds() {
+        let overrounds = [1.1, 1.2, 1.05];
+        assert_float_absolute_eq!(1.386, OverroundComposition::Product.compose(&overrounds));
+        assert_float_absolute_eq!(1.3, OverroundComposition::Capped(1.3).compose(&overrounds));
+        let by_leg_count = OverroundComposition::ByLegCount(vec![1.05, 1.15]);
+        assert_float_absolute_eq!(1.05, by_leg_count.compose(&overrounds[..1]));
+        assert_float_absolute_eq!(1.15, by_leg_count.compose(&overrounds[..2]));
+        assert_float_absolute_eq!(1.15, by_leg_count.compose(&overrounds));
+    }
+
+    #[test]
+    fn load_by_relatedness() {
+        let loading = CorrelationLoading::LogRelatedness {
+            coefficient: 0.5,
+            scale: 100.0,
+        };
+        assert_float_absolute_eq!(1.21, loading.load(1.21, 1.0));
+        assert_float_absolute_eq!(1.1, loading.load(1.21, 0.1));
+        assert_float_absolute_eq!(1.21, CorrelationLoading::None.load(1.21, 0.1));
+
+        // the loading bottoms out at 1 where the exponent would reach 0 and below
+        assert_float_absolute_eq!(1.0, loading.load(1.21, 0.01));
+        assert_float_absolute_eq!(1.0, loading.load(1.21, 0.001));
+        assert!(loading.load(1.21, 0.011) > 1.0);
+    }
+
+    #[test]
+    fn quote_default() {
+        let quotation = MultiPricingPolicy::default().quote(0.1, &[1.1, 1.2], 1.0);
+        assert_float_absolute_eq!(0.1, quotation.probability);
+        assert_float_absolute_eq!(1.32, quotation.overround());
+    }
+
+    #[test]
+    fn quote_with_correlation_loading() {
+        let policy = MultiPricingPolicy {
+            correlation_loading: CorrelationLoading::LogRelatedness {
+                coefficient: 0.5,
+                scale: 100.0,
+            },
+            ..MultiPricingPolicy::default()
+        };
+        let quotation = policy.quote(0.1, &[1.1, 1.1], 0.1);
+        assert_float_absolute_eq!(1.1, quotation.overround());
+        let quotation = policy.quote(0.1, &[1.1, 1.1], 0.0001);
+        assert_float_absolute_eq!(1.0, quotation.overround());
+    }
+
+    #[test]
+    fn quote_with_probability_floor() {
+        let policy = MultiPricingPolicy {
+            min_probability: 0.001,
+            ..MultiPricingPolicy::default()
+        };
+        let quotation = policy.quote(0.0001, &[1.1], 1.0);
+        assert_float_absolute_eq!(0.001, quotation.probability);
+        assert_float_absolute_eq!(1000.0 / 1.1, quotation.price);
+
+        // the unattainable remain so
+        let quotation = policy.quote(0.0, &[1.1], f64::NAN);
+        assert_eq!(0.0, quotation.probability);
+        assert!(quotation.price.is_infinite());
+    }
+
+    #[test]
+    fn validate() {
+        MultiPricingPolicy::default().validate().unwrap();
+        let policy = MultiPricingPolicy {
+            overround: OverroundComposition::ByLegCount(vec![]),
+            ..MultiPricingPolicy::default()
+        };
+        assert_eq!(
+            "overrounds by leg count cannot be empty",
+            policy.validate().unwrap_err().to_string()
+        );
+        let policy = MultiPricingPolicy {
+            overround: OverroundComposition::Capped(0.9),
+            ..MultiPricingPolicy::default()
+        };
+        assert_eq!(
+            "overround cap cannot be less than 1",
+            policy.validate().unwrap_err().to_string()
+        );
+    }
+}
@@ -64,6 +64,55 @@ impl PlayerGoalFitter {
             }
         }
 
+        // the remaining player offers are only used to fit players that don't appear in either of
+        // the above
+        let mut extra_offer_types = offers
+            .keys()
+            .filter(|offer_type| {
+                matches!(
+                    offer_type,
+                    OfferType::LastGoalscorer
+                        | OfferType::PlayerToScore(_)
+                        | OfferType::PlayerToScoreInHalf(_)
+                        | OfferType::PlayerToScoreInBothHalves
+                        | OfferType::PlayerToScoreMultipleInHalf(_, _)
+                )
+            })
+            .collect::<Vec<_>>();
+        extra_offer_types.sort();
+        for offer_type in extra_offer_types {
+            let offer = OfferCapture::try_from(get_offer(offers, offer_type)?)?;
+            let offer = offer.subset(|outcome, _| match outcome.get_player() {
+                None => false,
+                Some(player) => !fitted_goalscorer_probs.contains_key(player),
+            });
+
+            if let Some(offer) = offer {
+                debug!("fitting {offer_type:?} for extras {:?}", offer.outcomes.items());
+                let extra_fitted_goalscorer_probs = match offer_type {
+                    OfferType::LastGoalscorer => fit::fit_last_goalscorer_all(
+                        &goal_probs.h1,
+                        &goal_probs.h2,
+                        &offer,
+                        nil_all_draw_prob,
+                        model.config.intervals,
+                        model.config.max_total_goals,
+                    ),
+                    _ => fit::fit_player_to_score_all(
+                        &goal_probs.h1,
+                        &goal_probs.h2,
+                        &offer,
+                        nil_all_draw_prob,
+                        model.config.intervals,
+                        model.config.max_total_goals,
+                    ),
+                };
+                for (player, player_goal_prob) in extra_fitted_goalscorer_probs {
+                    fitted_goalscorer_probs.insert(player, player_goal_prob);
+                }
+            }
+        }
+
         for (player, player_goal_prob) in fitted_goalscorer_probs {
             get_or_create_player(&mut model.player_probs, player).goal = Some(player_goal_prob);
         }
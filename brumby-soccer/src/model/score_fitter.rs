@@ -4,19 +4,35 @@ use anyhow::anyhow;
 use rustc_hash::FxHashMap;
 use tracing::debug;
 
+use brumby::capture::Capture;
 use brumby::probs::Fraction;
 
 use crate::domain::{DrawHandicap, Offer, OfferCategory, OfferType, Period};
-use crate::domain::validation::OfferCapture;
+use crate::domain::validation::{InvalidOffer, OfferCapture, UnvalidatedOffer};
 use crate::fit;
+use crate::fit::OfferResidual;
 use crate::interval::BivariateProbs;
+use crate::scoregrid::ScoreDistribution;
 use crate::model::{
-    FitError, get_offer, GoalProbs, MissingOffer, Model, most_balanced_goals, ValidationError,
+    FitError, get_offer, GoalProbs, MissingOffer, Model, most_balanced_goals, team_goals, ValidationError,
 };
 
 pub struct Config {
-    h1_goal_ratio: f64,
-    half_total_goals_ratio: Fraction,
+    pub h1_goal_ratio: f64,
+    pub half_total_goals_ratio: Fraction,
+    /// Weights applied to the residuals of each offer category when fitting the goal model
+    /// jointly across all offers. Categories without a weight are excluded from the joint fit.
+    pub joint_fit_weights: FxHashMap<OfferCategory, f64>,
+    /// The distribution that the full-time scores are fitted to, and that the interval model is
+    /// subsequently calibrated against. If unset, the interval model's native bivariate binomial
+    /// is fitted to the offers directly.
+    ///
+    /// The calibration only rescales the home, away and common goal probabilities of the interval
+    /// model, so only the marginal goal rates of the distribution carry over. Its shape (the
+    /// low-score dependence of Dixon–Coles or the over-dispersion of the negative binomial) is not
+    /// reproduced by the interval model, which prices the offers. The reported residuals are
+    /// therefore those of the calibrated interval model, not of the fitted distribution.
+    pub score_distribution: Option<ScoreDistribution>,
 }
 impl Config {
     fn validate(&self) -> Result<(), ValidationError> {
@@ -34,6 +50,11 @@ impl Config {
         if self.half_total_goals_ratio.numerator == 0 {
             return Err(anyhow!("half total goals ratio cannot be zero").into());
         }
+        for (category, &weight) in &self.joint_fit_weights {
+            if weight < 0.0 {
+                return Err(anyhow!("joint fit weight for {category:?} cannot be negative").into());
+            }
+        }
         Ok(())
     }
 }
@@ -46,6 +67,19 @@ impl Default for Config {
                 numerator: 3,
                 denominator: 4,
             },
+            joint_fit_weights: FxHashMap::from_iter([
+                (OfferCategory::HeadToHead, 1.0),
+                (OfferCategory::TotalGoals, 1.0),
+                (OfferCategory::AsianHandicap, 1.0),
+                (OfferCategory::CorrectScore, 0.5),
+                (OfferCategory::TeamTotalGoals, 0.5),
+                (OfferCategory::ExactTeamGoals, 0.25),
+                (OfferCategory::ExactTotalGoals, 0.25),
+                (OfferCategory::OddEvenGoals, 0.25),
+                (OfferCategory::WinningMargin, 0.25),
+                (OfferCategory::DoubleChance, 0.25),
+            ]),
+            score_distribution: None,
         }
     }
 }
@@ -58,7 +92,7 @@ impl ScoreFitter {
         &self,
         model: &mut Model,
         offers: &FxHashMap<OfferType, Offer>,
-    ) -> Result<(), FitError> {
+    ) -> Result<Vec<OfferResidual>, FitError> {
         let (ft_goals, _) =
             most_balanced_goals(offers.values(), &Period::FullTime).ok_or(
                 FitError::MissingOffer(MissingOffer::Category(OfferCategory::TotalGoals))
@@ -67,9 +101,12 @@ impl ScoreFitter {
 
         let ft_h2h =
             OfferCapture::try_from(get_offer(offers, &OfferType::HeadToHead(Period::FullTime, DrawHandicap::Ahead(0)))?)?;
+        let ft_team_goals = validate_team_goals(offers, &Period::FullTime)?;
+        let ft_team_goals = ft_team_goals.iter().map(|offer| &**offer).collect::<Vec<_>>();
         let (ft_search_outcome, lambdas) = fit::fit_scoregrid_full(
             &ft_h2h,
             &ft_goals,
+            &ft_team_goals,
             model.config.intervals,
             model.config.max_total_goals,
         );
@@ -102,10 +139,15 @@ impl ScoreFitter {
         let max_total_goals_half =
             (model.config.max_total_goals as u64 * self.config.half_total_goals_ratio.numerator
                 / self.config.half_total_goals_ratio.denominator) as u16;
+        let h1_team_goals = validate_team_goals(offers, &Period::FirstHalf)?;
+        let h1_offers = [&*h1_h2h, &*h1_goals]
+            .into_iter()
+            .chain(h1_team_goals.iter().map(|offer| &**offer))
+            .collect::<Vec<_>>();
         let h1_search_outcome = fit::fit_scoregrid_half(
             h1_home_goals_estimate,
             h1_away_goals_estimate,
-            &[&h1_h2h, &h1_goals],
+            &h1_offers,
             model.config.intervals,
             max_total_goals_half,
         );
@@ -116,10 +158,15 @@ impl ScoreFitter {
         );
         let h2_home_goals_estimate = (lambdas[0] + lambdas[2]) * (1.0 - self.config.h1_goal_ratio);
         let h2_away_goals_estimate = (lambdas[1] + lambdas[2]) * (1.0 - self.config.h1_goal_ratio);
+        let h2_team_goals = validate_team_goals(offers, &Period::SecondHalf)?;
+        let h2_offers = [&*h2_h2h, &*h2_goals]
+            .into_iter()
+            .chain(h2_team_goals.iter().map(|offer| &**offer))
+            .collect::<Vec<_>>();
         let h2_search_outcome = fit::fit_scoregrid_half(
             h2_home_goals_estimate,
             h2_away_goals_estimate,
-            &[&h2_h2h, &h2_goals],
+            &h2_offers,
             model.config.intervals,
             max_total_goals_half,
         );
@@ -140,15 +187,131 @@ impl ScoreFitter {
         }
         adj_optimal_h1[2] = ft_search_outcome.optimal_values[2];
         adj_optimal_h2[2] = ft_search_outcome.optimal_values[2];
+        let (mut h1_probs, mut h2_probs) = (
+            BivariateProbs::from(&adj_optimal_h1),
+            BivariateProbs::from(&adj_optimal_h2),
+        );
+
+        // refine the staged fit against every offer that can be reproduced from a scoregrid
+        let joint_offers = self.validate_joint_offers(offers)?;
+        let joint_offers = joint_offers
+            .iter()
+            .map(|(offer, weight)| (&**offer, *weight))
+            .collect::<Vec<_>>();
+        match &self.config.score_distribution {
+            None if !joint_offers.is_empty() => {
+                let staged_residual = fit::scoregrid_residuals(
+                    &joint_offers,
+                    &h1_probs,
+                    &h2_probs,
+                    model.config.intervals,
+                    model.config.max_total_goals,
+                )
+                .iter()
+                .map(|residual| residual.residual)
+                .sum::<f64>();
+                let joint_search_outcome = fit::fit_scoregrid_joint(
+                    &joint_offers,
+                    &h1_probs,
+                    &h2_probs,
+                    model.config.intervals,
+                    model.config.max_total_goals,
+                );
+                debug!(
+                    "joint fit residual: {:.6}, staged fit residual: {staged_residual:.6}",
+                    joint_search_outcome.optimal_residual
+                );
+                if joint_search_outcome.optimal_residual < staged_residual {
+                    (h1_probs, h2_probs) = fit::joint_probs(&joint_search_outcome.optimal_values);
+                }
+            }
+            None => {}
+            Some(distribution) => {
+                // fit the distribution to the full-time offers, then calibrate against it
+                let ft_offers = joint_offers
+                    .iter()
+                    .filter(|(offer, _)| {
+                        fit::scoregrid_period(&offer.offer_type) == Some(&Period::FullTime)
+                    })
+                    .copied()
+                    .collect::<Vec<_>>();
+                if !ft_offers.is_empty() {
+                    let (distribution_outcome, target) = fit::fit_score_distribution(
+                        distribution,
+                        &ft_offers,
+                        lambdas[0] + lambdas[2],
+                        lambdas[1] + lambdas[2],
+                        model.config.intervals,
+                        model.config.max_total_goals,
+                    );
+                    let calibration_outcome = fit::calibrate_scoregrid(
+                        &target,
+                        &h1_probs,
+                        &h2_probs,
+                        model.config.intervals,
+                        model.config.max_total_goals,
+                    );
+                    debug!(
+                        "{distribution:?} fit residual: {:.6}, calibration error: {:.6}",
+                        distribution_outcome.optimal_residual, calibration_outcome.optimal_residual
+                    );
+                    (h1_probs, h2_probs) = fit::calibrated_probs(
+                        &h1_probs,
+                        &h2_probs,
+                        &calibration_outcome.optimal_values,
+                    );
+                }
+            }
+        }
+        let residuals = fit::scoregrid_residuals(
+            &joint_offers,
+            &h1_probs,
+            &h2_probs,
+            model.config.intervals,
+            model.config.max_total_goals,
+        );
         model.goal_probs = Some(GoalProbs {
-            h1: BivariateProbs::from(&adj_optimal_h1),
-            h2: BivariateProbs::from(&adj_optimal_h2),
+            h1: h1_probs,
+            h2: h2_probs,
         });
 
-        Ok(())
+        Ok(residuals)
+    }
+
+    fn validate_joint_offers<'a>(
+        &self,
+        offers: &'a FxHashMap<OfferType, Offer>,
+    ) -> Result<Vec<(OfferCapture<'a>, f64)>, InvalidOffer> {
+        let mut joint_offers = offers
+            .values()
+            .filter(|offer| fit::scoregrid_period(&offer.offer_type).is_some())
+            .filter_map(|offer| {
+                self.config
+                    .joint_fit_weights
+                    .get(&offer.offer_type.category())
+                    .filter(|&&weight| weight > 0.0)
+                    .map(|&weight| (offer, weight))
+            })
+            .map(|(offer, weight)| {
+                let offer = OfferCapture::try_from(UnvalidatedOffer::from(Capture::Borrowed(offer)))?;
+                Ok((offer, weight))
+            })
+            .collect::<Result<Vec<_>, InvalidOffer>>()?;
+        joint_offers.sort_by(|(a, _), (b, _)| a.offer_type.cmp(&b.offer_type));
+        Ok(joint_offers)
     }
 }
 
+fn validate_team_goals<'a>(
+    offers: &'a FxHashMap<OfferType, Offer>,
+    period: &Period,
+) -> Result<Vec<OfferCapture<'a>>, InvalidOffer> {
+    team_goals(offers.values(), period)
+        .into_iter()
+        .map(OfferCapture::try_from)
+        .collect()
+}
+
 impl TryFrom<Config> for ScoreFitter {
     type Error = ValidationError;
 
@@ -1,10 +1,22 @@
-use crate::domain::{DrawHandicap, Offer, OfferType, Outcome, Period, Side, WinHandicap};
-use crate::model::{Config, Model, Stub};
+use crate::domain::{DrawHandicap, EventStream, MatchResult, Offer, OfferType, Outcome, Over, Period, Player, Score, Side, TimeBand, VictoryMethod, WinHandicap};
+use crate::interval::{self, BivariateProbs, PlayerProbs};
+use crate::model::score_fitter::ScoreFitter;
+use crate::events::EventModel;
+use crate::knockout::Shootout;
+use crate::model::event_fitter::EventFitter;
+use crate::model::cache::ExplorationCache;
+use crate::model::multi_pricing::{MultiPricingPolicy, OverroundComposition, RedundancyHandling};
+use crate::model::player_goal_fitter::PlayerGoalFitter;
+use crate::model::{event_fitter, score_fitter, Config, ExtraTime, GoalProbs, Knockout, Model, Stub};
+use crate::domain::settlement::SettlementProbs;
 use crate::print;
+use crate::scoregrid::ScoreDistribution;
+use assert_float_eq::*;
 use brumby::hash_lookup::HashLookup;
 use brumby::market::{Market, Overround, OverroundMethod, PriceBounds};
 use brumby_testing::assert_slice_f64_relative;
 use rustc_hash::FxHashMap;
+use std::sync::Arc;
 use stanza::renderer::console::Console;
 use stanza::renderer::Renderer;
 
@@ -279,6 +291,641 @@ pub fn draw_no_bet() {
     );
 }
 
+#[test]
+pub fn half_time_full_time() {
+    let mut model = create_test_model();
+    model.goal_probs = Some(GoalProbs {
+        h1: BivariateProbs { home: 0.1, away: 0.08, common: 0.01 },
+        h2: BivariateProbs { home: 0.12, away: 0.1, common: 0.01 },
+    });
+    let outcomes = MatchResult::ALL
+        .iter()
+        .flat_map(|ht_result| {
+            MatchResult::ALL
+                .iter()
+                .map(|ft_result| Outcome::HalfTimeFullTime(ht_result.clone(), ft_result.clone()))
+        })
+        .collect::<Vec<_>>();
+    model
+        .derive(
+            &[
+                Stub {
+                    offer_type: OfferType::HalfTimeFullTime,
+                    outcomes: HashLookup::from(outcomes),
+                    normal: 1.0,
+                    overround: OVERROUND.clone(),
+                },
+                Stub {
+                    offer_type: OfferType::HeadToHead(Period::FullTime, DrawHandicap::Ahead(0)),
+                    outcomes: HashLookup::from([
+                        Outcome::Win(Side::Home, WinHandicap::AheadOver(0)),
+                        Outcome::Draw(DrawHandicap::Ahead(0)),
+                        Outcome::Win(Side::Away, WinHandicap::BehindUnder(0)),
+                    ]),
+                    normal: 1.0,
+                    overround: OVERROUND.clone(),
+                },
+            ],
+            &SINGLE_PRICE_BOUNDS,
+        )
+        .unwrap();
+    print_offers(model.offers());
+
+    let ht_ft = model.offers().get(&OfferType::HalfTimeFullTime).unwrap();
+    ht_ft.validate().unwrap();
+    let h2h = model
+        .offers()
+        .get(&OfferType::HeadToHead(Period::FullTime, DrawHandicap::Ahead(0)))
+        .unwrap();
+
+    // the full-time result is the marginal of the HT/FT outcomes
+    for (ft_result, ft_outcome) in [
+        (MatchResult::Win(Side::Home), Outcome::Win(Side::Home, WinHandicap::AheadOver(0))),
+        (MatchResult::Draw, Outcome::Draw(DrawHandicap::Ahead(0))),
+        (MatchResult::Win(Side::Away), Outcome::Win(Side::Away, WinHandicap::BehindUnder(0))),
+    ] {
+        let marginal = MatchResult::ALL
+            .iter()
+            .map(|ht_result| {
+                ht_ft
+                    .get_probability(&Outcome::HalfTimeFullTime(ht_result.clone(), ft_result.clone()))
+                    .unwrap()
+            })
+            .sum::<f64>();
+        assert_float_absolute_eq!(h2h.get_probability(&ft_outcome).unwrap(), marginal, EPSILON);
+    }
+
+    // a HT/FT leg implies the corresponding full-time result
+    let home_home = Outcome::HalfTimeFullTime(MatchResult::Win(Side::Home), MatchResult::Win(Side::Home));
+    let derivation = model
+        .derive_multi(&[
+            (OfferType::HalfTimeFullTime, home_home.clone()),
+            (
+                OfferType::HeadToHead(Period::FullTime, DrawHandicap::Ahead(0)),
+                Outcome::Win(Side::Home, WinHandicap::AheadOver(0)),
+            ),
+        ])
+        .unwrap()
+        .value;
+    assert_eq!(
+        vec![(
+            OfferType::HeadToHead(Period::FullTime, DrawHandicap::Ahead(0)),
+            Outcome::Win(Side::Home, WinHandicap::AheadOver(0)),
+        )],
+        derivation.redundancies
+    );
+    assert_float_absolute_eq!(
+        ht_ft.get_probability(&home_home).unwrap(),
+        derivation.quotation.probability,
+        EPSILON
+    );
+}
+
+#[test]
+pub fn double_chance_and_winning_margin() {
+    let mut model = create_test_model();
+    model.goal_probs = Some(GoalProbs {
+        h1: BivariateProbs { home: 0.1, away: 0.08, common: 0.01 },
+        h2: BivariateProbs { home: 0.12, away: 0.1, common: 0.01 },
+    });
+    let double_chance_outcomes = [
+        Outcome::DoubleChance(MatchResult::Win(Side::Home), MatchResult::Draw),
+        Outcome::DoubleChance(MatchResult::Win(Side::Home), MatchResult::Win(Side::Away)),
+        Outcome::DoubleChance(MatchResult::Draw, MatchResult::Win(Side::Away)),
+    ];
+    let winning_margin_outcomes = [
+        Outcome::WinBy(Side::Home, 1),
+        Outcome::WinBy(Side::Home, 2),
+        Outcome::Win(Side::Home, WinHandicap::AheadOver(2)),
+        Outcome::Draw(DrawHandicap::Ahead(0)),
+        Outcome::WinBy(Side::Away, 1),
+        Outcome::WinBy(Side::Away, 2),
+        Outcome::Win(Side::Away, WinHandicap::AheadOver(2)),
+    ];
+    let correct_score_outcomes = (0..=8)
+        .flat_map(|home| (0..=8 - home).map(move |away| Outcome::Score(Score::new(home, away))))
+        .collect::<Vec<_>>();
+    model
+        .derive(
+            &[
+                Stub {
+                    offer_type: OfferType::DoubleChance(Period::FullTime),
+                    outcomes: HashLookup::from(double_chance_outcomes),
+                    normal: 2.0,
+                    overround: OVERROUND.clone(),
+                },
+                Stub {
+                    offer_type: OfferType::WinningMargin(Period::FullTime),
+                    outcomes: HashLookup::from(winning_margin_outcomes),
+                    normal: 1.0,
+                    overround: OVERROUND.clone(),
+                },
+                Stub {
+                    offer_type: OfferType::CorrectScore(Period::FullTime),
+                    outcomes: HashLookup::from(correct_score_outcomes),
+                    normal: 1.0,
+                    overround: OVERROUND.clone(),
+                },
+            ],
+            &SINGLE_PRICE_BOUNDS,
+        )
+        .unwrap();
+    print_offers(model.offers());
+    model.offers().get(&OfferType::DoubleChance(Period::FullTime)).unwrap().validate().unwrap();
+    model.offers().get(&OfferType::WinningMargin(Period::FullTime)).unwrap().validate().unwrap();
+
+    // a correct score implies both the double chance and the winning margin that it falls into
+    let one_nil = (OfferType::CorrectScore(Period::FullTime), Outcome::Score(Score::new(1, 0)));
+    let home_or_draw = (
+        OfferType::DoubleChance(Period::FullTime),
+        Outcome::DoubleChance(MatchResult::Win(Side::Home), MatchResult::Draw),
+    );
+    let home_by_one = (OfferType::WinningMargin(Period::FullTime), Outcome::WinBy(Side::Home, 1));
+    let derivation = model
+        .derive_multi(&[home_or_draw.clone(), one_nil.clone(), home_by_one.clone()])
+        .unwrap()
+        .value;
+    assert_eq!(2, derivation.redundancies.len());
+    assert!(derivation.redundancies.contains(&home_or_draw));
+    assert!(derivation.redundancies.contains(&home_by_one));
+    let one_nil_prob = model
+        .offers()
+        .get(&one_nil.0)
+        .unwrap()
+        .get_probability(&one_nil.1)
+        .unwrap();
+    assert_float_relative_eq!(one_nil_prob, derivation.quotation.probability, 1e-2);
+}
+
+#[test]
+pub fn player_to_score_brace() {
+    let mut model = create_test_model();
+    model.goal_probs = Some(GoalProbs {
+        h1: BivariateProbs { home: 0.1, away: 0.08, common: 0.01 },
+        h2: BivariateProbs { home: 0.12, away: 0.1, common: 0.01 },
+    });
+    let player = Player::Named(Side::Home, "Markos".into());
+    model.player_probs.insert(player.clone(), PlayerProbs { goal: Some(0.25), assist: None });
+    for (offer_type, prob) in [
+        (OfferType::AnytimeGoalscorer, 0.3),
+        (OfferType::PlayerToScore(2), 0.05),
+        (OfferType::LastGoalscorer, 0.1),
+    ] {
+        model.insert_offer(Offer {
+            offer_type,
+            outcomes: HashLookup::from([Outcome::Player(player.clone())]),
+            market: Market::frame(&OVERROUND, vec![prob], &SINGLE_PRICE_BOUNDS),
+        });
+    }
+
+    // scoring a brace implies scoring at least once
+    let anytime = (OfferType::AnytimeGoalscorer, Outcome::Player(player.clone()));
+    let brace = (OfferType::PlayerToScore(2), Outcome::Player(player.clone()));
+    let derivation = model
+        .derive_multi(&[anytime.clone(), brace.clone()])
+        .unwrap()
+        .value;
+    assert_eq!(vec![anytime], derivation.redundancies);
+    assert!(derivation.quotation.probability > 0.0);
+    assert!(derivation.quotation.probability < 0.25);
+
+    // scoring last is not implied by a brace
+    let last = (OfferType::LastGoalscorer, Outcome::Player(player.clone()));
+    let derivation = model.derive_multi(&[brace, last]).unwrap().value;
+    assert!(derivation.redundancies.is_empty());
+}
+
+#[test]
+pub fn multi_pricing_policy() {
+    let mut model = create_test_model();
+    model.goal_probs = Some(create_test_goal_probs());
+    let player = Player::Named(Side::Home, "Markos".into());
+    model.player_probs.insert(player.clone(), PlayerProbs { goal: Some(0.25), assist: None });
+    let overround = Overround {
+        method: OverroundMethod::Multiplicative,
+        value: 1.1,
+    };
+    for (offer_type, prob) in [(OfferType::AnytimeGoalscorer, 0.3), (OfferType::PlayerToScore(2), 0.05)] {
+        model.insert_offer(Offer {
+            offer_type,
+            outcomes: HashLookup::from([Outcome::Player(player.clone())]),
+            market: Market::frame(&overround, vec![prob], &SINGLE_PRICE_BOUNDS),
+        });
+    }
+    insert_head_to_head(&mut model, DrawHandicap::Ahead(0), vec![2.0, 3.5, 4.5]);
+    let selections = [
+        (OfferType::AnytimeGoalscorer, Outcome::Player(player.clone())),
+        (OfferType::PlayerToScore(2), Outcome::Player(player.clone())),
+    ];
+
+    // by default, the redundant anytime goalscorer leg carries no overround
+    let default = model.derive_multi(&selections).unwrap().value;
+    assert_eq!(1, default.redundancies.len());
+    assert_float_relative_eq!(1.1, default.quotation.overround());
+
+    let charged = model
+        .derive_multi_with_policy(
+            &selections,
+            &MultiPricingPolicy {
+                redundancies: RedundancyHandling::Charge,
+                ..MultiPricingPolicy::default()
+            },
+        )
+        .unwrap()
+        .value;
+    assert_eq!(default.quotation.probability, charged.quotation.probability);
+    assert_float_relative_eq!(1.21, charged.quotation.overround());
+
+    // a charged leg also counts toward the relatedness, and is carried into the fringes
+    assert_eq!(default.redundancies, charged.redundancies);
+    assert!(charged.relatedness < default.relatedness);
+    let head_to_head = OfferType::HeadToHead(Period::FullTime, DrawHandicap::Ahead(0));
+    let (default_fringe, charged_fringe) =
+        (&default.fringes[&head_to_head][0], &charged.fringes[&head_to_head][0]);
+    assert!(default_fringe.redundancies.is_empty());
+    assert_float_relative_eq!(
+        1.1,
+        charged_fringe.quotation.overround() / default_fringe.quotation.overround()
+    );
+    assert_eq!(vec![selections[0].clone()], charged_fringe.redundancies);
+
+    let capped = model
+        .derive_multi_with_policy(
+            &selections,
+            &MultiPricingPolicy {
+                overround: OverroundComposition::Capped(1.15),
+                redundancies: RedundancyHandling::Charge,
+                ..MultiPricingPolicy::default()
+            },
+        )
+        .unwrap()
+        .value;
+    assert_float_relative_eq!(1.15, capped.quotation.overround());
+
+    assert_eq!(
+        "overround cap cannot be less than 1",
+        model
+            .derive_multi_with_policy(
+                &selections,
+                &MultiPricingPolicy {
+                    overround: OverroundComposition::Capped(0.5),
+                    ..MultiPricingPolicy::default()
+                },
+            )
+            .unwrap_err()
+            .to_string()
+    );
+}
+
+#[test]
+pub fn player_goals_by_half() {
+    fn selection_prob(model: &Model, selection: (OfferType, Outcome)) -> f64 {
+        model.derive_multi(&[selection]).unwrap().value.quotation.probability
+    }
+
+    let mut source_model = create_test_model();
+    source_model.goal_probs = Some(create_test_goal_probs());
+    let players = [
+        (Player::Named(Side::Home, "Markos".into()), 0.25),
+        (Player::Named(Side::Away, "Nikos".into()), 0.2),
+        (Player::Named(Side::Home, "Kostas".into()), 0.15),
+    ];
+    for (player, goal_prob) in &players {
+        source_model.player_probs.insert(player.clone(), PlayerProbs { goal: Some(*goal_prob), assist: None });
+    }
+
+    let markos = Outcome::Player(players[0].0.clone());
+    let nikos = Outcome::Player(players[1].0.clone());
+    let kostas = Outcome::Player(players[2].0.clone());
+    let sourced = [
+        (OfferType::FirstGoalscorer, vec![markos.clone(), Outcome::None]),
+        (OfferType::AnytimeGoalscorer, vec![markos.clone()]),
+        (OfferType::PlayerToScoreInHalf(Period::FirstHalf), vec![nikos.clone()]),
+        (OfferType::PlayerToScoreInHalf(Period::SecondHalf), vec![nikos.clone()]),
+        (OfferType::PlayerToScoreInBothHalves, vec![nikos.clone()]),
+        (OfferType::PlayerToScoreMultipleInHalf(Period::SecondHalf, 2), vec![kostas.clone()]),
+    ];
+    source_model
+        .derive(
+            &sourced
+                .iter()
+                .map(|(offer_type, outcomes)| stub(offer_type.clone(), outcomes.clone()))
+                .collect::<Vec<_>>(),
+            &SINGLE_PRICE_BOUNDS,
+        )
+        .unwrap();
+
+    // scoring in both halves is a multi of scoring in each half
+    let multi = source_model
+        .derive_multi(&[
+            (OfferType::PlayerToScoreInHalf(Period::FirstHalf), nikos.clone()),
+            (OfferType::PlayerToScoreInHalf(Period::SecondHalf), nikos.clone()),
+        ])
+        .unwrap()
+        .value;
+    assert_float_relative_eq!(
+        selection_prob(&source_model, (OfferType::PlayerToScoreInBothHalves, nikos)),
+        multi.quotation.probability,
+        1e-6
+    );
+
+    // fit Markos from the goalscorer offers, and the others from the offers by half
+    let mut offers = FxHashMap::default();
+    for (offer_type, outcomes) in sourced {
+        if matches!(offer_type, OfferType::PlayerToScoreInHalf(_)) {
+            continue;
+        }
+        let probs = outcomes
+            .iter()
+            .map(|outcome| selection_prob(&source_model, (offer_type.clone(), outcome.clone())))
+            .collect::<Vec<_>>();
+        offers.insert(
+            offer_type.clone(),
+            Offer {
+                offer_type,
+                outcomes: HashLookup::from(outcomes),
+                market: Market::frame(&OVERROUND, probs, &SINGLE_PRICE_BOUNDS),
+            },
+        );
+    }
+
+    let mut model = create_test_model();
+    model.goal_probs = Some(create_test_goal_probs());
+    PlayerGoalFitter.fit(&mut model, &offers).unwrap();
+    for (player, goal_prob) in &players {
+        let fitted_goal_prob = model.player_probs.get(player).unwrap().goal.unwrap();
+        assert_float_relative_eq!(*goal_prob, fitted_goal_prob, 1e-2);
+    }
+}
+
+#[test]
+pub fn exploration_cache_persists_across_derivations() {
+    let mut model = create_test_model().with_exploration_cache(Arc::new(ExplorationCache::with_capacity(4)));
+    model.goal_probs = Some(create_test_goal_probs());
+    insert_head_to_head(&mut model, DrawHandicap::Ahead(0), vec![2.0, 3.5, 4.5]);
+    let selections = [(
+        OfferType::HeadToHead(Period::FullTime, DrawHandicap::Ahead(0)),
+        Outcome::Win(Side::Home, WinHandicap::AheadOver(0)),
+    )];
+
+    let first = model.derive_multi(&selections).unwrap().value;
+    let stats = model.exploration_cache().stats();
+    assert_eq!(0, stats.hits());
+    assert!(stats.misses() > 0);
+
+    // repeating the quote reuses the earlier explorations
+    let second = model.derive_multi(&selections).unwrap().value;
+    assert_eq!(first.quotation.probability, second.quotation.probability);
+    assert_eq!(stats.misses(), model.exploration_cache().stats().misses());
+    assert!(model.exploration_cache().stats().hits() > 0);
+    assert!(model.exploration_cache().len() <= 4);
+
+    // changing the goal probabilities invalidates the cache
+    model.goal_probs = Some(GoalProbs {
+        h1: BivariateProbs { home: 0.12, away: 0.08, common: 0.01 },
+        h2: BivariateProbs { home: 0.14, away: 0.1, common: 0.01 },
+    });
+    let third = model.derive_multi(&selections).unwrap().value;
+    assert_eq!(1, model.exploration_cache().stats().invalidations());
+    assert!(third.quotation.probability > first.quotation.probability);
+}
+
+#[test]
+pub fn multi_with_many_players() {
+    let mut model = create_test_model();
+    model.goal_probs = Some(GoalProbs {
+        h1: BivariateProbs { home: 0.1, away: 0.08, common: 0.01 },
+        h2: BivariateProbs { home: 0.12, away: 0.1, common: 0.01 },
+    });
+    let players = (0..4)
+        .map(|index| {
+            let side = if index % 2 == 0 { Side::Home } else { Side::Away };
+            Player::Named(side, format!("Player {index}"))
+        })
+        .collect::<Vec<_>>();
+    for player in &players {
+        model.player_probs.insert(player.clone(), PlayerProbs { goal: Some(0.15), assist: None });
+    }
+    model.insert_offer(Offer {
+        offer_type: OfferType::AnytimeGoalscorer,
+        outcomes: HashLookup::from(
+            players.iter().map(|player| Outcome::Player(player.clone())).collect::<Vec<_>>(),
+        ),
+        market: Market::frame(&OVERROUND, vec![0.2; players.len()], &SINGLE_PRICE_BOUNDS),
+    });
+
+    let selections = players
+        .iter()
+        .map(|player| (OfferType::AnytimeGoalscorer, Outcome::Player(player.clone())))
+        .collect::<Vec<_>>();
+    let derivation = model.derive_multi(&selections).unwrap().value;
+    assert!(derivation.redundancies.is_empty());
+    assert!(derivation.quotation.probability > 0.0);
+    assert!(derivation.quotation.probability < 0.2f64.powi(players.len() as i32));
+}
+
+fn stub_total_goals(period: Period, over: u8) -> Stub {
+    Stub {
+        offer_type: OfferType::TotalGoals(period, Over(over)),
+        outcomes: HashLookup::from([Outcome::Over(over), Outcome::Under(over + 1)]),
+        normal: 1.0,
+        overround: OVERROUND.clone(),
+    }
+}
+
+fn stub_split_total_goals(period: Period, whole: u8, over: u8) -> Stub {
+    Stub {
+        offer_type: OfferType::SplitTotalGoals(period, whole, Over(over)),
+        outcomes: HashLookup::from([
+            Outcome::SplitOver(whole, over),
+            Outcome::SplitUnder(whole, over + 1),
+        ]),
+        normal: 1.0,
+        overround: OVERROUND.clone(),
+    }
+}
+
+#[test]
+pub fn split_total_goals() {
+    let mut model = create_test_model();
+    model.goal_probs = Some(create_test_goal_probs());
+    model
+        .derive(
+            &[
+                stub_total_goals(Period::FullTime, 1),
+                stub_total_goals(Period::FullTime, 2),
+                stub_total_goals(Period::FullTime, 3),
+                stub_split_total_goals(Period::FullTime, 2, 2), // 2.25
+                stub_split_total_goals(Period::FullTime, 3, 2), // 2.75
+            ],
+            &SINGLE_PRICE_BOUNDS,
+        )
+        .unwrap();
+    print_offers(model.offers());
+
+    let over_prob = |over| {
+        model
+            .offers()
+            .get(&OfferType::TotalGoals(Period::FullTime, Over(over)))
+            .unwrap()
+            .get_probability(&Outcome::Over(over))
+            .unwrap()
+    };
+    let (two_goals_prob, three_goals_prob) = (over_prob(1) - over_prob(2), over_prob(2) - over_prob(3));
+
+    // over 2.25 loses half the stake on exactly 2 goals
+    let offer = model
+        .offers()
+        .get(&OfferType::SplitTotalGoals(Period::FullTime, 2, Over(2)))
+        .unwrap();
+    let over_2_25_prob = offer.get_probability(&Outcome::SplitOver(2, 2)).unwrap();
+    assert_float_absolute_eq!(over_prob(2) / (1.0 - 0.5 * two_goals_prob), over_2_25_prob, 1e-6);
+    let over_2_25_settlement = SettlementProbs {
+        win: over_prob(2),
+        half_loss: two_goals_prob,
+        loss: 1.0 - over_prob(1),
+        ..SettlementProbs::default()
+    };
+    assert_float_absolute_eq!(1.0, over_2_25_settlement.expected_return(1.0 / over_2_25_prob), 1e-6);
+
+    // over 2.75 wins half the stake on exactly 3 goals
+    let offer = model
+        .offers()
+        .get(&OfferType::SplitTotalGoals(Period::FullTime, 3, Over(2)))
+        .unwrap();
+    let over_2_75_prob = offer.get_probability(&Outcome::SplitOver(3, 2)).unwrap();
+    assert_float_absolute_eq!(
+        (over_prob(3) + 0.5 * three_goals_prob) / (1.0 - 0.5 * three_goals_prob),
+        over_2_75_prob,
+        1e-6
+    );
+    assert!(over_prob(3) < over_2_75_prob && over_2_75_prob < over_2_25_prob && over_2_25_prob < over_prob(1));
+
+    // split offers are not supported in multis
+    assert!(model
+        .derive_multi(&[(
+            OfferType::SplitTotalGoals(Period::FullTime, 2, Over(2)),
+            Outcome::SplitOver(2, 2)
+        )])
+        .is_err());
+}
+
+#[test]
+pub fn score_fitter_joint_fit() {
+    let goal_probs = create_test_goal_probs();
+    let (sample_offers, stubs) = create_goal_model_offers(&goal_probs);
+
+    let mut model = create_test_model();
+    let residuals = ScoreFitter::try_from(score_fitter::Config::default())
+        .unwrap()
+        .fit(&mut model, &sample_offers)
+        .unwrap();
+    for residual in &residuals {
+        println!("{residual:?}");
+    }
+
+    // every sourced offer contributes to the fit, not just the most balanced lines
+    assert_eq!(stubs, residuals.len());
+    let total_residual = residuals.iter().map(|residual| residual.residual).sum::<f64>();
+    assert!(total_residual < 0.05, "total_residual: {total_residual}");
+
+    let fitted = model.goal_probs.unwrap();
+    for (expected, actual) in [(&goal_probs.h1, &fitted.h1), (&goal_probs.h2, &fitted.h2)] {
+        assert_float_absolute_eq!(expected.home, actual.home, 0.02);
+        assert_float_absolute_eq!(expected.away, actual.away, 0.02);
+        assert_float_absolute_eq!(expected.common, actual.common, 0.02);
+    }
+}
+
+#[test]
+pub fn score_fitter_score_distributions() {
+    let goal_probs = create_test_goal_probs();
+    let (sample_offers, stubs) = create_goal_model_offers(&goal_probs);
+
+    for distribution in [
+        ScoreDistribution::DixonColes,
+        ScoreDistribution::NegativeBinomial,
+        ScoreDistribution::WeibullCount,
+    ] {
+        let mut model = create_test_model();
+        let residuals = ScoreFitter::try_from(score_fitter::Config {
+            score_distribution: Some(distribution),
+            ..score_fitter::Config::default()
+        })
+        .unwrap()
+        .fit(&mut model, &sample_offers)
+        .unwrap();
+        let total_residual = residuals.iter().map(|residual| residual.residual).sum::<f64>();
+        println!("{distribution:?}: total_residual: {total_residual}, {:?}", model.goal_probs);
+        assert_eq!(stubs, residuals.len());
+
+        // the sample offers are sourced from a bivariate binomial, which the alternative
+        // distributions only approximate; however, the full-time result should be reproduced
+        let ft_h2h_residual = residuals
+            .iter()
+            .find(|residual| {
+                residual.offer_type == OfferType::HeadToHead(Period::FullTime, DrawHandicap::Ahead(0))
+            })
+            .unwrap()
+            .residual;
+        assert!(ft_h2h_residual < 0.01, "{distribution:?} ft_h2h_residual: {ft_h2h_residual}");
+
+        let fitted = model.goal_probs.unwrap();
+        for (expected, actual) in [(&goal_probs.h1, &fitted.h1), (&goal_probs.h2, &fitted.h2)] {
+            assert_float_absolute_eq!(expected.home, actual.home, 0.03);
+            assert_float_absolute_eq!(expected.away, actual.away, 0.03);
+        }
+    }
+}
+
+fn create_test_goal_probs() -> GoalProbs {
+    GoalProbs {
+        h1: BivariateProbs { home: 0.1, away: 0.08, common: 0.01 },
+        h2: BivariateProbs { home: 0.12, away: 0.1, common: 0.01 },
+    }
+}
+
+/// Derives head-to-head and total goals offers for each period, and a full-time correct score
+/// offer, from the given goal probabilities. Returns the offers and the number of stubs.
+fn create_goal_model_offers(goal_probs: &GoalProbs) -> (FxHashMap<OfferType, Offer>, usize) {
+    let mut source_model = create_test_model();
+    source_model.goal_probs = Some(goal_probs.clone());
+    let h2h_outcomes = HashLookup::from([
+        Outcome::Win(Side::Home, WinHandicap::AheadOver(0)),
+        Outcome::Draw(DrawHandicap::Ahead(0)),
+        Outcome::Win(Side::Away, WinHandicap::BehindUnder(0)),
+    ]);
+    let mut stubs = vec![];
+    for period in [Period::FirstHalf, Period::SecondHalf, Period::FullTime] {
+        stubs.push(Stub {
+            offer_type: OfferType::HeadToHead(period.clone(), DrawHandicap::Ahead(0)),
+            outcomes: h2h_outcomes.clone(),
+            normal: 1.0,
+            overround: OVERROUND.clone(),
+        });
+        for goals in 0..=3 {
+            stubs.push(Stub {
+                offer_type: OfferType::TotalGoals(period.clone(), Over(goals)),
+                outcomes: HashLookup::from([Outcome::Over(goals), Outcome::Under(goals + 1)]),
+                normal: 1.0,
+                overround: OVERROUND.clone(),
+            });
+        }
+    }
+    stubs.push(Stub {
+        offer_type: OfferType::CorrectScore(Period::FullTime),
+        outcomes: HashLookup::from(
+            (0..=8)
+                .flat_map(|home| (0..=8 - home).map(move |away| Outcome::Score(Score::new(home, away))))
+                .collect::<Vec<_>>(),
+        ),
+        normal: 1.0,
+        overround: OVERROUND.clone(),
+    });
+    source_model.derive(&stubs, &SINGLE_PRICE_BOUNDS).unwrap();
+    (source_model.offers, stubs.len())
+}
+
 fn assert_prices(
     offers: &FxHashMap<OfferType, Offer>,
     offer_type: &OfferType,
@@ -305,3 +952,784 @@ fn sort_tuples<K: Ord, V>(tuples: impl IntoIterator<Item = (K, V)>) -> Vec<(K, V
     tuples.sort_by(|(k1, _), (k2, _)| k1.cmp(k2));
     tuples
 }
+
+fn stub_knockout(offer_type: OfferType) -> Stub {
+    let outcomes = match offer_type {
+        OfferType::ToQualify => HashLookup::from([Outcome::Qualify(Side::Home), Outcome::Qualify(Side::Away)]),
+        OfferType::MethodOfVictory => HashLookup::from(
+            [Side::Home, Side::Away]
+                .iter()
+                .flat_map(|side| VictoryMethod::ALL.map(|method| Outcome::Victory(side.clone(), method)))
+                .collect::<Vec<_>>(),
+        ),
+        _ => unreachable!(),
+    };
+    Stub {
+        offer_type,
+        outcomes,
+        normal: 1.0,
+        overround: OVERROUND.clone(),
+    }
+}
+
+#[test]
+pub fn knockout() {
+    let mut model = create_test_model();
+    let goal_probs = create_test_goal_probs();
+    model.goal_probs = Some(goal_probs.clone());
+    let stubs = [
+        stub_knockout(OfferType::ToQualify),
+        stub_knockout(OfferType::MethodOfVictory),
+    ];
+    assert_eq!(
+        "missing knockout parameters",
+        model.derive(&stubs, &SINGLE_PRICE_BOUNDS).unwrap_err().to_string()
+    );
+
+    model.knockout = Some(Knockout {
+        aggregate: Score::nil_all(),
+        extra_time: ExtraTime::from_regular_time(model.config.intervals, &goal_probs),
+        shootout: Shootout::default(),
+    });
+    let h2h_outcomes = HashLookup::from([
+        Outcome::Win(Side::Home, WinHandicap::AheadOver(0)),
+        Outcome::Draw(DrawHandicap::Ahead(0)),
+        Outcome::Win(Side::Away, WinHandicap::BehindUnder(0)),
+    ]);
+    let h2h_stub = Stub {
+        offer_type: OfferType::HeadToHead(Period::FullTime, DrawHandicap::Ahead(0)),
+        outcomes: h2h_outcomes.clone(),
+        normal: 1.0,
+        overround: OVERROUND.clone(),
+    };
+    model.derive(&[h2h_stub], &SINGLE_PRICE_BOUNDS).unwrap();
+    model.derive(&stubs, &SINGLE_PRICE_BOUNDS).unwrap();
+    print_offers(model.offers());
+
+    let h2h = model.offers().get(&OfferType::HeadToHead(Period::FullTime, DrawHandicap::Ahead(0))).unwrap();
+    let to_qualify = model.offers().get(&OfferType::ToQualify).unwrap();
+    let method_of_victory = model.offers().get(&OfferType::MethodOfVictory).unwrap();
+    for side in [Side::Home, Side::Away] {
+        // winning in regular time is the same as winning the head-to-head
+        let h2h_outcome = &h2h_outcomes[match side {
+            Side::Home => 0,
+            Side::Away => 2,
+        }];
+        assert_float_absolute_eq!(
+            h2h.get_probability(h2h_outcome).unwrap(),
+            method_of_victory
+                .get_probability(&Outcome::Victory(side.clone(), VictoryMethod::RegularTime))
+                .unwrap(),
+            1e-6
+        );
+
+        let method_probs = VictoryMethod::ALL
+            .iter()
+            .map(|method| method_of_victory.get_probability(&Outcome::Victory(side.clone(), method.clone())).unwrap())
+            .sum::<f64>();
+        assert_float_absolute_eq!(to_qualify.get_probability(&Outcome::Qualify(side)).unwrap(), method_probs, 1e-6);
+    }
+    let home_qualify_prob = to_qualify.get_probability(&Outcome::Qualify(Side::Home)).unwrap();
+    assert!(home_qualify_prob > 0.5, "{home_qualify_prob}");
+
+    // trailing from the first leg makes the home side less likely to progress
+    model.knockout.as_mut().unwrap().aggregate = Score::new(0, 1);
+    model.derive(&stubs[..1], &SINGLE_PRICE_BOUNDS).unwrap();
+    let trailing_home_qualify_prob = model
+        .offers()
+        .get(&OfferType::ToQualify)
+        .unwrap()
+        .get_probability(&Outcome::Qualify(Side::Home))
+        .unwrap();
+    assert!(trailing_home_qualify_prob < 0.5, "{trailing_home_qualify_prob}");
+
+    // knockout offers are not supported in multis
+    assert!(model
+        .derive_multi(&[(OfferType::ToQualify, Outcome::Qualify(Side::Home))])
+        .is_err());
+}
+
+fn stub(offer_type: OfferType, outcomes: Vec<Outcome>) -> Stub {
+    Stub {
+        offer_type,
+        outcomes: HashLookup::from(outcomes),
+        normal: 1.0,
+        overround: OVERROUND.clone(),
+    }
+}
+
+#[test]
+pub fn goal_times() {
+    let mut model = Model::try_from(Config {
+        intervals: 18,
+        max_total_goals: 8,
+    })
+    .unwrap();
+    model.goal_probs = Some(create_test_goal_probs());
+    model
+        .derive(
+            &[
+                stub(OfferType::TotalGoals(Period::FullTime, Over(0)), vec![Outcome::Over(0), Outcome::Under(1)]),
+                stub(
+                    OfferType::TimeOfFirstGoal,
+                    vec![
+                        Outcome::FirstGoalIn(TimeBand::new(0, 15)),
+                        Outcome::FirstGoalIn(TimeBand::new(15, 45)),
+                        Outcome::FirstGoalIn(TimeBand::new(45, 90)),
+                        Outcome::None,
+                    ],
+                ),
+                stub(OfferType::GoalInTimeBand(TimeBand::new(0, 15)), vec![Outcome::Yes, Outcome::No]),
+                stub(OfferType::GoalInTimeBand(TimeBand::new(80, 90)), vec![Outcome::Yes, Outcome::No]),
+                stub(OfferType::GoalInBothHalves, vec![Outcome::Yes, Outcome::No]),
+                stub(
+                    OfferType::HighestScoringHalf,
+                    vec![Outcome::Half(Period::FirstHalf), Outcome::Half(Period::SecondHalf), Outcome::None],
+                ),
+            ],
+            &SINGLE_PRICE_BOUNDS,
+        )
+        .unwrap();
+    print_offers(model.offers());
+    for offer in model.offers().values() {
+        offer.validate().unwrap();
+    }
+
+    let get_prob = |offer_type: &OfferType, outcome: &Outcome| {
+        model.offers().get(offer_type).unwrap().get_probability(outcome).unwrap()
+    };
+    assert_float_absolute_eq!(
+        get_prob(&OfferType::TotalGoals(Period::FullTime, Over(0)), &Outcome::Under(1)),
+        get_prob(&OfferType::TimeOfFirstGoal, &Outcome::None),
+        1e-6
+    );
+
+    // a first goal inside the first 15 minutes is the same as a goal in the first 15 minutes
+    assert_float_absolute_eq!(
+        get_prob(&OfferType::TimeOfFirstGoal, &Outcome::FirstGoalIn(TimeBand::new(0, 15))),
+        get_prob(&OfferType::GoalInTimeBand(TimeBand::new(0, 15)), &Outcome::Yes),
+        1e-6
+    );
+
+    // the second half is more likely to be the highest scoring
+    assert!(
+        get_prob(&OfferType::HighestScoringHalf, &Outcome::Half(Period::SecondHalf))
+            > get_prob(&OfferType::HighestScoringHalf, &Outcome::Half(Period::FirstHalf))
+    );
+
+    // goals in non-overlapping bands are nearly independent when the goal rate is constant
+    let early_goal = (OfferType::GoalInTimeBand(TimeBand::new(0, 15)), Outcome::Yes);
+    let late_goal = (OfferType::GoalInTimeBand(TimeBand::new(80, 90)), Outcome::Yes);
+    let derivation = model
+        .derive_multi(&[early_goal.clone(), late_goal.clone()])
+        .unwrap()
+        .value;
+    assert_float_relative_eq!(
+        get_prob(&early_goal.0, &early_goal.1) * get_prob(&late_goal.0, &late_goal.1),
+        derivation.quotation.probability,
+        1e-2
+    );
+}
+
+#[test]
+pub fn scoring_team() {
+    let mut model = Model::try_from(Config {
+        intervals: 18,
+        max_total_goals: 8,
+    })
+    .unwrap();
+    model.goal_probs = Some(create_test_goal_probs());
+    let team_outcomes = || vec![Outcome::Team(Side::Home), Outcome::Team(Side::Away), Outcome::None];
+    model
+        .derive(
+            &[
+                stub(OfferType::FirstTeamToScore, team_outcomes()),
+                stub(OfferType::LastTeamToScore, team_outcomes()),
+                stub(OfferType::RaceToGoals(1), team_outcomes()),
+                stub(OfferType::RaceToGoals(2), team_outcomes()),
+                stub(OfferType::RaceToGoals(3), team_outcomes()),
+            ],
+            &SINGLE_PRICE_BOUNDS,
+        )
+        .unwrap();
+    print_offers(model.offers());
+    for offer in model.offers().values() {
+        offer.validate().unwrap();
+    }
+
+    let get_prob = |offer_type: &OfferType, outcome: &Outcome| {
+        model.offers().get(offer_type).unwrap().get_probability(outcome).unwrap()
+    };
+    for side in [Side::Home, Side::Away] {
+        assert_float_absolute_eq!(
+            get_prob(&OfferType::FirstTeamToScore, &Outcome::Team(side.clone())),
+            get_prob(&OfferType::RaceToGoals(1), &Outcome::Team(side.clone())),
+            1e-6
+        );
+    }
+    assert_float_absolute_eq!(
+        get_prob(&OfferType::FirstTeamToScore, &Outcome::None),
+        get_prob(&OfferType::LastTeamToScore, &Outcome::None),
+        1e-6
+    );
+
+    // longer races are less likely to be decided
+    assert!(
+        get_prob(&OfferType::RaceToGoals(3), &Outcome::None) > get_prob(&OfferType::RaceToGoals(2), &Outcome::None)
+    );
+    assert!(
+        get_prob(&OfferType::RaceToGoals(2), &Outcome::None) > get_prob(&OfferType::RaceToGoals(1), &Outcome::None)
+    );
+
+    // the first team to score is likely to also reach two goals first
+    let derivation = model
+        .derive_multi(&[
+            (OfferType::FirstTeamToScore, Outcome::Team(Side::Home)),
+            (OfferType::RaceToGoals(2), Outcome::Team(Side::Home)),
+        ])
+        .unwrap()
+        .value;
+    assert!(
+        derivation.quotation.probability
+            > get_prob(&OfferType::FirstTeamToScore, &Outcome::Team(Side::Home))
+                * get_prob(&OfferType::RaceToGoals(2), &Outcome::Team(Side::Home))
+    );
+}
+
+#[test]
+pub fn exact_total_goals_and_odd_even() {
+    let mut model = Model::try_from(Config {
+        intervals: 18,
+        max_total_goals: 8,
+    })
+    .unwrap();
+    model.goal_probs = Some(create_test_goal_probs());
+    let exact_outcomes = |over: u8| {
+        (0..=over)
+            .map(Outcome::Exactly)
+            .chain(std::iter::once(Outcome::Over(over)))
+            .collect::<Vec<_>>()
+    };
+    model
+        .derive(
+            &[
+                stub_total_goals(Period::FullTime, 2),
+                stub_total_goals(Period::FirstHalf, 0),
+                stub(OfferType::ExactTotalGoals(Period::FullTime, Over(2)), exact_outcomes(2)),
+                stub(OfferType::ExactTotalGoals(Period::FullTime, Over(7)), exact_outcomes(7)),
+                stub(OfferType::ExactTotalGoals(Period::FirstHalf, Over(1)), exact_outcomes(1)),
+                stub(OfferType::OddEvenGoals(Period::FullTime), vec![Outcome::Odd, Outcome::Even]),
+            ],
+            &SINGLE_PRICE_BOUNDS,
+        )
+        .unwrap();
+    print_offers(model.offers());
+    for offer in model.offers().values() {
+        offer.validate().unwrap();
+    }
+
+    let get_prob = |offer_type: &OfferType, outcome: &Outcome| {
+        model.offers().get(offer_type).unwrap().get_probability(outcome).unwrap()
+    };
+    let exact_ft = OfferType::ExactTotalGoals(Period::FullTime, Over(2));
+    assert_float_absolute_eq!(
+        get_prob(&OfferType::TotalGoals(Period::FullTime, Over(2)), &Outcome::Over(2)),
+        get_prob(&exact_ft, &Outcome::Over(2)),
+        1e-6
+    );
+    assert_float_absolute_eq!(
+        get_prob(&OfferType::TotalGoals(Period::FullTime, Over(2)), &Outcome::Under(3)),
+        (0..=2).map(|goals| get_prob(&exact_ft, &Outcome::Exactly(goals))).sum::<f64>(),
+        1e-6
+    );
+    assert_float_absolute_eq!(
+        get_prob(&OfferType::TotalGoals(Period::FirstHalf, Over(0)), &Outcome::Under(1)),
+        get_prob(&OfferType::ExactTotalGoals(Period::FirstHalf, Over(1)), &Outcome::Exactly(0)),
+        1e-6
+    );
+
+    // the odd totals make up the odd outcome
+    let exact_ft = OfferType::ExactTotalGoals(Period::FullTime, Over(7));
+    assert_float_absolute_eq!(
+        get_prob(&OfferType::OddEvenGoals(Period::FullTime), &Outcome::Odd),
+        [1, 3, 5, 7].into_iter().map(|goals| get_prob(&exact_ft, &Outcome::Exactly(goals))).sum::<f64>(),
+        1e-3
+    );
+
+    // an even total cannot be odd
+    let derivation = model
+        .derive_multi(&[
+            (OfferType::OddEvenGoals(Period::FullTime), Outcome::Odd),
+            (OfferType::ExactTotalGoals(Period::FullTime, Over(2)), Outcome::Exactly(2)),
+        ])
+        .unwrap()
+        .value;
+    assert_eq!(0.0, derivation.quotation.probability);
+}
+
+#[test]
+pub fn scorecast_and_wincast() {
+    fn selection_prob(model: &Model, selection: (OfferType, Outcome)) -> f64 {
+        model.derive_multi(&[selection]).unwrap().value.quotation.probability
+    }
+
+    let mut model = create_test_model();
+    model.goal_probs = Some(GoalProbs {
+        h1: BivariateProbs { home: 0.1, away: 0.08, common: 0.01 },
+        h2: BivariateProbs { home: 0.12, away: 0.1, common: 0.01 },
+    });
+    let players = [Player::Named(Side::Home, "Markos".into()), Player::Named(Side::Away, "Nikos".into())];
+    for player in &players {
+        model.player_probs.insert(player.clone(), PlayerProbs { goal: Some(0.25), assist: None });
+    }
+    let scorecast_outcomes = players
+        .iter()
+        .flat_map(|player| {
+            (0..=3).flat_map(move |home| {
+                (0..=3).map(move |away| Outcome::Scorecast(player.clone(), Score::new(home, away)))
+            })
+        })
+        .filter(|outcome| OfferType::Scorecast.validate_outcome(outcome).is_ok())
+        .collect::<Vec<_>>();
+    let wincast_outcomes = players
+        .iter()
+        .flat_map(|player| {
+            MatchResult::ALL
+                .iter()
+                .map(|match_result| Outcome::Wincast(player.clone(), match_result.clone()))
+        })
+        .collect::<Vec<_>>();
+    model
+        .derive(
+            &[
+                stub(OfferType::Scorecast, scorecast_outcomes),
+                stub(OfferType::Wincast, wincast_outcomes),
+            ],
+            &SINGLE_PRICE_BOUNDS,
+        )
+        .unwrap();
+    print_offers(model.offers());
+    for offer in model.offers().values() {
+        offer.validate().unwrap();
+    }
+
+    // the grid preserves the relative likelihoods of its outcomes
+    let scorecast = model.offers().get(&OfferType::Scorecast).unwrap();
+    let markos_one_nil = Outcome::Scorecast(players[0].clone(), Score::new(1, 0));
+    let nikos_one_two = Outcome::Scorecast(players[1].clone(), Score::new(1, 2));
+    assert_float_relative_eq!(
+        scorecast.get_probability(&markos_one_nil).unwrap() / scorecast.get_probability(&nikos_one_two).unwrap(),
+        selection_prob(&model, (OfferType::Scorecast, markos_one_nil)) / selection_prob(&model, (OfferType::Scorecast, nikos_one_two)),
+        1e-6
+    );
+
+    // a wincast is the same as a multi comprising its anytime goalscorer and match result legs
+    insert_head_to_head(&mut model, DrawHandicap::Ahead(0), vec![2.0, 3.5, 4.5]);
+    model.insert_offer(Offer {
+        offer_type: OfferType::AnytimeGoalscorer,
+        outcomes: HashLookup::from([Outcome::Player(players[0].clone())]),
+        market: Market::frame(&OVERROUND, vec![0.3], &SINGLE_PRICE_BOUNDS),
+    });
+    let multi = model
+        .derive_multi(&[
+            (OfferType::AnytimeGoalscorer, Outcome::Player(players[0].clone())),
+            (OfferType::HeadToHead(Period::FullTime, DrawHandicap::Ahead(0)), Outcome::Win(Side::Home, WinHandicap::AheadOver(0))),
+        ])
+        .unwrap()
+        .value;
+    assert_float_relative_eq!(
+        multi.quotation.probability,
+        selection_prob(&model, (OfferType::Wincast, Outcome::Wincast(players[0].clone(), MatchResult::Win(Side::Home)))),
+        1e-6
+    );
+}
+
+#[test]
+pub fn corners_and_bookings() {
+    let mut model = create_test_model();
+    model.goal_probs = Some(create_test_goal_probs());
+
+    // source offers from known event models, and fit to them
+    let mut offers = FxHashMap::default();
+    for (stream, sourced_model, total_over, handicap) in [
+        (
+            EventStream::Corners,
+            EventModel::from_negative_binomial(5.8, 4.2, 0.05, 24, 0.0),
+            9,
+            WinHandicap::AheadOver(1),
+        ),
+        (
+            EventStream::Bookings,
+            EventModel::from_negative_binomial(2.2, 1.8, 0.1, 24, 0.0),
+            35,
+            WinHandicap::BehindUnder(10),
+        ),
+    ] {
+        for (offer_type, outcomes) in [
+            (
+                OfferType::TotalEvents(stream.clone(), Over(total_over)),
+                vec![Outcome::Over(total_over), Outcome::Under(total_over + 1)],
+            ),
+            (
+                OfferType::TeamTotalEvents(stream.clone(), Side::Home, Over(total_over / 2)),
+                vec![Outcome::Over(total_over / 2), Outcome::Under(total_over / 2 + 1)],
+            ),
+            (
+                OfferType::EventHandicap(stream.clone(), handicap.clone()),
+                vec![
+                    Outcome::Win(Side::Home, handicap.clone()),
+                    Outcome::Win(Side::Away, handicap.flip_asian()),
+                ],
+            ),
+        ] {
+            let probs = outcomes
+                .iter()
+                .map(|outcome| sourced_model.isolate(&offer_type, outcome))
+                .collect::<Vec<_>>();
+            let offer = Offer {
+                offer_type: offer_type.clone(),
+                outcomes: HashLookup::from(outcomes),
+                market: Market::frame(&OVERROUND, probs, &SINGLE_PRICE_BOUNDS),
+            };
+            offers.insert(offer_type, offer);
+        }
+    }
+    let event_fitter = EventFitter::try_from(event_fitter::Config::default()).unwrap();
+    for stream in EventStream::ALL {
+        event_fitter.fit(&mut model, &stream, &offers).unwrap();
+    }
+    let (home_corners, away_corners) = model.events[&EventStream::Corners].expectations();
+    assert!(home_corners > away_corners, "{home_corners} vs {away_corners}");
+
+    let stubs = offers
+        .values()
+        .map(|offer| Stub {
+            offer_type: offer.offer_type.clone(),
+            outcomes: offer.outcomes.clone(),
+            normal: 1.0,
+            overround: OVERROUND.clone(),
+        })
+        .chain([stub_total_goals(Period::FullTime, 2)])
+        .collect::<Vec<_>>();
+    model.derive(&stubs, &SINGLE_PRICE_BOUNDS).unwrap();
+    print_offers(model.offers());
+    for (offer_type, sourced) in &offers {
+        let derived = model.offers().get(offer_type).unwrap();
+        derived.validate().unwrap();
+        assert_slice_f64_relative(&sourced.market.probs, &derived.market.probs, 0.05);
+    }
+
+    // combined with the goals, independently by default
+    let over_goals = (OfferType::TotalGoals(Period::FullTime, Over(2)), Outcome::Over(2));
+    let over_corners = (OfferType::TotalEvents(EventStream::Corners, Over(9)), Outcome::Over(9));
+    let over_bookings = (OfferType::TotalEvents(EventStream::Bookings, Over(35)), Outcome::Over(35));
+    let get_prob = |model: &Model, (offer_type, outcome): &(OfferType, Outcome)| {
+        model.offers().get(offer_type).unwrap().get_probability(outcome).unwrap()
+    };
+    let independent_prob = get_prob(&model, &over_goals) * get_prob(&model, &over_corners) * get_prob(&model, &over_bookings);
+    let derivation = model
+        .derive_multi(&[over_goals.clone(), over_corners.clone(), over_bookings.clone()])
+        .unwrap()
+        .value;
+    assert_float_relative_eq!(independent_prob, derivation.quotation.probability, 1e-2);
+
+    // more corners are expected when there are more goals
+    model.events.get_mut(&EventStream::Corners).unwrap().goal_correlation = 0.5;
+    let derivation = model
+        .derive_multi(&[over_goals.clone(), over_corners.clone()])
+        .unwrap()
+        .value;
+    assert!(derivation.quotation.probability > get_prob(&model, &over_goals) * get_prob(&model, &over_corners) * 1.05);
+
+    // the event model must be present
+    model.events.remove(&EventStream::Bookings);
+    assert_eq!(
+        "missing Bookings model",
+        model.derive_multi(&[over_goals, over_bookings]).unwrap_err().to_string()
+    );
+}
+
+#[test]
+pub fn player_bookings() {
+    let mut model = create_test_model();
+    model.goal_probs = Some(create_test_goal_probs());
+
+    // source offers from a known event model with known player shares, and fit to them
+    let markos = Player::Named(Side::Home, "Markos".into());
+    let nikos = Player::Named(Side::Away, "Nikos".into());
+    let mut sourced_model = EventModel::from_negative_binomial(2.2, 1.8, 0.1, 24, 0.0);
+    sourced_model.player_shares.insert(markos.clone(), 0.25);
+    sourced_model.player_shares.insert(nikos.clone(), 0.15);
+    let total = OfferType::TotalEvents(EventStream::Bookings, Over(35));
+    let carded = OfferType::PlayerEvents(EventStream::Bookings, Over(5));
+    let mut offers = FxHashMap::default();
+    for (offer_type, outcomes) in [
+        (total.clone(), vec![Outcome::Over(35), Outcome::Under(36)]),
+        (
+            OfferType::TeamTotalEvents(EventStream::Bookings, Side::Home, Over(15)),
+            vec![Outcome::Over(15), Outcome::Under(16)],
+        ),
+        (
+            OfferType::TeamTotalEvents(EventStream::Bookings, Side::Away, Over(15)),
+            vec![Outcome::Over(15), Outcome::Under(16)],
+        ),
+        (carded.clone(), vec![Outcome::Player(markos.clone()), Outcome::Player(nikos.clone())]),
+    ] {
+        let probs = outcomes
+            .iter()
+            .map(|outcome| sourced_model.isolate(&offer_type, outcome))
+            .collect::<Vec<_>>();
+        let offer = Offer {
+            offer_type: offer_type.clone(),
+            outcomes: HashLookup::from(outcomes),
+            market: Market::frame(&OVERROUND, probs, &SINGLE_PRICE_BOUNDS),
+        };
+        offers.insert(offer_type, offer);
+    }
+    let event_fitter = EventFitter::try_from(event_fitter::Config::default()).unwrap();
+    event_fitter.fit(&mut model, &EventStream::Bookings, &offers).unwrap();
+    let bookings = &model.events[&EventStream::Bookings];
+    assert_float_absolute_eq!(0.25, bookings.player_share(&markos).unwrap(), 0.03);
+    assert_float_absolute_eq!(0.15, bookings.player_share(&nikos).unwrap(), 0.03);
+
+    let sourced = &offers[&carded];
+    let carded_prob = |model: &Model, player: &Player| {
+        model.offers()[&carded].get_probability(&Outcome::Player(player.clone())).unwrap()
+    };
+    model
+        .derive(
+            &[Stub {
+                offer_type: carded.clone(),
+                outcomes: sourced.outcomes.clone(),
+                normal: sourced.market.probs.iter().sum(),
+                overround: OVERROUND.clone(),
+            }],
+            &SINGLE_PRICE_BOUNDS,
+        )
+        .unwrap();
+    assert_slice_f64_relative(&sourced.market.probs, &model.offers()[&carded].market.probs, 0.05);
+
+    // players of opposing sides are independent when the sides' bookings are
+    let derivation = model
+        .derive_multi(&[
+            (carded.clone(), Outcome::Player(markos.clone())),
+            (carded.clone(), Outcome::Player(nikos.clone())),
+        ])
+        .unwrap()
+        .value;
+    assert_float_relative_eq!(
+        carded_prob(&model, &markos) * carded_prob(&model, &nikos),
+        derivation.quotation.probability,
+        1e-2
+    );
+
+    // the share of every selected player must be present
+    let other = Player::Named(Side::Home, "Other".into());
+    assert_eq!(
+        "missing Bookings share for Named(Home, \"Other\")",
+        model.derive_multi(&[(carded.clone(), Outcome::Player(other.clone()))]).unwrap_err().to_string()
+    );
+
+    // the shares of one side may not exceed 1
+    offers.get_mut(&carded).unwrap().market.probs[0] = 0.7;
+    let sent_off = OfferType::PlayerEvents(EventStream::Bookings, Over(15));
+    offers.insert(
+        sent_off.clone(),
+        Offer {
+            offer_type: sent_off,
+            outcomes: HashLookup::from([Outcome::Player(other)]),
+            market: Market::frame(&OVERROUND, vec![0.4], &SINGLE_PRICE_BOUNDS),
+        },
+    );
+    assert!(event_fitter
+        .fit(&mut model, &EventStream::Bookings, &offers)
+        .unwrap_err()
+        .to_string()
+        .contains("exceeding 1"));
+}
+
+#[test]
+pub fn fringe_explorations_share_cache() {
+    let cache = ExplorationCache::with_capacity(1024);
+    let mut model = create_test_model().with_exploration_cache(Arc::new(cache));
+    model.goal_probs = Some(create_test_goal_probs());
+    let players = (0..16)
+        .map(|index| Player::Named(Side::Home, format!("Player {index}")))
+        .collect::<Vec<_>>();
+    for player in &players {
+        model.player_probs.insert(player.clone(), PlayerProbs { goal: Some(0.05), assist: None });
+    }
+    model.insert_offer(Offer {
+        offer_type: OfferType::AnytimeGoalscorer,
+        outcomes: HashLookup::from(
+            players.iter().cloned().map(Outcome::Player).collect::<Vec<_>>(),
+        ),
+        market: Market::frame(&OVERROUND, vec![0.1; players.len()], &SINGLE_PRICE_BOUNDS),
+    });
+
+    // the fringe of each pair of players adds every other player, so that each triple of players
+    // is explored thrice, whichever pair was selected
+    let mut multis = 0;
+    for (index, first) in players.iter().enumerate() {
+        for second in &players[index + 1..] {
+            let derivation = model
+                .derive_multi(&[
+                    (OfferType::AnytimeGoalscorer, Outcome::Player(first.clone())),
+                    (OfferType::AnytimeGoalscorer, Outcome::Player(second.clone())),
+                ])
+                .unwrap()
+                .value;
+            assert_eq!(players.len() - 2, derivation.fringes[&OfferType::AnytimeGoalscorer].len());
+            multis += 1;
+        }
+    }
+    let triples = multis * (players.len() - 2) / 3;
+    assert_eq!(multis + triples, model.exploration_cache().stats().misses());
+    assert_eq!(2 * triples, model.exploration_cache().stats().hits());
+}
+
+#[test]
+pub fn derive_multi_batch() {
+    let mut model = create_test_model().with_exploration_cache(Arc::new(ExplorationCache::default()));
+    model.goal_probs = Some(create_test_goal_probs());
+    insert_head_to_head(&mut model, DrawHandicap::Ahead(0), vec![2.0, 3.5, 4.5]);
+    let player = Player::Named(Side::Home, "Markos".into());
+    model.player_probs.insert(player.clone(), PlayerProbs { goal: Some(0.25), assist: None });
+    model.insert_offer(Offer {
+        offer_type: OfferType::AnytimeGoalscorer,
+        outcomes: HashLookup::from([Outcome::Player(player.clone())]),
+        market: Market::frame(&OVERROUND, vec![0.3], &SINGLE_PRICE_BOUNDS),
+    });
+    let head_to_head = OfferType::HeadToHead(Period::FullTime, DrawHandicap::Ahead(0));
+    let selection_sets = vec![
+        vec![(head_to_head.clone(), Outcome::Win(Side::Home, WinHandicap::AheadOver(0)))],
+        vec![(head_to_head.clone(), Outcome::Win(Side::Away, WinHandicap::BehindUnder(0)))],
+        vec![
+            (head_to_head.clone(), Outcome::Win(Side::Home, WinHandicap::AheadOver(0))),
+            (OfferType::AnytimeGoalscorer, Outcome::Player(player.clone())),
+        ],
+        vec![],
+    ];
+
+    let policy = MultiPricingPolicy::default();
+    let batch = model.derive_multi_batch(&selection_sets, &policy).unwrap().value;
+    assert_eq!(selection_sets.len(), batch.len());
+
+    // the two head-to-head singles share an exploration
+    assert_eq!(2, model.exploration_cache().stats().misses());
+    assert_eq!(0, model.exploration_cache().stats().hits());
+
+    for (selections, derivation) in selection_sets.iter().zip(&batch).take(3) {
+        let derivation = derivation.as_ref().unwrap();
+        assert!(derivation.fringes.is_empty());
+        let single = model.derive_multi(selections).unwrap().value;
+        assert_eq!(single.quotation, derivation.quotation);
+        assert_eq!(single.redundancies, derivation.redundancies);
+    }
+    assert_eq!(
+        "no selections specified",
+        batch[3].as_ref().unwrap_err().to_string()
+    );
+}
+
+#[test]
+pub fn derive_multi_batch_independent_of_batch() {
+    let mut model = create_test_model().with_exploration_cache(Arc::new(ExplorationCache::default()));
+    model.goal_probs = Some(create_test_goal_probs());
+    insert_head_to_head(&mut model, DrawHandicap::Ahead(0), vec![2.0, 3.5, 4.5]);
+    let players = (0..interval::NUM_PLAYERS)
+        .map(|index| Player::Named(Side::Home, format!("Player {index}")))
+        .collect::<Vec<_>>();
+    for player in &players {
+        model.player_probs.insert(player.clone(), PlayerProbs { goal: Some(0.02), assist: None });
+    }
+    model.insert_offer(Offer {
+        offer_type: OfferType::AnytimeGoalscorer,
+        outcomes: HashLookup::from(players.iter().cloned().map(Outcome::Player).collect::<Vec<_>>()),
+        market: Market::frame(&OVERROUND, vec![0.03; players.len()], &SINGLE_PRICE_BOUNDS),
+    });
+    let home_win = (
+        OfferType::HeadToHead(Period::FullTime, DrawHandicap::Ahead(0)),
+        Outcome::Win(Side::Home, WinHandicap::AheadOver(0)),
+    );
+    let selection_sets = players
+        .iter()
+        .map(|player| vec![home_win.clone(), (OfferType::AnytimeGoalscorer, Outcome::Player(player.clone()))])
+        .collect::<Vec<_>>();
+
+    // a multi is quoted identically whether batched alone, batched with other players, or derived
+    let policy = MultiPricingPolicy::default();
+    let alone = model.derive_multi_batch(&selection_sets[..1], &policy).unwrap().value;
+    let batch = model.derive_multi_batch(&selection_sets, &policy).unwrap().value;
+    let single = model.derive_multi(&selection_sets[0]).unwrap().value;
+    let alone = alone[0].as_ref().unwrap();
+    assert_eq!(single.quotation, alone.quotation);
+    assert_eq!(single.quotation, batch[0].as_ref().unwrap().quotation);
+    for (selections, derivation) in selection_sets.iter().zip(&batch).skip(1) {
+        let single = model.derive_multi(selections).unwrap().value;
+        assert_eq!(single.quotation, derivation.as_ref().unwrap().quotation);
+    }
+}
+
+#[test]
+pub fn multi_explanation() {
+    let mut model = create_test_model();
+    model.goal_probs = Some(create_test_goal_probs());
+    insert_head_to_head(&mut model, DrawHandicap::Ahead(0), vec![2.0, 3.5, 4.5]);
+    let player = Player::Named(Side::Home, "Markos".into());
+    model.player_probs.insert(player.clone(), PlayerProbs { goal: Some(0.25), assist: None });
+    let overround = Overround {
+        method: OverroundMethod::Multiplicative,
+        value: 1.1,
+    };
+    for (offer_type, prob) in [(OfferType::AnytimeGoalscorer, 0.3), (OfferType::PlayerToScore(2), 0.05)] {
+        model.insert_offer(Offer {
+            offer_type,
+            outcomes: HashLookup::from([Outcome::Player(player.clone())]),
+            market: Market::frame(&overround, vec![prob], &SINGLE_PRICE_BOUNDS),
+        });
+    }
+    let home_win = (
+        OfferType::HeadToHead(Period::FullTime, DrawHandicap::Ahead(0)),
+        Outcome::Win(Side::Home, WinHandicap::AheadOver(0)),
+    );
+    let selections = [
+        home_win.clone(),
+        (OfferType::AnytimeGoalscorer, Outcome::Player(player.clone())),
+        (OfferType::PlayerToScore(2), Outcome::Player(player.clone())),
+    ];
+
+    let derivation = model.derive_multi(&selections).unwrap().value;
+    let explanation = &model.explain_multi(&selections, &MultiPricingPolicy::default()).unwrap().value;
+    println!("{}", Console::default().render(&print::tabulate_multi_explanation(explanation)));
+    assert_eq!(derivation.quotation.probability, explanation.joint_prob);
+    assert_float_relative_eq!(derivation.quotation.overround(), explanation.overround);
+    assert_float_relative_eq!(
+        derivation.relatedness,
+        explanation.independent_prob / explanation.joint_prob
+    );
+
+    // the brace is the least likely leg, rendering the anytime goalscorer redundant
+    assert_eq!(3, explanation.legs.len());
+    assert_eq!(OfferType::PlayerToScore(2), explanation.legs[0].offer_type);
+    assert_eq!(OfferType::AnytimeGoalscorer, explanation.legs[1].offer_type);
+    assert_eq!(
+        vec![true, false, true],
+        explanation.legs.iter().map(|leg| leg.charged).collect::<Vec<_>>()
+    );
+    let charged_overround = explanation
+        .legs
+        .iter()
+        .filter(|leg| leg.charged)
+        .map(|leg| leg.single_overround)
+        .product::<f64>();
+    assert_float_relative_eq!(charged_overround, explanation.composed_overround);
+
+    // a home goalscorer is positively correlated with a home win, and a brace implies a goal
+    assert_eq!(3, explanation.pairs.len());
+    assert!(explanation.pairs.iter().all(|pair| pair.uplift > 1.0));
+    let brace_and_anytime = &explanation.pairs[0];
+    assert_eq!((0, 1), brace_and_anytime.legs);
+    assert_float_relative_eq!(explanation.legs[0].model_prob, brace_and_anytime.joint_prob);
+    assert!(explanation.pruned >= 0.0);
+}
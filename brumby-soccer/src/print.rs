@@ -1,5 +1,6 @@
 use crate::domain::{Offer, OfferType};
-use crate::fit::FittingErrors;
+use crate::fit::{FittingErrors, OfferResidual};
+use crate::model::MultiExplanation;
 use stanza::style::HAlign::Left;
 use stanza::style::{HAlign, Header, MinWidth, Styles};
 use stanza::table::{Col, Row, Table};
@@ -55,6 +56,29 @@ pub fn tabulate_errors(errors: &[(&OfferType, FittingErrors)]) -> Table {
         }))
 }
 
+pub fn tabulate_residuals(residuals: &[OfferResidual]) -> Table {
+    Table::default()
+        .with_cols(vec![
+            Col::new(Styles::default().with(MinWidth(10)).with(Left)),
+            Col::new(Styles::default().with(MinWidth(5)).with(HAlign::Right)),
+            Col::new(Styles::default().with(MinWidth(5)).with(HAlign::Right)),
+        ])
+        .with_row(Row::new(
+            Styles::default().with(Header(true)),
+            vec!["Offer type".into(), "Weight".into(), "Residual".into()],
+        ))
+        .with_rows(residuals.iter().map(|residual| {
+            Row::new(
+                Styles::default(),
+                vec![
+                    format!("{:?}", residual.offer_type).into(),
+                    format!("{:.3}", residual.weight).into(),
+                    format!("{:.6}", residual.residual).into(),
+                ],
+            )
+        }))
+}
+
 pub fn tabulate_overrounds(offers: &[&Offer]) -> Table {
     Table::default()
         .with_cols(vec![
@@ -88,3 +112,97 @@ pub fn tabulate_overrounds(offers: &[&Offer]) -> Table {
             )
         }))
 }
+
+pub fn tabulate_multi_explanation(explanation: &MultiExplanation) -> Table {
+    fn describe_leg(explanation: &MultiExplanation, index: usize) -> String {
+        let leg = &explanation.legs[index];
+        format!("{:?}: {:?}", leg.offer_type, leg.outcome)
+    }
+
+    Table::default()
+        .with_cols(vec![
+            Col::new(Styles::default().with(MinWidth(10)).with(Left)),
+            Col::new(Styles::default().with(MinWidth(5)).with(HAlign::Right)),
+            Col::new(Styles::default().with(MinWidth(5)).with(HAlign::Right)),
+            Col::new(Styles::default().with(MinWidth(5)).with(HAlign::Right)),
+            Col::new(Styles::default().with(MinWidth(5)).with(HAlign::Right)),
+        ])
+        .with_row(Row::new(
+            Styles::default().with(Header(true)),
+            vec![
+                "Component".into(),
+                "Single prob".into(),
+                "Model prob".into(),
+                "Overround".into(),
+                "Uplift".into(),
+            ],
+        ))
+        .with_rows(explanation.legs.iter().enumerate().map(|(index, leg)| {
+            let overround = if leg.charged {
+                format!("{:.3}", leg.single_overround)
+            } else {
+                format!("({:.3})", leg.single_overround)
+            };
+            Row::new(
+                Styles::default(),
+                vec![
+                    describe_leg(explanation, index).into(),
+                    format!("{:.6}", leg.single_prob).into(),
+                    format!("{:.6}", leg.model_prob).into(),
+                    overround.into(),
+                    "".into(),
+                ],
+            )
+        }))
+        .with_rows(explanation.pairs.iter().map(|pair| {
+            Row::new(
+                Styles::default(),
+                vec![
+                    format!(
+                        "{} & {}",
+                        describe_leg(explanation, pair.legs.0),
+                        describe_leg(explanation, pair.legs.1)
+                    )
+                    .into(),
+                    "".into(),
+                    format!("{:.6}", pair.joint_prob).into(),
+                    "".into(),
+                    format!("{:.3}", pair.uplift).into(),
+                ],
+            )
+        }))
+        .with_row(Row::new(
+            Styles::default(),
+            vec![
+                "Independent".into(),
+                format!("{:.6}", explanation.independent_prob).into(),
+                "".into(),
+                format!("{:.3}", explanation.composed_overround).into(),
+                "".into(),
+            ],
+        ))
+        .with_row(Row::new(
+            Styles::default(),
+            vec![
+                "Joint".into(),
+                "".into(),
+                format!("{:.6}", explanation.joint_prob).into(),
+                format!("{:.3}", explanation.overround).into(),
+                format!(
+                    "{:.3}",
+                    explanation.joint_prob / explanation.independent_prob
+                )
+                .into(),
+            ],
+        ))
+        .with_row(Row::new(
+            Styles::default(),
+            vec![
+                "Pruned".into(),
+                "".into(),
+                format!("{:.6}", explanation.pruned).into(),
+                "".into(),
+                "".into(),
+            ],
+        ))
+}
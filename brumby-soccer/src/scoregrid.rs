@@ -1,15 +1,16 @@
-use std::ops::Range;
+use std::ops::{Range, RangeInclusive};
 use ordinalizer::Ordinal;
-use strum_macros::{EnumCount, EnumIter};
+use strum_macros::{EnumCount, EnumIter, EnumString};
 
 use brumby::multinomial::binomial;
 
 use brumby::comb::{count_permutations, pick};
-use crate::domain::{DrawHandicap, Outcome, Period, Score, Side, WinHandicap};
+use crate::domain::{DrawHandicap, MatchResult, Outcome, Period, Score, Side, WinHandicap};
 use crate::interval::{explore, Config, PruneThresholds, BivariateProbs, TeamProbs, UnivariateProbs};
 use brumby::linear::matrix::Matrix;
 use brumby::multinomial::bivariate_binomial;
 use brumby::probs::SliceExt;
+use brumby::factorial::gamma;
 use brumby::{factorial, poisson, sv};
 use crate::domain::OfferType::CorrectScore;
 use crate::interval::query::requirements;
@@ -317,11 +318,174 @@ pub fn subtract(future: &Matrix<f64>, past: &Matrix<f64>) -> Matrix<f64> {
     diff
 }
 
+/// Combines two scoregrids of independent, consecutive periods into a scoregrid of the overall
+/// period. Scores that do not fit into `result` are discarded.
+pub fn convolve(first: &Matrix<f64>, second: &Matrix<f64>, result: &mut Matrix<f64>) {
+    result.fill(0.0);
+    for first_home_goals in 0..usize::min(first.rows(), result.rows()) {
+        for first_away_goals in 0..usize::min(first.cols(), result.cols()) {
+            let first_prob = first[(first_home_goals, first_away_goals)];
+            if first_prob == 0.0 {
+                continue;
+            }
+            for second_home_goals in 0..usize::min(second.rows(), result.rows() - first_home_goals) {
+                for second_away_goals in 0..usize::min(second.cols(), result.cols() - first_away_goals) {
+                    result[(first_home_goals + second_home_goals, first_away_goals + second_away_goals)] +=
+                        first_prob * second[(second_home_goals, second_away_goals)];
+                }
+            }
+        }
+    }
+}
+
 pub fn inflate_zero(additive: f64, scoregrid: &mut Matrix<f64>) {
     scoregrid[(0, 0)] += additive;
     scoregrid.flatten_mut().normalise(1.0);
 }
 
+/// A parametric distribution of full-time scores, as an alternative to the bivariate binomial
+/// that is native to the interval model. Each distribution is parametrised by a home rate, an
+/// away rate and a third parameter that shapes the dependence or dispersion of the scores.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumString)]
+#[strum(serialize_all = "kebab-case")]
+pub enum ScoreDistribution {
+    DixonColes,
+    NegativeBinomial,
+    WeibullCount,
+}
+impl ScoreDistribution {
+    /// The plausible range of the third parameter, which always includes the value at which the
+    /// distribution reduces to univariate Poisson.
+    pub fn shape_bounds(&self) -> RangeInclusive<f64> {
+        match self {
+            ScoreDistribution::DixonColes => -0.2..=0.2,
+            ScoreDistribution::NegativeBinomial => 0.0..=0.4,
+            ScoreDistribution::WeibullCount => 0.75..=1.25,
+        }
+    }
+
+    pub fn populate(&self, home_rate: f64, away_rate: f64, shape: f64, scoregrid: &mut Matrix<f64>) {
+        match self {
+            ScoreDistribution::DixonColes => from_dixon_coles(home_rate, away_rate, shape, scoregrid),
+            ScoreDistribution::NegativeBinomial => {
+                from_negative_binomial(home_rate, away_rate, shape, scoregrid)
+            }
+            ScoreDistribution::WeibullCount => from_weibull_count(home_rate, away_rate, shape, scoregrid),
+        }
+    }
+}
+
+/// Dixon–Coles: univariate Poisson, with the 0-0, 1-0, 0-1 and 1-1 scores adjusted by the
+/// `rho` dependence parameter. A negative `rho` makes the low-scoring draws more likely.
+///
+/// `rho` is clamped to the range admissible for the given rates (see [dixon_coles_rho_bounds]),
+/// outside of which the adjusted scores would take on negative probabilities.
+pub fn from_dixon_coles(home_rate: f64, away_rate: f64, rho: f64, scoregrid: &mut Matrix<f64>) {
+    let (min_rho, max_rho) = dixon_coles_rho_bounds(home_rate, away_rate);
+    let rho = rho.clamp(min_rho, max_rho);
+    from_univariate_poisson(home_rate, away_rate, scoregrid);
+    scoregrid[(0, 0)] *= 1.0 - home_rate * away_rate * rho;
+    if scoregrid.rows() > 1 {
+        scoregrid[(1, 0)] *= 1.0 + away_rate * rho;
+    }
+    if scoregrid.cols() > 1 {
+        scoregrid[(0, 1)] *= 1.0 + home_rate * rho;
+    }
+    if scoregrid.rows() > 1 && scoregrid.cols() > 1 {
+        scoregrid[(1, 1)] *= 1.0 - rho;
+    }
+}
+
+/// The range of `rho` for which the Dixon–Coles adjustments are non-negative:
+/// `max(-1/λ, -1/μ) ≤ ρ ≤ min(1/(λμ), 1)`.
+pub fn dixon_coles_rho_bounds(home_rate: f64, away_rate: f64) -> (f64, f64) {
+    let min_rho = f64::max(-1.0 / home_rate, -1.0 / away_rate);
+    let max_rho = f64::min(1.0 / (home_rate * away_rate), 1.0);
+    (min_rho, max_rho)
+}
+
+/// Independent negative binomial goals, where each side's variance is
+/// `mean * (1 + dispersion * mean)`. A zero dispersion reduces to univariate Poisson.
+pub fn from_negative_binomial(
+    home_mean: f64,
+    away_mean: f64,
+    dispersion: f64,
+    scoregrid: &mut Matrix<f64>,
+) {
+    let mut home_probs = vec![0.0; scoregrid.rows()];
+    negative_binomial(home_mean, dispersion, &mut home_probs);
+    let mut away_probs = vec![0.0; scoregrid.cols()];
+    negative_binomial(away_mean, dispersion, &mut away_probs);
+    from_independent(&home_probs, &away_probs, scoregrid);
+}
+
+fn negative_binomial(mean: f64, dispersion: f64, probs: &mut [f64]) {
+    if dispersion == 0.0 {
+        probs[0] = f64::exp(-mean);
+        for goals in 1..probs.len() {
+            probs[goals] = probs[goals - 1] * mean / goals as f64;
+        }
+    } else {
+        let size = 1.0 / dispersion;
+        probs[0] = (size / (size + mean)).powf(size);
+        for goals in 1..probs.len() {
+            probs[goals] =
+                probs[goals - 1] * (goals as f64 - 1.0 + size) / goals as f64 * mean / (size + mean);
+        }
+    }
+}
+
+/// Independent Weibull-count goals (McShane et al., 2008), in which the times between successive
+/// goals are Weibull-distributed. A `shape` below 1 over-disperses the goals, whereas a `shape`
+/// above 1 under-disperses them. A `shape` of 1 reduces to univariate Poisson, in which case the
+/// rates are also the means.
+pub fn from_weibull_count(home_rate: f64, away_rate: f64, shape: f64, scoregrid: &mut Matrix<f64>) {
+    let mut home_probs = vec![0.0; scoregrid.rows()];
+    weibull_count(home_rate, shape, &mut home_probs);
+    let mut away_probs = vec![0.0; scoregrid.cols()];
+    weibull_count(away_rate, shape, &mut away_probs);
+    from_independent(&home_probs, &away_probs, scoregrid);
+}
+
+/// Number of terms at which the Weibull-count series is truncated.
+const WEIBULL_COUNT_TERMS: usize = 50;
+
+fn weibull_count(rate: f64, shape: f64, probs: &mut [f64]) {
+    const J: usize = WEIBULL_COUNT_TERMS;
+    // gammas[k] = Γ(shape * k + 1); factorials[k] = k!
+    let gammas: [f64; J] = std::array::from_fn(|k| gamma(shape * k as f64 + 1.0));
+    let factorials: [f64; J] = std::array::from_fn(|k| gamma(k as f64 + 1.0));
+
+    // alphas[j] holds α(j, n) for the current n, per the recurrence in McShane et al.
+    let mut alphas: [f64; J] = std::array::from_fn(|j| gammas[j] / factorials[j]);
+    for (goals, prob) in probs.iter_mut().enumerate() {
+        if goals > 0 {
+            let mut next_alphas = [0.0; J];
+            for j in goals..J {
+                next_alphas[j] = (goals - 1..j)
+                    .map(|m| alphas[m] * gammas[j - m] / factorials[j - m])
+                    .sum();
+            }
+            alphas = next_alphas;
+        }
+        let series = (goals..J)
+            .map(|j| {
+                let sign = if (j + goals) % 2 == 0 { 1.0 } else { -1.0 };
+                sign * rate.powi(j as i32) * alphas[j] / gammas[j]
+            })
+            .sum::<f64>();
+        *prob = f64::max(0.0, series);
+    }
+}
+
+fn from_independent(home_probs: &[f64], away_probs: &[f64], scoregrid: &mut Matrix<f64>) {
+    for (home_goals, home_prob) in home_probs.iter().enumerate() {
+        for (away_goals, away_prob) in away_probs.iter().enumerate() {
+            scoregrid[(home_goals, away_goals)] = home_prob * away_prob;
+        }
+    }
+}
+
 impl Outcome {
     pub fn gather(&self, scoregrid: &Matrix<f64>) -> f64 {
         match self {
@@ -329,11 +493,40 @@ impl Outcome {
             Outcome::Draw(draw_handicap) => Self::gather_draw(scoregrid, draw_handicap),
             Outcome::Under(goals) => Self::gather_goals_under(*goals, scoregrid),
             Outcome::Over(goals) => Self::gather_goals_over(*goals, scoregrid),
+            Outcome::Exactly(goals) => Self::gather_goals_exactly(*goals, scoregrid),
+            Outcome::Odd => Self::gather_goals_parity(1, scoregrid),
+            Outcome::Even => Self::gather_goals_parity(0, scoregrid),
             Outcome::Score(score) => Self::gather_correct_score(score, scoregrid),
+            Outcome::WinBy(side, margin) => Self::gather_win_by(side, *margin, scoregrid),
+            Outcome::DoubleChance(first, second) => Self::gather_double_chance(first, second, scoregrid),
             _ => unimplemented!(),
         }
     }
 
+    /// Gathers the probability of this outcome with respect to the goals scored by one side only,
+    /// as used by team goals offers.
+    pub fn gather_team(&self, side: &Side, scoregrid: &Matrix<f64>) -> f64 {
+        let matches = |goals: u8| match self {
+            Outcome::Under(limit) => goals < *limit,
+            Outcome::Over(limit) => goals > *limit,
+            Outcome::Exactly(count) => goals == *count,
+            _ => unimplemented!(),
+        };
+        let mut prob = 0.0;
+        for home_goals in 0..scoregrid.rows() {
+            for away_goals in 0..scoregrid.cols() {
+                let goals = match side {
+                    Side::Home => home_goals,
+                    Side::Away => away_goals,
+                };
+                if matches(goals as u8) {
+                    prob += scoregrid[(home_goals, away_goals)];
+                }
+            }
+        }
+        prob
+    }
+
     fn gather_win(side: &Side, scoregrid: &Matrix<f64>, win_handicap: &WinHandicap) -> f64 {
         let mut prob = 0.0;
         for home_goals in 0..scoregrid.rows() {
@@ -374,6 +567,35 @@ impl Outcome {
         prob
     }
 
+    fn gather_win_by(side: &Side, margin: u8, scoregrid: &Matrix<f64>) -> f64 {
+        let mut prob = 0.0;
+        for home_goals in 0..scoregrid.rows() {
+            for away_goals in 0..scoregrid.cols() {
+                let (winner_goals, loser_goals) = match side {
+                    Side::Home => (home_goals, away_goals),
+                    Side::Away => (away_goals, home_goals),
+                };
+                if winner_goals > loser_goals && winner_goals - loser_goals == margin as usize {
+                    prob += scoregrid[(home_goals, away_goals)];
+                }
+            }
+        }
+        prob
+    }
+
+    fn gather_double_chance(first: &MatchResult, second: &MatchResult, scoregrid: &Matrix<f64>) -> f64 {
+        let mut prob = 0.0;
+        for home_goals in 0..scoregrid.rows() {
+            for away_goals in 0..scoregrid.cols() {
+                let result = MatchResult::from_score(&Score::new(home_goals as u8, away_goals as u8));
+                if result == *first || result == *second {
+                    prob += scoregrid[(home_goals, away_goals)];
+                }
+            }
+        }
+        prob
+    }
+
     fn gather_goals_over(goals: u8, scoregrid: &Matrix<f64>) -> f64 {
         let goals = goals as usize;
         let mut prob = 0.0;
@@ -400,6 +622,31 @@ impl Outcome {
         prob
     }
 
+    fn gather_goals_exactly(goals: u8, scoregrid: &Matrix<f64>) -> f64 {
+        let goals = goals as usize;
+        let mut prob = 0.0;
+        for row in 0..scoregrid.rows() {
+            for col in 0..scoregrid.cols() {
+                if row + col == goals {
+                    prob += scoregrid[(row, col)];
+                }
+            }
+        }
+        prob
+    }
+
+    fn gather_goals_parity(remainder: usize, scoregrid: &Matrix<f64>) -> f64 {
+        let mut prob = 0.0;
+        for row in 0..scoregrid.rows() {
+            for col in 0..scoregrid.cols() {
+                if (row + col) % 2 == remainder {
+                    prob += scoregrid[(row, col)];
+                }
+            }
+        }
+        prob
+    }
+
     fn gather_correct_score(score: &Score, scoregrid: &Matrix<f64>) -> f64 {
         if (score.home as usize) < scoregrid.rows() && (score.away as usize) < scoregrid.cols() {
             scoregrid[(score.home as usize, score.away as usize)]
@@ -1,5 +1,5 @@
 use super::*;
-use crate::domain::{DrawHandicap, Side, WinHandicap};
+use crate::domain::{DrawHandicap, MatchResult, Side, WinHandicap};
 use brumby::opt::{hypergrid_search, HypergridSearchConfig, RangeCapture};
 use brumby::probs::SliceExt;
 use assert_float_eq::*;
@@ -89,6 +89,46 @@ pub fn outcome_goals_ou_gather() {
     assert_float_absolute_eq!(0.65, Outcome::Over(2).gather(&scoregrid));
 }
 
+#[test]
+pub fn outcome_exact_goals_gather() {
+    let scoregrid = create_test_4x4_scoregrid();
+    assert_float_absolute_eq!(0.04, Outcome::Exactly(0).gather(&scoregrid));
+    assert_float_absolute_eq!(0.2, Outcome::Exactly(2).gather(&scoregrid));
+    assert_float_absolute_eq!(0.3, Outcome::Exactly(3).gather(&scoregrid));
+}
+
+#[test]
+pub fn outcome_odd_even_gather() {
+    let scoregrid = create_test_4x4_scoregrid();
+    assert_float_absolute_eq!(0.52, Outcome::Odd.gather(&scoregrid));
+    assert_float_absolute_eq!(0.48, Outcome::Even.gather(&scoregrid));
+}
+
+#[test]
+pub fn outcome_team_goals_gather() {
+    let scoregrid = create_test_4x4_scoregrid();
+    assert_float_absolute_eq!(0.3, Outcome::Under(2).gather_team(&Side::Home, &scoregrid));
+    assert_float_absolute_eq!(0.7, Outcome::Over(1).gather_team(&Side::Home, &scoregrid));
+    assert_float_absolute_eq!(0.3, Outcome::Exactly(1).gather_team(&Side::Away, &scoregrid));
+    assert_float_absolute_eq!(0.1, Outcome::Over(2).gather_team(&Side::Away, &scoregrid));
+}
+
+#[test]
+pub fn outcome_win_by_gather() {
+    let scoregrid = create_test_4x4_scoregrid();
+    assert_float_absolute_eq!(0.25, Outcome::WinBy(Side::Home, 1).gather(&scoregrid));
+    assert_float_absolute_eq!(0.24, Outcome::WinBy(Side::Home, 2).gather(&scoregrid));
+    assert_float_absolute_eq!(0.04, Outcome::WinBy(Side::Away, 2).gather(&scoregrid));
+}
+
+#[test]
+pub fn outcome_double_chance_gather() {
+    let scoregrid = create_test_4x4_scoregrid();
+    assert_float_absolute_eq!(0.85, Outcome::DoubleChance(MatchResult::Win(Side::Home), MatchResult::Draw).gather(&scoregrid));
+    assert_float_absolute_eq!(0.8, Outcome::DoubleChance(MatchResult::Win(Side::Home), MatchResult::Win(Side::Away)).gather(&scoregrid));
+    assert_float_absolute_eq!(0.35, Outcome::DoubleChance(MatchResult::Draw, MatchResult::Win(Side::Away)).gather(&scoregrid));
+}
+
 #[test]
 pub fn outcome_correct_score_gather() {
     let scoregrid = create_test_4x4_scoregrid();
@@ -296,6 +336,147 @@ pub fn bivariate_binomial_interval_equivalence() {
     assert!(mse < 1e-9, "mse: {mse}");
 }
 
+#[test]
+pub fn convolve_halves_interval_equivalence() {
+    const INTERVALS: usize = 6;
+    let h1_probs = BivariateProbs { home: 0.1, away: 0.08, common: 0.02 };
+    let h2_probs = BivariateProbs { home: 0.14, away: 0.1, common: 0.03 };
+    let mut h1 = Matrix::allocate(INTERVALS / 2 + 1, INTERVALS / 2 + 1);
+    from_bivariate_binomial((INTERVALS / 2) as u8, h1_probs.home, h1_probs.away, h1_probs.common, &mut h1);
+    let mut h2 = Matrix::allocate(INTERVALS / 2 + 1, INTERVALS / 2 + 1);
+    from_bivariate_binomial((INTERVALS / 2) as u8, h2_probs.home, h2_probs.away, h2_probs.common, &mut h2);
+    let mut convolved = Matrix::allocate(INTERVALS + 1, INTERVALS + 1);
+    convolve(&h1, &h2, &mut convolved);
+    println!(
+        "convolved:\n{}sum: {}",
+        convolved.verbose(),
+        convolved.flatten().sum()
+    );
+
+    let mut interval = Matrix::allocate(INTERVALS + 1, INTERVALS + 1);
+    from_interval(INTERVALS as u8, 0..INTERVALS as u8, u16::MAX, h1_probs, h2_probs, &mut interval);
+    println!(
+        "interval:\n{}sum: {}",
+        interval.verbose(),
+        interval.flatten().sum()
+    );
+
+    let mse = compute_mse(convolved.flatten(), interval.flatten());
+    assert!(mse < 1e-9, "mse: {mse}");
+
+    // truncating the result discards the scores that don't fit
+    let mut truncated = Matrix::allocate(3, 3);
+    convolve(&h1, &h2, &mut truncated);
+    assert_float_absolute_eq!(convolved[(1, 2)], truncated[(1, 2)]);
+    assert!(truncated.flatten().sum() < 1.0);
+}
+
+#[test]
+pub fn alternative_distributions_reduce_to_univariate_poisson() {
+    const HOME_RATE: f64 = 1.6;
+    const AWAY_RATE: f64 = 1.1;
+    let mut poisson = Matrix::allocate(9, 9);
+    from_univariate_poisson(HOME_RATE, AWAY_RATE, &mut poisson);
+
+    for (distribution, shape) in [
+        (ScoreDistribution::DixonColes, 0.0),
+        (ScoreDistribution::NegativeBinomial, 0.0),
+        (ScoreDistribution::WeibullCount, 1.0),
+    ] {
+        assert!(distribution.shape_bounds().contains(&shape));
+        let mut scoregrid = Matrix::allocate(9, 9);
+        distribution.populate(HOME_RATE, AWAY_RATE, shape, &mut scoregrid);
+        let mse = compute_mse(poisson.flatten(), scoregrid.flatten());
+        assert!(mse < 1e-12, "{distribution:?} mse: {mse}");
+    }
+}
+
+#[test]
+pub fn dixon_coles_low_scores() {
+    const RHO: f64 = -0.1;
+    let mut poisson = Matrix::allocate(9, 9);
+    from_univariate_poisson(1.6, 1.1, &mut poisson);
+    let mut dixon_coles = Matrix::allocate(9, 9);
+    from_dixon_coles(1.6, 1.1, RHO, &mut dixon_coles);
+    println!("dixon_coles:\n{}sum: {}", dixon_coles.verbose(), dixon_coles.flatten().sum());
+
+    // the adjustment redistributes probability among the low scores, preserving the total
+    assert_float_absolute_eq!(poisson.flatten().sum(), dixon_coles.flatten().sum());
+    assert!(dixon_coles[(0, 0)] > poisson[(0, 0)]);
+    assert!(dixon_coles[(1, 1)] > poisson[(1, 1)]);
+    assert!(dixon_coles[(1, 0)] < poisson[(1, 0)]);
+    assert!(dixon_coles[(0, 1)] < poisson[(0, 1)]);
+    assert_float_absolute_eq!(poisson[(2, 1)], dixon_coles[(2, 1)]);
+}
+
+#[test]
+pub fn dixon_coles_high_rates() {
+    for (home_rate, away_rate, rho) in [(3.0, 2.5, 0.2), (6.0, 0.5, -0.2), (0.5, 6.0, -0.2)] {
+        let (min_rho, max_rho) = dixon_coles_rho_bounds(home_rate, away_rate);
+        assert!(rho < min_rho || rho > max_rho, "{rho} in [{min_rho}, {max_rho}]");
+        let mut poisson = Matrix::allocate(15, 15);
+        from_univariate_poisson(home_rate, away_rate, &mut poisson);
+        let mut dixon_coles = Matrix::allocate(15, 15);
+        from_dixon_coles(home_rate, away_rate, rho, &mut dixon_coles);
+
+        // rho is clamped to its bound, zeroing one of the adjusted scores rather than negating it
+        assert!(dixon_coles.flatten().iter().all(|&prob| prob >= 0.0));
+        let low_scores = [(0, 0), (1, 0), (0, 1), (1, 1)];
+        assert!(low_scores.iter().any(|&cell| dixon_coles[cell] == 0.0));
+        assert_float_absolute_eq!(poisson.flatten().sum(), dixon_coles.flatten().sum());
+    }
+
+    // within the bounds, rho is unchanged
+    assert_eq!((-0.5, 0.25), dixon_coles_rho_bounds(2.0, 2.0));
+    let mut clamped = Matrix::allocate(9, 9);
+    from_dixon_coles(2.0, 2.0, 0.2, &mut clamped);
+    let mut expected = Matrix::allocate(9, 9);
+    from_univariate_poisson(2.0, 2.0, &mut expected);
+    expected[(0, 0)] *= 1.0 - 2.0 * 2.0 * 0.2;
+    assert_eq!(expected[(0, 0)], clamped[(0, 0)]);
+}
+
+#[test]
+pub fn negative_binomial_moments() {
+    const HOME_MEAN: f64 = 1.6;
+    const AWAY_MEAN: f64 = 1.1;
+    const DISPERSION: f64 = 0.2;
+    let mut scoregrid = Matrix::allocate(31, 31);
+    from_negative_binomial(HOME_MEAN, AWAY_MEAN, DISPERSION, &mut scoregrid);
+    assert_float_absolute_eq!(1.0, scoregrid.flatten().sum(), 1e-9);
+    let (home_expectation, away_expectation) = home_away_expectations(&scoregrid);
+    assert_float_absolute_eq!(HOME_MEAN, home_expectation, 1e-9);
+    assert_float_absolute_eq!(AWAY_MEAN, away_expectation, 1e-9);
+    let (home_variance, _) = home_away_variances(&scoregrid);
+    assert_float_absolute_eq!(HOME_MEAN * (1.0 + DISPERSION * HOME_MEAN), home_variance, 1e-9);
+}
+
+#[test]
+pub fn weibull_count_dispersion() {
+    for (shape, over_dispersed) in [(0.8, true), (1.2, false)] {
+        let mut scoregrid = Matrix::allocate(16, 16);
+        from_weibull_count(1.5, 1.0, shape, &mut scoregrid);
+        println!("shape: {shape}, scoregrid:\n{}sum: {}", scoregrid.verbose(), scoregrid.flatten().sum());
+        assert_float_absolute_eq!(1.0, scoregrid.flatten().sum(), 1e-6);
+        let (home_expectation, _) = home_away_expectations(&scoregrid);
+        let (home_variance, _) = home_away_variances(&scoregrid);
+        assert_eq!(over_dispersed, home_variance > home_expectation, "shape: {shape}");
+    }
+}
+
+fn home_away_variances(scoregrid: &Matrix<f64>) -> (f64, f64) {
+    let (home_expectation, away_expectation) = home_away_expectations(scoregrid);
+    let (mut home_variance, mut away_variance) = (0.0, 0.0);
+    for home_goals in 0..scoregrid.rows() {
+        for away_goals in 0..scoregrid.cols() {
+            let prob = scoregrid[(home_goals, away_goals)];
+            home_variance += (home_goals as f64 - home_expectation).powi(2) * prob;
+            away_variance += (away_goals as f64 - away_expectation).powi(2) * prob;
+        }
+    }
+    (home_variance, away_variance)
+}
+
 fn compute_mse(sample_probs: &[f64], fitted_probs: &[f64]) -> f64 {
     let mut sq_error = 0.0;
     for (index, sample_prob) in sample_probs.iter().enumerate() {
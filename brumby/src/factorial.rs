@@ -41,6 +41,35 @@ impl Default for Lookup {
     }
 }
 
+const LANCZOS_G: f64 = 7.0;
+const LANCZOS_COEFFICIENTS: [f64; 9] = [
+    0.999_999_999_999_809_9,
+    676.520_368_121_885_1,
+    -1_259.139_216_722_402_8,
+    771.323_428_777_653_1,
+    -176.615_029_162_140_6,
+    12.507_343_278_686_905,
+    -0.138_571_095_265_720_12,
+    9.984_369_578_019_572e-6,
+    1.505_632_735_149_311_6e-7,
+];
+
+/// The gamma function, extending the factorial to real arguments, such that `gamma(n + 1) = n!`.
+/// Uses the Lanczos approximation, with the reflection formula for arguments below 0.5.
+pub fn gamma(x: f64) -> f64 {
+    if x < 0.5 {
+        std::f64::consts::PI / (f64::sin(std::f64::consts::PI * x) * gamma(1.0 - x))
+    } else {
+        let x = x - 1.0;
+        let mut sum = LANCZOS_COEFFICIENTS[0];
+        for (i, &coefficient) in LANCZOS_COEFFICIENTS.iter().enumerate().skip(1) {
+            sum += coefficient / (x + i as f64);
+        }
+        let t = x + LANCZOS_G + 0.5;
+        f64::sqrt(2.0 * std::f64::consts::PI) * t.powf(x + 0.5) * f64::exp(-t) * sum
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -55,6 +84,24 @@ mod tests {
         test_impl(Lookup::default());
     }
 
+    #[test]
+    pub fn gamma_of_integers() {
+        let f = Lookup::default();
+        for n in 0..=20 {
+            let expected = f.get(n) as f64;
+            let actual = gamma(n as f64 + 1.0);
+            assert!(((expected - actual) / expected).abs() < 1e-12, "n: {n}, actual: {actual}");
+        }
+    }
+
+    #[test]
+    pub fn gamma_of_fractions() {
+        let sqrt_pi = f64::sqrt(std::f64::consts::PI);
+        assert!((sqrt_pi - gamma(0.5)).abs() < 1e-12);
+        assert!((sqrt_pi / 2.0 - gamma(1.5)).abs() < 1e-12);
+        assert!((-2.0 * sqrt_pi - gamma(-0.5)).abs() < 1e-12);
+    }
+
     fn test_impl(f: impl Factorial) {
         assert_eq!(1, f.get(0));
         assert_eq!(1, f.get(1));
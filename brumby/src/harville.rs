@@ -127,6 +127,58 @@ pub fn harville_summary_condensed_no_alloc(
     }
 }
 
+/// Computes the top-_N_ probabilities of the runners, conditional on the runners fixed at some of
+/// the ranks. `fixed` holds an entry for each rank, with `None` for the ranks that are open. Row
+/// `n` of the result holds the probability of each runner finishing in the top `n + 1` places.
+/// The result is zeroed if the fixed runners cannot occupy their ranks.
+pub fn harville_top_n_conditional(
+    probs: &Matrix<f64>,
+    ranks: usize,
+    fixed: &[Option<usize>],
+) -> Matrix<f64> {
+    debug_assert_eq!(
+        probs.rows(),
+        ranks,
+        "number of rows in the probabilities matrix must equal to the number of ranks"
+    );
+    assert_eq!(ranks, fixed.len(), "a fixed entry must exist for each rank");
+    let runners = probs.cols();
+    let cardinalities = fixed
+        .iter()
+        .map(|fixed_runner| if fixed_runner.is_some() { 1 } else { runners })
+        .collect::<Vec<_>>();
+    let mut podium = vec![0; ranks];
+    let mut bitmap = vec![false; runners];
+    let mut summary = Matrix::allocate(ranks, runners);
+    let mut total_prob = 0.0;
+    let permutations = count_permutations(&cardinalities);
+    for permutation in 0..permutations {
+        pick(&cardinalities, permutation, &mut podium);
+        for (rank, fixed_runner) in fixed.iter().enumerate() {
+            if let Some(fixed_runner) = fixed_runner {
+                podium[rank] = *fixed_runner;
+            }
+        }
+        if !is_unique_linear(&podium, &mut bitmap) {
+            continue;
+        }
+        let prob = harville(probs, &podium);
+        total_prob += prob;
+        for (rank, &runner) in podium.iter().enumerate() {
+            for top_n in rank..ranks {
+                summary[(top_n, runner)] += prob;
+            }
+        }
+    }
+
+    if total_prob > 0.0 {
+        for prob in summary.flatten_mut() {
+            *prob /= total_prob;
+        }
+    }
+    summary
+}
+
 #[cfg(test)]
 mod tests {
     use assert_float_eq::assert_float_relative_eq;
@@ -136,6 +188,7 @@ mod tests {
     use crate::capture::Capture;
     use crate::comb::{is_unique_quadratic, Permuter};
     use crate::dilative::DilatedProbs;
+    use crate::mc::MonteCarloEngine;
     use crate::probs::SliceExt;
 
     use super::*;
@@ -540,4 +593,64 @@ mod tests {
             assert_float_relative_eq!(1.0, col_cells.sum::<f64>());
         }
     }
+
+    #[test]
+    fn harville_top_n_conditional_3x3_winner_fixed() {
+        const WIN_PROBS: [f64; 3] = [0.6, 0.3, 0.1];
+        const RANKS: usize = 3;
+        let probs = Matrix::from(
+            DilatedProbs::default()
+                .with_win_probs(Capture::Borrowed(&WIN_PROBS))
+                .with_podium_places(RANKS),
+        );
+        let summary = harville_top_n_conditional(&probs, RANKS, &[Some(0), None, None]);
+        println!("summary:\n{}", summary.verbose());
+        assert_slice_f64_relative(
+            &[
+                1.0, 0.0, 0.0,
+                1.0, 0.75, 0.25,
+                1.0, 1.0, 1.0,
+            ],
+            summary.flatten(),
+            1e-9,
+        );
+    }
+
+    #[test]
+    fn harville_top_n_conditional_4x4_runner_fixed_second() {
+        const WIN_PROBS: [f64; 4] = [0.4, 0.3, 0.2, 0.1];
+        const RANKS: usize = 4;
+        let probs = Matrix::from(
+            DilatedProbs::default()
+                .with_win_probs(Capture::Borrowed(&WIN_PROBS))
+                .with_podium_places(RANKS),
+        );
+        let fixed = [None, Some(2), None, None];
+        let summary = harville_top_n_conditional(&probs, RANKS, &fixed);
+        println!("summary:\n{}", summary.verbose());
+
+        // the winner is conditional on runner 2 having come second
+        let joint = [0, 1, 3].map(|winner| {
+            let rank_probs = &probs[1];
+            WIN_PROBS[winner] * rank_probs[2] / (1.0 - rank_probs[winner])
+        });
+        let total = joint.iter().sum::<f64>();
+        assert_slice_f64_relative(
+            &[joint[0] / total, joint[1] / total, 0.0, joint[2] / total],
+            &summary[0],
+            1e-9,
+        );
+        assert_float_relative_eq!(1.0, summary[(1, 2)]);
+        for row in 0..RANKS {
+            assert_float_relative_eq!((row + 1) as f64, summary[row].sum());
+        }
+
+        // the Monte Carlo estimates converge on the same probabilities
+        let mut engine = MonteCarloEngine::default()
+            .with_trials(200_000)
+            .with_probs(Capture::Borrowed(&probs));
+        let simulated = engine.simulate_top_n_conditional(&fixed);
+        println!("simulated:\n{}", simulated.verbose());
+        assert_slice_f64_relative(summary.flatten(), simulated.flatten(), 0.02);
+    }
 }
@@ -6,7 +6,7 @@ use std::ops::Range;
 use std::string::ToString;
 
 use anyhow::bail;
-use linregress::fit_low_level_regression_model;
+use linregress::{fit_low_level_regression_model, LowLevelRegressionModel};
 use serde::{Deserialize, Serialize};
 use stanza::style::{HAlign, Header, MinWidth, Styles};
 use stanza::table::{Col, Row, Table};
@@ -15,6 +15,9 @@ use strum_macros::{Display, EnumCount, EnumIter};
 
 use crate::linear::matrix::Matrix;
 
+pub mod cross_validation;
+pub mod selection;
+
 pub trait AsIndex {
     fn as_index(&self) -> usize;
 }
@@ -24,6 +27,9 @@ pub enum Regressor<O: AsIndex> {
     Variable(O),
     Exp(Box<Regressor<O>>, i32),
     Product(Vec<Regressor<O>>),
+    /// One-hot encoding of a categorical variable: 1 where the variable takes the given level,
+    /// 0 otherwise.
+    Indicator(O, i32),
     Intercept,
     Origin,
 }
@@ -36,6 +42,13 @@ impl<O: AsIndex> Regressor<O> {
                 .iter()
                 .map(|regressor| regressor.resolve(input))
                 .product(),
+            Regressor::Indicator(ordinal, level) => {
+                if input[ordinal.as_index()] == *level as f64 {
+                    1.
+                } else {
+                    0.
+                }
+            }
             Regressor::Intercept => 1.,
             Regressor::Origin => 0.,
         }
@@ -44,6 +57,21 @@ impl<O: AsIndex> Regressor<O> {
     pub fn is_constant(&self) -> bool {
         matches!(self, Regressor::Intercept | Regressor::Origin)
     }
+
+    /// Whether the given variable appears anywhere in this regressor.
+    pub fn references(&self, variable: &O) -> bool
+    where
+        O: PartialEq,
+    {
+        match self {
+            Regressor::Variable(ordinal) | Regressor::Indicator(ordinal, _) => ordinal == variable,
+            Regressor::Exp(regressor, _) => regressor.references(variable),
+            Regressor::Product(regressors) => regressors
+                .iter()
+                .any(|regressor| regressor.references(variable)),
+            Regressor::Intercept | Regressor::Origin => false,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -89,16 +117,29 @@ impl<O: AsIndex> Predictor<O> {
     }
 
     pub fn r_squared(&self, response: &O, data: &Matrix<f64>) -> RSquared {
+        self.weighted_r_squared(response, data, &vec![1.; data.rows()])
+    }
+
+    /// The r-squared over the weighted samples, scaling the squared residual and the squared
+    /// deviation of each sample by its weight. Samples with a zero weight are excluded from the
+    /// degrees of freedom.
+    pub fn weighted_r_squared(
+        &self,
+        response: &O,
+        data: &Matrix<f64>,
+        weights: &[f64],
+    ) -> RSquared {
         let response_index = response.as_index();
         let (mut sum_sq_residual, mut sum_sq_total) = (0., 0.);
-        let mut sum = 0.;
-        for row in data {
+        let (mut sum, mut sum_weights) = (0., 0.);
+        for (row, &weight) in data.into_iter().zip(weights) {
             let response = row[response_index];
             let predicted = self.predict(row);
-            sum_sq_residual += (response - predicted).powi(2);
-            sum += response;
+            sum_sq_residual += weight * (response - predicted).powi(2);
+            sum += weight * response;
+            sum_weights += weight;
         }
-        let samples = data.rows();
+        let samples = weights.iter().filter(|&&weight| weight > 0.).count();
 
         let has_zero_intercept = self
             .regressors
@@ -110,19 +151,19 @@ impl<O: AsIndex> Predictor<O> {
         if has_zero_intercept {
             // emulates the behaviour of R for deriving both unadjusted and adjusted r-squared
             // when the intercept is suppressed
-            for row in data {
+            for (row, &weight) in data.into_iter().zip(weights) {
                 let response = row[response_index];
-                sum_sq_total += response.powi(2);
+                sum_sq_total += weight * response.powi(2);
             }
             df_residual = samples - self.regressors.len() + 1;
             df_total = samples;
         } else {
             // standard derivation of unadjusted and adjusted r-squared when an intercept term
             // is present
-            let mean = sum / samples as f64;
-            for row in data {
+            let mean = sum / sum_weights;
+            for (row, &weight) in data.into_iter().zip(weights) {
                 let response = row[response_index];
-                sum_sq_total += (response - mean).powi(2);
+                sum_sq_total += weight * (response - mean).powi(2);
             }
             df_residual = samples - self.regressors.len();
             df_total = samples - 1;
@@ -137,6 +178,241 @@ impl<O: AsIndex> Predictor<O> {
     }
 }
 
+fn predict<O: AsIndex>(regressors: &[Regressor<O>], coefficients: &[f64], input: &[f64]) -> f64 {
+    regressors
+        .iter()
+        .zip(coefficients)
+        .map(|(regressor, coefficient)| coefficient * regressor.resolve(input))
+        .sum()
+}
+
+/// Fits by least squares, scaling each sample by the square root of its weight and penalising the
+/// non-constant coefficients by augmenting the data with a pseudo-sample for each.
+fn fit_weighted<O: AsIndex>(
+    response: &O,
+    regressors: &[Regressor<O>],
+    data: &Matrix<f64>,
+    weights: &[f64],
+    ridge: f64,
+) -> Result<LowLevelRegressionModel, anyhow::Error> {
+    let weighted_rows = weights.iter().filter(|&&weight| weight > 0.).count();
+    let penalty_rows = if ridge > 0. {
+        regressors
+            .iter()
+            .filter(|regressor| !regressor.is_constant())
+            .count()
+    } else {
+        0
+    };
+    let mut subset: Matrix<f64> =
+        Matrix::allocate(weighted_rows + penalty_rows, 1 + regressors.len());
+    let mut subset_row = 0;
+    for (row_data, &weight) in data.into_iter().zip(weights) {
+        if weight > 0. {
+            let scale = weight.sqrt();
+            subset[(subset_row, 0)] = scale * row_data[response.as_index()];
+            for (regressor_index, regressor) in regressors.iter().enumerate() {
+                subset[(subset_row, 1 + regressor_index)] = scale * regressor.resolve(row_data);
+            }
+            subset_row += 1;
+        }
+    }
+    if penalty_rows > 0 {
+        let penalty = ridge.sqrt();
+        for (regressor_index, regressor) in regressors.iter().enumerate() {
+            if !regressor.is_constant() {
+                subset[(subset_row, 1 + regressor_index)] = penalty;
+                subset_row += 1;
+            }
+        }
+    }
+
+    Ok(fit_low_level_regression_model(
+        subset.flatten(),
+        subset.rows(),
+        subset.cols(),
+    )?)
+}
+
+/// The median absolute deviation of the residuals of the weighted samples, rescaled to
+/// estimate the standard deviation of normally distributed residuals.
+fn robust_scale(residuals: &[f64], weights: &[f64]) -> f64 {
+    fn median(values: &mut [f64]) -> f64 {
+        values.sort_by(f64::total_cmp);
+        let mid = values.len() / 2;
+        if values.len().is_multiple_of(2) {
+            (values[mid - 1] + values[mid]) / 2.
+        } else {
+            values[mid]
+        }
+    }
+
+    let mut residuals = residuals
+        .iter()
+        .zip(weights)
+        .filter(|(_, &weight)| weight > 0.)
+        .map(|(&residual, _)| residual)
+        .collect::<Vec<_>>();
+    if residuals.is_empty() {
+        return 0.;
+    }
+    let centre = median(&mut residuals);
+    let mut deviations = residuals
+        .iter()
+        .map(|residual| (residual - centre).abs())
+        .collect::<Vec<_>>();
+    median(&mut deviations) / 0.6745
+}
+
+/// Refits the model under the Huber loss, reweighting the samples by the residuals of the previous
+/// fit until the coefficients converge. Returns the refitted model and the weights it was fitted
+/// with.
+fn reweight_huber<M>(
+    huber: &Huber,
+    sample_weights: &[f64],
+    mut model: M,
+    coefficients: impl Fn(&M) -> &[f64],
+    residuals: impl Fn(&M) -> Vec<f64>,
+    refit: impl Fn(&[f64]) -> Result<M, anyhow::Error>,
+) -> Result<(M, Vec<f64>), anyhow::Error> {
+    let mut weights = sample_weights.to_vec();
+    for _ in 0..huber.max_iterations {
+        let residuals = residuals(&model);
+        let scale = robust_scale(&residuals, sample_weights);
+        if scale == 0. {
+            break;
+        }
+        for (index, residual) in residuals.iter().enumerate() {
+            let huber_weight = f64::min(1., huber.tuning * scale / residual.abs());
+            weights[index] = sample_weights[index] * huber_weight;
+        }
+        let refitted = refit(&weights)?;
+        let converged = coefficients(&model)
+            .iter()
+            .zip(coefficients(&refitted))
+            .all(|(&previous, &current)| {
+                (current - previous).abs() <= huber.tolerance * f64::max(1., previous.abs())
+            });
+        model = refitted;
+        if converged {
+            break;
+        }
+    }
+    Ok((model, weights))
+}
+
+/// The weight of each sample, taken from the weights column of the options, if one is specified.
+fn sample_weights<O: AsIndex>(
+    data: &Matrix<f64>,
+    options: &RegressionOptions<O>,
+) -> Result<Vec<f64>, anyhow::Error> {
+    match &options.weights {
+        None => Ok(vec![1.; data.rows()]),
+        Some(weights) => {
+            if weights.as_index() >= data.cols() {
+                bail!("no such weights column {}", weights.as_index());
+            }
+            let sample_weights = data.col(weights.as_index()).copied().collect::<Vec<_>>();
+            if sample_weights
+                .iter()
+                .any(|weight| !weight.is_finite() || *weight < 0.)
+            {
+                bail!("sample weights must be non-negative numbers");
+            }
+            Ok(sample_weights)
+        }
+    }
+}
+
+/// Fits a model comprising only the `constant` regressor, returning its predictor and r-squared.
+/// Unlike [RegressionModel::fit_with_options], which requires at least one non-constant regressor,
+/// the fit is closed-form: an intercept takes the weighted (and, under a robust loss, reweighted)
+/// mean of the response, whereas the origin has nothing to fit. The r-squared is taken over the
+/// weighted data, as for [RegressionModel::fit_with_options].
+pub fn fit_constant<O: AsIndex + Clone>(
+    response: &O,
+    constant: &Regressor<O>,
+    data: &Matrix<f64>,
+    options: &RegressionOptions<O>,
+) -> Result<(Predictor<O>, RSquared), anyhow::Error> {
+    if !constant.is_constant() {
+        bail!("{constant} is not a constant regressor");
+    }
+    options.validate()?;
+    let sample_weights = sample_weights(data, options)?;
+
+    let response_index = response.as_index();
+    let fit = |weights: &[f64]| match constant {
+        Regressor::Intercept => {
+            let (sum, sum_weights) = data
+                .into_iter()
+                .zip(weights)
+                .fold((0., 0.), |(sum, sum_weights), (row, &weight)| {
+                    (sum + weight * row[response_index], sum_weights + weight)
+                });
+            if sum_weights == 0. {
+                bail!("sample weights cannot all be zero");
+            }
+            Ok(vec![sum / sum_weights])
+        }
+        _ => Ok(vec![0.]),
+    };
+    let mut coefficients = fit(&sample_weights)?;
+    let mut weights = sample_weights.clone();
+    if let Some(huber) = &options.robust {
+        let regressors = [constant.clone()];
+        (coefficients, weights) = reweight_huber(
+            huber,
+            &sample_weights,
+            coefficients,
+            Vec::as_slice,
+            |coefficients| residuals(response, &regressors, coefficients, data),
+            fit,
+        )?;
+    }
+
+    let predictor = Predictor {
+        regressors: vec![constant.clone()],
+        coefficients,
+    };
+    let r_squared = predictor.weighted_r_squared(response, data, &weights);
+    Ok((predictor, r_squared))
+}
+
+/// Fits the regressors with the given options, returning the predictor and its r-squared. Unlike
+/// [RegressionModel::fit_with_options], the regressors may comprise only the constant, which is
+/// fitted by [fit_constant].
+pub fn fit_predictor<O: AsIndex + Clone>(
+    response: &O,
+    regressors: &[Regressor<O>],
+    data: &Matrix<f64>,
+    options: &RegressionOptions<O>,
+) -> Result<(Predictor<O>, RSquared), anyhow::Error> {
+    match regressors {
+        [constant] if constant.is_constant() => fit_constant(response, constant, data, options),
+        _ => {
+            let model = RegressionModel::fit_with_options(
+                response.clone(),
+                regressors.to_vec(),
+                data,
+                options,
+            )?;
+            Ok((model.predictor, model.r_squared))
+        }
+    }
+}
+
+fn residuals<O: AsIndex>(
+    response: &O,
+    regressors: &[Regressor<O>],
+    coefficients: &[f64],
+    data: &Matrix<f64>,
+) -> Vec<f64> {
+    data.into_iter()
+        .map(|row| row[response.as_index()] - predict(regressors, coefficients, row))
+        .collect()
+}
+
 pub fn validate_regressors<O: AsIndex>(
     regressors: &[Regressor<O>],
 ) -> Result<(), anyhow::Error> {
@@ -157,6 +433,75 @@ pub fn validate_regressors<O: AsIndex>(
     Ok(())
 }
 
+/// Options for fitting a [RegressionModel]. The default is an unweighted, unpenalised
+/// least-squares fit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegressionOptions<O: AsIndex> {
+    /// The data column holding the weight of each sample, or `None` for equal weights. Samples
+    /// with a zero weight are excluded from the fit.
+    pub weights: Option<O>,
+    /// The ridge penalty on the coefficients of the non-constant regressors. The standard errors
+    /// of a penalised fit are approximate.
+    pub ridge: f64,
+    /// Downweights outlying samples with a Huber loss, or `None` for a least-squares fit.
+    pub robust: Option<Huber>,
+}
+impl<O: AsIndex> RegressionOptions<O> {
+    pub fn validate(&self) -> Result<(), anyhow::Error> {
+        if !self.ridge.is_finite() || self.ridge < 0. {
+            bail!("ridge penalty must be a non-negative number");
+        }
+        if let Some(huber) = &self.robust {
+            huber.validate()?;
+        }
+        Ok(())
+    }
+}
+
+impl<O: AsIndex> Default for RegressionOptions<O> {
+    fn default() -> Self {
+        Self {
+            weights: None,
+            ridge: 0.,
+            robust: None,
+        }
+    }
+}
+
+/// Iteratively reweighted least squares under the Huber loss. Samples whose residuals exceed
+/// `tuning` robust standard deviations have their weights scaled down in proportion.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Huber {
+    pub tuning: f64,
+    pub max_iterations: usize,
+    /// The largest change in any coefficient, relative to its magnitude, that ends the iterations.
+    pub tolerance: f64,
+}
+impl Huber {
+    pub fn validate(&self) -> Result<(), anyhow::Error> {
+        if !self.tuning.is_finite() || self.tuning <= 0. {
+            bail!("Huber tuning constant must be positive");
+        }
+        if self.max_iterations == 0 {
+            bail!("at least one Huber iteration must be permitted");
+        }
+        if self.tolerance <= 0. {
+            bail!("Huber tolerance must be positive");
+        }
+        Ok(())
+    }
+}
+
+impl Default for Huber {
+    fn default() -> Self {
+        Self {
+            tuning: 1.345,
+            max_iterations: 50,
+            tolerance: 1e-9,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct RegressionModel<O: AsIndex> {
     pub response: O,
@@ -170,21 +515,39 @@ impl<O: AsIndex> RegressionModel<O> {
         response: O,
         regressors: Vec<Regressor<O>>,
         data: &Matrix<f64>,
+    ) -> Result<Self, anyhow::Error> {
+        Self::fit_with_options(response, regressors, data, &RegressionOptions::default())
+    }
+
+    /// Fits the model with the given sample weights, ridge penalty and robust loss. The reported
+    /// r-squared is that of the fitted predictor over the weighted data: each sample is weighted
+    /// by its sample weight and, under a robust loss, by its Huber weight in the final iteration.
+    pub fn fit_with_options(
+        response: O,
+        regressors: Vec<Regressor<O>>,
+        data: &Matrix<f64>,
+        options: &RegressionOptions<O>,
     ) -> Result<Self, anyhow::Error> {
         if data.cols() < 2 {
             bail!("insufficient number of columns in the data");
         }
         validate_regressors(&regressors)?;
+        options.validate()?;
 
-        let mut subset: Matrix<f64> = Matrix::allocate(data.rows(), 1 + regressors.len());
-        for (row_index, row_data) in data.into_iter().enumerate() {
-            subset[(row_index, 0)] = row_data[response.as_index()];
-            for (regressor_index, regressor) in regressors.iter().enumerate() {
-                subset[(row_index, 1 + regressor_index)] = regressor.resolve(row_data);
-            }
+        let sample_weights = sample_weights(data, options)?;
+        let mut model = fit_weighted(&response, &regressors, data, &sample_weights, options.ridge)?;
+        let mut weights = sample_weights.clone();
+        if let Some(huber) = &options.robust {
+            (model, weights) = reweight_huber(
+                huber,
+                &sample_weights,
+                model,
+                LowLevelRegressionModel::parameters,
+                |model| residuals(&response, &regressors, model.parameters(), data),
+                |weights| fit_weighted(&response, &regressors, data, weights, options.ridge),
+            )?;
         }
 
-        let model = fit_low_level_regression_model(subset.flatten(), subset.rows(), subset.cols())?;
         let coefficients = model.parameters().to_vec();
         let std_errors = model.se().to_vec();
         let p_values = model.p_values().to_vec();
@@ -192,7 +555,7 @@ impl<O: AsIndex> RegressionModel<O> {
             regressors,
             coefficients,
         };
-        let r_squared = predictor.r_squared(&response, data);
+        let r_squared = predictor.weighted_r_squared(&response, data, &weights);
         Ok(RegressionModel {
             response,
             predictor,
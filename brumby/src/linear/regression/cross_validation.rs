@@ -0,0 +1,350 @@
+//! Out-of-sample scoring of regression models.
+
+use core::fmt::Debug;
+
+use anyhow::bail;
+use rustc_hash::FxHashMap;
+use stanza::style::{HAlign, Header, MinWidth, Styles};
+use stanza::table::{Col, Row, Table};
+
+use crate::linear::matrix::Matrix;
+use crate::linear::regression::{
+    fit_predictor, AsIndex, Predictor, RegressionOptions, Regressor,
+};
+
+/// How the samples are partitioned into training and test sets. Samples are partitioned by group,
+/// taken in the order in which the groups first appear in the data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Folds {
+    /// The groups are dealt into `k` folds in turn, each fold serving once as the test set while
+    /// the remaining folds are trained on.
+    KFold(usize),
+    /// The groups are split into `k + 1` contiguous blocks. The `i`-th split trains on the first
+    /// `i` blocks and tests on the block that follows.
+    TimeOrdered(usize),
+}
+impl Folds {
+    pub fn validate(&self, groups: usize) -> Result<(), anyhow::Error> {
+        match self {
+            Folds::KFold(k) => {
+                if *k < 2 {
+                    bail!("at least two folds are required");
+                }
+                if *k > groups {
+                    bail!("cannot split {groups} groups into {k} folds");
+                }
+            }
+            Folds::TimeOrdered(k) => {
+                if *k < 1 {
+                    bail!("at least one split is required");
+                }
+                if *k + 1 > groups {
+                    bail!("cannot split {groups} groups into {} blocks", k + 1);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Assigns the groups to the training and test sets of each split.
+    fn splits(&self, groups: usize) -> Vec<(Vec<usize>, Vec<usize>)> {
+        match self {
+            Folds::KFold(k) => (0..*k)
+                .map(|fold| (0..groups).partition(|group| group % k != fold))
+                .collect(),
+            Folds::TimeOrdered(k) => {
+                let blocks = k + 1;
+                let block_of = |group: usize| group * blocks / groups;
+                (1..blocks)
+                    .map(|split| {
+                        let train = (0..groups).filter(|&group| block_of(group) < split).collect();
+                        let test = (0..groups).filter(|&group| block_of(group) == split).collect();
+                        (train, test)
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FoldScore {
+    pub train_samples: usize,
+    pub test_samples: usize,
+    /// The root mean squared error of the fitted predictor over the test samples.
+    pub rmse: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CrossValidationScore {
+    pub folds: Vec<FoldScore>,
+    /// The root mean squared error over the test samples of all folds.
+    pub rmse: f64,
+}
+impl CrossValidationScore {
+    pub fn tabulate(&self) -> Table {
+        let mut table = Table::default()
+            .with_cols(vec![
+                Col::new(Styles::default()),
+                Col::new(Styles::default().with(MinWidth(8)).with(HAlign::Right)),
+                Col::new(Styles::default().with(MinWidth(8)).with(HAlign::Right)),
+                Col::new(Styles::default().with(MinWidth(10)).with(HAlign::Right)),
+            ])
+            .with_row(Row::new(
+                Styles::default().with(Header(true)),
+                vec![
+                    "Fold".into(),
+                    "Train".into(),
+                    "Test".into(),
+                    "RMSE".into(),
+                ],
+            ));
+        for (fold_index, fold) in self.folds.iter().enumerate() {
+            table.push_row(Row::new(
+                Styles::default(),
+                vec![
+                    format!("{}", fold_index + 1).into(),
+                    format!("{}", fold.train_samples).into(),
+                    format!("{}", fold.test_samples).into(),
+                    format!("{:.6}", fold.rmse).into(),
+                ],
+            ));
+        }
+        table.push_row(Row::new(
+            Styles::default(),
+            vec![
+                "Overall".into(),
+                "".into(),
+                format!("{}", self.test_samples()).into(),
+                format!("{:.6}", self.rmse).into(),
+            ],
+        ));
+        table
+    }
+
+    pub fn test_samples(&self) -> usize {
+        self.folds.iter().map(|fold| fold.test_samples).sum()
+    }
+}
+
+/// Fits the regressors to the training samples of each split, scoring the fitted predictor on the
+/// held-out samples. Samples sharing a value in the `group` column are kept together; if `None`,
+/// each sample forms a group of its own. The regressors may comprise only the constant (see
+/// [fit_predictor]).
+pub fn cross_validate<O: AsIndex + Clone>(
+    response: &O,
+    regressors: &[Regressor<O>],
+    data: &Matrix<f64>,
+    options: &RegressionOptions<O>,
+    group: Option<&O>,
+    folds: &Folds,
+) -> Result<CrossValidationScore, anyhow::Error> {
+    let group_rows = assign_groups(data, group);
+    let groups = group_rows.len();
+    folds.validate(groups)?;
+
+    let mut fold_scores = vec![];
+    let (mut sum_sq_error, mut test_samples) = (0., 0);
+    for (train_groups, test_groups) in folds.splits(groups) {
+        let train = subset(data, &group_rows, &train_groups);
+        let test = subset(data, &group_rows, &test_groups);
+        let (predictor, _) = fit_predictor(response, regressors, &train, options)?;
+        let fold_sum_sq_error = sum_sq_error_of(&predictor, response, &test);
+        sum_sq_error += fold_sum_sq_error;
+        test_samples += test.rows();
+        fold_scores.push(FoldScore {
+            train_samples: train.rows(),
+            test_samples: test.rows(),
+            rmse: (fold_sum_sq_error / test.rows() as f64).sqrt(),
+        });
+    }
+
+    Ok(CrossValidationScore {
+        folds: fold_scores,
+        rmse: (sum_sq_error / test_samples as f64).sqrt(),
+    })
+}
+
+/// Numbers the groups in the order of their first appearance, returning the rows of each group.
+fn assign_groups<O: AsIndex>(data: &Matrix<f64>, group: Option<&O>) -> Vec<Vec<usize>> {
+    match group {
+        None => (0..data.rows()).map(|row| vec![row]).collect(),
+        Some(group) => {
+            let mut group_indexes = FxHashMap::default();
+            let mut group_rows: Vec<Vec<usize>> = vec![];
+            for (row, key) in data.col(group.as_index()).enumerate() {
+                let group_index = *group_indexes.entry(key.to_bits()).or_insert_with(|| {
+                    group_rows.push(vec![]);
+                    group_rows.len() - 1
+                });
+                group_rows[group_index].push(row);
+            }
+            group_rows
+        }
+    }
+}
+
+/// The rows of the given groups, in their original order.
+fn subset(data: &Matrix<f64>, group_rows: &[Vec<usize>], groups: &[usize]) -> Matrix<f64> {
+    let mut rows = groups
+        .iter()
+        .flat_map(|&group| group_rows[group].iter().copied())
+        .collect::<Vec<_>>();
+    rows.sort_unstable();
+    let mut subset = Matrix::allocate(rows.len(), data.cols());
+    for (subset_row, row) in rows.into_iter().enumerate() {
+        subset.row_slice_mut(subset_row).copy_from_slice(&data[row]);
+    }
+    subset
+}
+
+fn sum_sq_error_of<O: AsIndex>(predictor: &Predictor<O>, response: &O, data: &Matrix<f64>) -> f64 {
+    data.into_iter()
+        .map(|row| (row[response.as_index()] - predictor.predict(row)).powi(2))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_float_eq::*;
+
+    use crate::linear::regression::Regressor::{Exp, Intercept, Variable};
+
+    use super::*;
+
+    #[derive(Debug, Clone)]
+    enum Factor {
+        Group,
+        Y,
+        X,
+    }
+    impl AsIndex for Factor {
+        fn as_index(&self) -> usize {
+            match self {
+                Factor::Group => 0,
+                Factor::Y => 1,
+                Factor::X => 2,
+            }
+        }
+    }
+
+    /// Two samples in each of 12 groups, with a response that is linear in the input plus an
+    /// alternating disturbance.
+    fn sample_data(disturbance: f64) -> Matrix<f64> {
+        let mut data = Matrix::allocate(24, 3);
+        for row in 0..data.rows() {
+            let x = row as f64 / 4.;
+            let sign = if row % 3 == 0 { 1. } else { -1. };
+            data[(row, Factor::Group.as_index())] = (100 + row / 2) as f64;
+            data[(row, Factor::Y.as_index())] = 1. + 2. * x + sign * disturbance;
+            data[(row, Factor::X.as_index())] = x;
+        }
+        data
+    }
+
+    fn score(
+        regressors: &[Regressor<Factor>],
+        data: &Matrix<f64>,
+        folds: &Folds,
+    ) -> CrossValidationScore {
+        cross_validate(
+            &Factor::Y,
+            regressors,
+            data,
+            &RegressionOptions::default(),
+            Some(&Factor::Group),
+            folds,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn k_fold() {
+        let data = sample_data(0.);
+        let cv = score(&[Intercept, Variable(Factor::X)], &data, &Folds::KFold(4));
+        println!("cv: {cv:?}");
+        assert_eq!(4, cv.folds.len());
+        for fold in &cv.folds {
+            assert_eq!(18, fold.train_samples);
+            assert_eq!(6, fold.test_samples);
+            assert_float_absolute_eq!(0., fold.rmse, 1e-9);
+        }
+        assert_eq!(24, cv.test_samples());
+        assert_float_absolute_eq!(0., cv.rmse, 1e-9);
+    }
+
+    #[test]
+    fn time_ordered() {
+        let data = sample_data(0.1);
+        let cv = score(&[Intercept, Variable(Factor::X)], &data, &Folds::TimeOrdered(3));
+        println!("cv: {cv:?}");
+        assert_eq!(
+            vec![(6, 6), (12, 6), (18, 6)],
+            cv.folds
+                .iter()
+                .map(|fold| (fold.train_samples, fold.test_samples))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(18, cv.test_samples());
+        assert!(cv.rmse > 0.);
+    }
+
+    #[test]
+    fn overfitting_raises_out_of_sample_error() {
+        let data = sample_data(0.5);
+        let folds = Folds::TimeOrdered(3);
+        let linear = score(&[Intercept, Variable(Factor::X)], &data, &folds);
+        let polynomial = score(
+            &[
+                Intercept,
+                Variable(Factor::X),
+                Exp(Box::new(Variable(Factor::X)), 2),
+                Exp(Box::new(Variable(Factor::X)), 3),
+                Exp(Box::new(Variable(Factor::X)), 4),
+            ],
+            &data,
+            &folds,
+        );
+        println!("linear: {}, polynomial: {}", linear.rmse, polynomial.rmse);
+        assert!(polynomial.rmse > linear.rmse);
+    }
+
+    #[test]
+    fn groups_and_subsets() {
+        let mut data = Matrix::allocate(5, 3);
+        for (row, group) in [7., 3., 7., 5., 3.].into_iter().enumerate() {
+            data[(row, Factor::Group.as_index())] = group;
+            data[(row, Factor::X.as_index())] = row as f64;
+        }
+        let group_rows = assign_groups(&data, Some(&Factor::Group));
+        assert_eq!(vec![vec![0, 2], vec![1, 4], vec![3]], group_rows);
+        assert_eq!(5, assign_groups::<Factor>(&data, None).len());
+
+        let subset = subset(&data, &group_rows, &[2, 0]);
+        assert_eq!(
+            vec![0., 2., 3.],
+            subset.col(Factor::X.as_index()).copied().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn invalid_folds() {
+        let data = sample_data(0.);
+        let regressors = [Intercept, Variable(Factor::X)];
+        let cross_validate = |folds| {
+            cross_validate(
+                &Factor::Y,
+                &regressors,
+                &data,
+                &RegressionOptions::default(),
+                Some(&Factor::Group),
+                &folds,
+            )
+            .unwrap_err()
+            .to_string()
+        };
+        assert_eq!("at least two folds are required", cross_validate(Folds::KFold(1)));
+        assert_eq!("cannot split 12 groups into 13 folds", cross_validate(Folds::KFold(13)));
+        assert_eq!("cannot split 12 groups into 13 blocks", cross_validate(Folds::TimeOrdered(12)));
+    }
+}
@@ -0,0 +1,506 @@
+//! Stepwise selection of regressors from a set of candidate terms.
+
+use core::fmt::Debug;
+
+use anyhow::bail;
+use stanza::style::{HAlign, Header, MinWidth, Styles};
+use stanza::table::{Col, Row, Table};
+
+use crate::linear::matrix::Matrix;
+use crate::linear::regression::cross_validation::{cross_validate, Folds};
+use crate::linear::regression::{fit_predictor, AsIndex, RegressionOptions, Regressor};
+
+/// The score by which competing sets of regressors are ranked. Lower scores are better.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Criterion<O: AsIndex> {
+    /// Akaike information criterion of the in-sample fit.
+    Aic,
+    /// Bayesian information criterion of the in-sample fit.
+    Bic,
+    /// Root mean squared error over the held-out samples, keeping together the samples that share
+    /// a value in the `group` column.
+    CrossValidation { group: Option<O>, folds: Folds },
+}
+impl<O: AsIndex + Clone> Criterion<O> {
+    pub fn score(
+        &self,
+        response: &O,
+        regressors: &[Regressor<O>],
+        data: &Matrix<f64>,
+        options: &RegressionOptions<O>,
+    ) -> Result<f64, anyhow::Error> {
+        match self {
+            Criterion::Aic => information_criterion(response, regressors, data, options, 2.),
+            Criterion::Bic => {
+                let penalty = (data.rows() as f64).ln();
+                information_criterion(response, regressors, data, options, penalty)
+            }
+            Criterion::CrossValidation { group, folds } => {
+                let score =
+                    cross_validate(response, regressors, data, options, group.as_ref(), folds)?;
+                Ok(score.rmse)
+            }
+        }
+    }
+}
+
+/// The information criterion of the fitted regressors, taking the likelihood from the weighted
+/// residuals of the fit, in which each sample is weighted by its sample weight and, under a robust
+/// loss, by its final Huber weight. Samples with a zero weight are not counted.
+fn information_criterion<O: AsIndex + Clone>(
+    response: &O,
+    regressors: &[Regressor<O>],
+    data: &Matrix<f64>,
+    options: &RegressionOptions<O>,
+    penalty_per_parameter: f64,
+) -> Result<f64, anyhow::Error> {
+    let (_, r_squared) = fit_predictor(response, regressors, data, options)?;
+    let samples = match &options.weights {
+        None => data.rows(),
+        Some(weights) => data
+            .col(weights.as_index())
+            .filter(|&&weight| weight > 0.)
+            .count(),
+    };
+    let parameters = (samples - r_squared.df_residual) as f64;
+    let samples = samples as f64;
+    let log_likelihood_term = samples * (r_squared.sum_sq_residual / samples).ln();
+    Ok(log_likelihood_term + penalty_per_parameter * parameters)
+}
+
+/// Generates the candidate terms for the given variables: each variable raised to the powers
+/// `1..=max_power` and, if `interactions` is set, the product of every pair of variables.
+pub fn candidates<O: AsIndex + Clone>(
+    variables: &[O],
+    max_power: i32,
+    interactions: bool,
+) -> Vec<Regressor<O>> {
+    let mut candidates = vec![];
+    for variable in variables {
+        candidates.push(Regressor::Variable(variable.clone()));
+        for power in 2..=max_power {
+            candidates.push(Regressor::Exp(
+                Box::new(Regressor::Variable(variable.clone())),
+                power,
+            ));
+        }
+    }
+    if interactions {
+        for (index, first) in variables.iter().enumerate() {
+            for second in &variables[index + 1..] {
+                candidates.push(Regressor::Product(vec![
+                    Regressor::Variable(first.clone()),
+                    Regressor::Variable(second.clone()),
+                ]));
+            }
+        }
+    }
+    candidates
+}
+
+/// Generates the one-hot candidate terms for a categorical variable, one per level. To avoid
+/// collinearity with an intercept, the caller may omit a reference level.
+pub fn indicator_candidates<O: AsIndex + Clone>(variable: &O, levels: &[i32]) -> Vec<Regressor<O>> {
+    levels
+        .iter()
+        .map(|&level| Regressor::Indicator(variable.clone(), level))
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change<O: AsIndex> {
+    Initial,
+    Add(Regressor<O>),
+    Remove(Regressor<O>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Step<O: AsIndex> {
+    pub change: Change<O>,
+    /// The number of non-constant terms after the change.
+    pub terms: usize,
+    pub score: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Selection<O: AsIndex> {
+    /// The selected terms, followed by the constant regressor.
+    pub regressors: Vec<Regressor<O>>,
+    pub score: f64,
+    pub steps: Vec<Step<O>>,
+}
+impl<O: AsIndex> Selection<O> {
+    pub fn tabulate(&self) -> Table
+    where
+        O: Debug,
+    {
+        let mut table = Table::default()
+            .with_cols(vec![
+                Col::new(Styles::default().with(MinWidth(6)).with(HAlign::Right)),
+                Col::new(Styles::default().with(MinWidth(30))),
+                Col::new(Styles::default().with(MinWidth(6)).with(HAlign::Right)),
+                Col::new(Styles::default().with(MinWidth(12)).with(HAlign::Right)),
+            ])
+            .with_row(Row::new(
+                Styles::default().with(Header(true)),
+                vec![
+                    "Step".into(),
+                    "Change".into(),
+                    "Terms".into(),
+                    "Score".into(),
+                ],
+            ));
+        for (step_index, step) in self.steps.iter().enumerate() {
+            let change = match &step.change {
+                Change::Initial => "".into(),
+                Change::Add(regressor) => format!("+ {regressor:?}"),
+                Change::Remove(regressor) => format!("- {regressor:?}"),
+            };
+            table.push_row(Row::new(
+                Styles::default(),
+                vec![
+                    format!("{step_index}").into(),
+                    change.into(),
+                    format!("{}", step.terms).into(),
+                    format!("{:.6}", step.score).into(),
+                ],
+            ));
+        }
+        table
+    }
+}
+
+/// Selects regressors by bidirectional stepwise search. Starting from the `initial` regressors,
+/// each step adds the candidate or removes the non-constant term that most improves the score,
+/// stopping when no change improves it or when `max_terms` would be exceeded. The constant
+/// regressor of the initial set is always retained; if it is the only initial regressor, the
+/// search starts from the score of the constant alone, so that no term is selected unless it
+/// improves on that baseline. Changes that cannot be fitted, such as those producing a singular
+/// design, are passed over.
+pub fn select_stepwise<O: AsIndex + Clone + PartialEq>(
+    response: &O,
+    initial: &[Regressor<O>],
+    candidates: &[Regressor<O>],
+    data: &Matrix<f64>,
+    options: &RegressionOptions<O>,
+    criterion: &Criterion<O>,
+    max_terms: Option<usize>,
+) -> Result<Selection<O>, anyhow::Error> {
+    let mut constants = initial.iter().filter(|regressor| regressor.is_constant());
+    let constant = match (constants.next(), constants.next()) {
+        (Some(constant), None) => constant.clone(),
+        _ => bail!("the initial regressors must include exactly one constant regressor"),
+    };
+    if candidates.iter().any(Regressor::is_constant) {
+        bail!("candidate regressors cannot be constant");
+    }
+    let mut terms: Vec<_> = initial
+        .iter()
+        .filter(|regressor| !regressor.is_constant())
+        .cloned()
+        .collect();
+    let max_terms = max_terms.unwrap_or(usize::MAX);
+    if terms.len() > max_terms {
+        bail!("the initial regressors exceed the maximum of {max_terms} terms");
+    }
+
+    let regressors_of = |terms: &[Regressor<O>]| {
+        let mut regressors = terms.to_vec();
+        regressors.push(constant.clone());
+        regressors
+    };
+    let score_of = |terms: &[Regressor<O>]| {
+        criterion
+            .score(response, &regressors_of(terms), data, options)
+            .ok()
+            .filter(|score| !score.is_nan())
+    };
+
+    let mut score = match score_of(&terms) {
+        None => bail!("the initial regressors could not be scored"),
+        Some(score) => score,
+    };
+    let mut steps = vec![Step {
+        change: Change::Initial,
+        terms: terms.len(),
+        score,
+    }];
+
+    loop {
+        let mut best: Option<Trial<O>> = None;
+        if terms.len() < max_terms {
+            for candidate in candidates.iter().filter(|candidate| !terms.contains(candidate)) {
+                let mut trial = terms.clone();
+                trial.push(candidate.clone());
+                consider(&mut best, Change::Add(candidate.clone()), trial, score_of);
+            }
+        }
+        if !terms.is_empty() {
+            for (index, term) in terms.iter().enumerate() {
+                let mut trial = terms.clone();
+                trial.remove(index);
+                consider(&mut best, Change::Remove(term.clone()), trial, score_of);
+            }
+        }
+
+        match best {
+            Some(trial) if trial.score < score => {
+                terms = trial.terms;
+                score = trial.score;
+                steps.push(Step {
+                    change: trial.change,
+                    terms: terms.len(),
+                    score: trial.score,
+                });
+            }
+            _ => break,
+        }
+    }
+
+    Ok(Selection {
+        regressors: regressors_of(&terms),
+        score,
+        steps,
+    })
+}
+
+struct Trial<O: AsIndex> {
+    change: Change<O>,
+    terms: Vec<Regressor<O>>,
+    score: f64,
+}
+
+/// Retains the trial terms as the best change so far if they score lower than the incumbent.
+fn consider<O: AsIndex>(
+    best: &mut Option<Trial<O>>,
+    change: Change<O>,
+    terms: Vec<Regressor<O>>,
+    score_of: impl Fn(&[Regressor<O>]) -> Option<f64>,
+) {
+    if let Some(score) = score_of(&terms) {
+        if best.as_ref().is_none_or(|best| score < best.score) {
+            *best = Some(Trial { change, terms, score });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_float_eq::*;
+
+    use crate::linear::regression::Regressor::{
+        Exp, Indicator, Intercept, Origin, Product, Variable,
+    };
+
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Factor {
+        Group,
+        Y,
+        X,
+        Z,
+        Noise,
+    }
+    impl AsIndex for Factor {
+        fn as_index(&self) -> usize {
+            match self {
+                Factor::Group => 0,
+                Factor::Y => 1,
+                Factor::X => 2,
+                Factor::Z => 3,
+                Factor::Noise => 4,
+            }
+        }
+    }
+
+    /// A response of `1 + 2x + 0.5z²` plus a small disturbance, alongside an unrelated input.
+    fn sample_data() -> Matrix<f64> {
+        let mut data = Matrix::allocate(60, 5);
+        for row in 0..data.rows() {
+            let x = (row % 10) as f64;
+            let z = (row * 7 % 11) as f64 / 2.;
+            let noise = (row * 13 % 17) as f64;
+            let disturbance = ((row * 5 % 9) as f64 - 4.) / 40.;
+            data[(row, Factor::Group.as_index())] = (row / 3) as f64;
+            data[(row, Factor::Y.as_index())] = 1. + 2. * x + 0.5 * z.powi(2) + disturbance;
+            data[(row, Factor::X.as_index())] = x;
+            data[(row, Factor::Z.as_index())] = z;
+            data[(row, Factor::Noise.as_index())] = noise;
+        }
+        data
+    }
+
+    fn select(criterion: &Criterion<Factor>, max_terms: Option<usize>) -> Selection<Factor> {
+        let data = sample_data();
+        let candidates = candidates(&[Factor::X, Factor::Z, Factor::Noise], 2, false);
+        select_stepwise(
+            &Factor::Y,
+            &[Intercept],
+            &candidates,
+            &data,
+            &RegressionOptions::default(),
+            criterion,
+            max_terms,
+        )
+        .unwrap()
+    }
+
+    fn z_squared() -> Regressor<Factor> {
+        Exp(Box::new(Variable(Factor::Z)), 2)
+    }
+
+    #[test]
+    fn candidates_with_powers_and_interactions() {
+        assert_eq!(
+            vec![
+                Variable(Factor::X),
+                Exp(Box::new(Variable(Factor::X)), 2),
+                Variable(Factor::Z),
+                Exp(Box::new(Variable(Factor::Z)), 2),
+                Product(vec![Variable(Factor::X), Variable(Factor::Z)]),
+            ],
+            candidates(&[Factor::X, Factor::Z], 2, true)
+        );
+    }
+
+    #[test]
+    fn indicator_candidates_per_level() {
+        assert_eq!(
+            vec![Indicator(Factor::Group, 1), Indicator(Factor::Group, 2)],
+            indicator_candidates(&Factor::Group, &[1, 2])
+        );
+    }
+
+    #[test]
+    fn select_by_bic() {
+        let selection = select(&Criterion::Bic, None);
+        println!("selection: {selection:?}");
+        assert_eq!(
+            vec![Variable(Factor::X), z_squared(), Intercept],
+            order_terms(selection.regressors)
+        );
+        assert!(selection.steps.windows(2).all(|pair| pair[1].score < pair[0].score));
+    }
+
+    #[test]
+    fn select_by_cross_validation() {
+        let criterion = Criterion::CrossValidation {
+            group: Some(Factor::Group),
+            folds: Folds::KFold(5),
+        };
+        let selection = select(&criterion, None);
+        println!("selection: {selection:?}");
+        assert_eq!(Change::Initial, selection.steps[0].change);
+        assert_eq!(0, selection.steps[0].terms);
+        assert_eq!(Change::Add(Variable(Factor::X)), selection.steps[1].change);
+        assert_eq!(Change::Add(z_squared()), selection.steps[2].change);
+        assert!(selection.steps.windows(2).all(|pair| pair[1].score < pair[0].score));
+        assert!(selection.score < 0.1);
+    }
+
+    #[test]
+    fn constant_only_baseline() {
+        let data = sample_data();
+        let samples = data.rows() as f64;
+        let response = data.col(Factor::Y.as_index()).copied().collect::<Vec<_>>();
+        let mean = response.iter().sum::<f64>() / samples;
+        let sum_sq_deviation = response.iter().map(|y| (y - mean).powi(2)).sum::<f64>();
+        let sum_sq = response.iter().map(|y| y.powi(2)).sum::<f64>();
+        let score = |constant: Regressor<Factor>| {
+            Criterion::Bic
+                .score(&Factor::Y, &[constant], &data, &RegressionOptions::default())
+                .unwrap()
+        };
+
+        // the intercept alone is the mean of the response, with one parameter; the origin alone
+        // predicts zero, with none
+        assert_float_relative_eq!(
+            samples * (sum_sq_deviation / samples).ln() + samples.ln(),
+            score(Intercept)
+        );
+        assert_float_relative_eq!(samples * (sum_sq / samples).ln(), score(Origin));
+
+        // terms are only added if they improve on the baseline
+        let selection = select(&Criterion::Bic, None);
+        assert_eq!(Change::Initial, selection.steps[0].change);
+        assert_eq!(score(Intercept), selection.steps[0].score);
+    }
+
+    #[test]
+    fn no_term_beats_baseline() {
+        // the response is unrelated to the sole candidate
+        let mut data = sample_data();
+        for row in 0..data.rows() {
+            data[(row, Factor::Y.as_index())] = if row % 2 == 0 { 1. } else { -1. };
+            data[(row, Factor::X.as_index())] = (row / 2 % 2) as f64;
+        }
+        for criterion in [
+            Criterion::Bic,
+            Criterion::CrossValidation {
+                group: None,
+                folds: Folds::KFold(5),
+            },
+        ] {
+            let selection = select_stepwise(
+                &Factor::Y,
+                &[Intercept],
+                &[Variable(Factor::X)],
+                &data,
+                &RegressionOptions::default(),
+                &criterion,
+                None,
+            )
+            .unwrap();
+            assert_eq!(vec![Intercept], selection.regressors, "{criterion:?}");
+            assert_eq!(1, selection.steps.len(), "{criterion:?}");
+        }
+    }
+
+    #[test]
+    fn select_within_max_terms() {
+        let selection = select(&Criterion::Aic, Some(1));
+        println!("selection: {selection:?}");
+        assert_eq!(2, selection.regressors.len());
+        assert_eq!(Some(&Intercept), selection.regressors.last());
+    }
+
+    #[test]
+    fn invalid_initial_regressors() {
+        let data = sample_data();
+        let select = |initial: &[Regressor<Factor>]| {
+            select_stepwise(
+                &Factor::Y,
+                initial,
+                &[Variable(Factor::X)],
+                &data,
+                &RegressionOptions::default(),
+                &Criterion::Bic,
+                None,
+            )
+            .unwrap_err()
+            .to_string()
+        };
+        assert_eq!(
+            "the initial regressors must include exactly one constant regressor",
+            select(&[Variable(Factor::X)])
+        );
+        assert_eq!(
+            "the initial regressors must include exactly one constant regressor",
+            select(&[Intercept, Origin])
+        );
+    }
+
+    /// Orders the selected terms by their position among the candidates, for comparison.
+    fn order_terms(mut regressors: Vec<Regressor<Factor>>) -> Vec<Regressor<Factor>> {
+        let candidates = candidates(&[Factor::X, Factor::Z, Factor::Noise], 2, false);
+        let position = |regressor: &Regressor<Factor>| {
+            candidates
+                .iter()
+                .position(|candidate| candidate == regressor)
+                .unwrap_or(usize::MAX)
+        };
+        regressors.sort_by_key(position);
+        regressors
+    }
+}
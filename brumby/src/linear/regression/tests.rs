@@ -4,7 +4,7 @@ use brumby_testing::assert_slice_f64_relative;
 
 use Regressor::{Exp, Variable, Product};
 
-use crate::linear::regression::Regressor::{Intercept, Origin};
+use crate::linear::regression::Regressor::{Indicator, Intercept, Origin};
 
 use super::*;
 
@@ -51,6 +51,13 @@ fn serde_json() {
         let rr = from_json(&json);
         assert_eq!(r, rr);
     }
+    {
+        let r = Indicator(TestFactor::B, 3);
+        let json = to_json(&r);
+        assert_eq!(r#"{"Indicator":["B",3]}"#, json);
+        let rr = from_json(&json);
+        assert_eq!(r, rr);
+    }
     {
         let r = Intercept;
         let json = to_json(&r);
@@ -457,6 +464,300 @@ fn regression_data_2() {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Ordinal)]
+enum WeightedFactor {
+    Y,
+    X,
+    W,
+}
+impl AsIndex for WeightedFactor {
+    fn as_index(&self) -> usize {
+        self.ordinal()
+    }
+}
+
+#[test]
+fn regression_weighted() {
+    #[rustfmt::skip]
+    fn sample_data() -> Matrix<f64> {
+        let mut data = Matrix::allocate(5, 3);
+        data.flatten_mut()
+            .clone_from_slice(&[
+                2., 2., 1.,
+                3., 4., 2.,
+                4., 6., 1.,
+                6., 7., 1.,
+                9., 5., 0.,
+            ]);
+        data
+    }
+    #[rustfmt::skip]
+    fn expanded_data() -> Matrix<f64> {
+        let mut data = Matrix::allocate(5, 3);
+        data.flatten_mut()
+            .clone_from_slice(&[
+                2., 2., 1.,
+                3., 4., 1.,
+                3., 4., 1.,
+                4., 6., 1.,
+                6., 7., 1.,
+            ]);
+        data
+    }
+    const EPSILON: f64 = 1e-9;
+    let options = RegressionOptions {
+        weights: Some(WeightedFactor::W),
+        ..RegressionOptions::default()
+    };
+
+    // a weight of 2 is akin to repeating the sample; a weight of 0 excludes it
+    let weighted = RegressionModel::fit_with_options(
+        WeightedFactor::Y,
+        vec![Intercept, Variable(WeightedFactor::X)],
+        &sample_data(),
+        &options,
+    )
+    .unwrap();
+    let expanded = RegressionModel::fit(
+        WeightedFactor::Y,
+        vec![Intercept, Variable(WeightedFactor::X)],
+        &expanded_data(),
+    )
+    .unwrap();
+    assert_slice_f64_relative(
+        &expanded.predictor.coefficients,
+        &weighted.predictor.coefficients,
+        EPSILON,
+    );
+    assert_float_relative_eq!(
+        expanded.r_squared.sum_sq_residual,
+        weighted.r_squared.sum_sq_residual,
+        EPSILON
+    );
+    assert_float_relative_eq!(
+        expanded.r_squared.sum_sq_total,
+        weighted.r_squared.sum_sq_total,
+        EPSILON
+    );
+    assert_eq!(2, weighted.r_squared.df_residual);
+    assert_eq!(2, weighted.std_errors.len());
+    assert!(weighted.std_errors.iter().all(|std_error| *std_error > 0.));
+    assert_eq!(2, weighted.p_values.len());
+
+    // negative weights are rejected
+    let mut data = sample_data();
+    data[(0, WeightedFactor::W.as_index())] = -1.;
+    assert_eq!(
+        "sample weights must be non-negative numbers",
+        RegressionModel::fit_with_options(
+            WeightedFactor::Y,
+            vec![Intercept, Variable(WeightedFactor::X)],
+            &data,
+            &options,
+        )
+        .unwrap_err()
+        .to_string()
+    );
+}
+
+#[test]
+fn regression_ridge() {
+    #[rustfmt::skip]
+    fn sample_data() -> Matrix<f64> {
+        let mut data = Matrix::allocate(4, 3);
+        data.flatten_mut()
+            .clone_from_slice(&[
+                2., 2., 1.,
+                3., 4., 1.,
+                4., 6., 1.,
+                6., 7., 1.,
+            ]);
+        data
+    }
+    const EPSILON: f64 = 1e-9;
+    let data = sample_data();
+    let fit = |ridge| {
+        RegressionModel::fit_with_options(
+            WeightedFactor::Y,
+            vec![Intercept, Variable(WeightedFactor::X)],
+            &data,
+            &RegressionOptions {
+                ridge,
+                ..RegressionOptions::default()
+            },
+        )
+        .unwrap()
+    };
+
+    // an unpenalised fit is ordinary least squares
+    assert_slice_f64_relative(
+        &[0.28813559322033333, 0.7288135593220351],
+        &fit(0.).predictor.coefficients,
+        EPSILON,
+    );
+
+    // the slope shrinks to Sxy / (Sxx + ridge), leaving the intercept unpenalised
+    let model = fit(1.);
+    let slope = 10.75 / (14.75 + 1.);
+    assert_slice_f64_relative(
+        &[3.75 - slope * 4.75, slope],
+        &model.predictor.coefficients,
+        EPSILON,
+    );
+    assert!(model.r_squared.unadjusted() < fit(0.).r_squared.unadjusted());
+
+    assert_eq!(
+        "ridge penalty must be a non-negative number",
+        RegressionModel::fit_with_options(
+            WeightedFactor::Y,
+            vec![Intercept, Variable(WeightedFactor::X)],
+            &data,
+            &RegressionOptions {
+                ridge: -1.,
+                ..RegressionOptions::default()
+            },
+        )
+        .unwrap_err()
+        .to_string()
+    );
+}
+
+#[test]
+fn regression_huber() {
+    fn sample_data() -> Matrix<f64> {
+        let mut data = Matrix::allocate(12, 3);
+        for row in 0..data.rows() {
+            let x = row as f64;
+            let noise = if row % 2 == 0 { 0.1 } else { -0.1 };
+            data[(row, WeightedFactor::Y.as_index())] = 1. + 2. * x + noise;
+            data[(row, WeightedFactor::X.as_index())] = x;
+            data[(row, WeightedFactor::W.as_index())] = 1.;
+        }
+        // a single gross outlier
+        data[(9, WeightedFactor::Y.as_index())] += 50.;
+        data
+    }
+    let data = sample_data();
+    let regressors = || vec![Intercept, Variable(WeightedFactor::X)];
+
+    let ols = RegressionModel::fit(WeightedFactor::Y, regressors(), &data).unwrap();
+    let huber = RegressionModel::fit_with_options(
+        WeightedFactor::Y,
+        regressors(),
+        &data,
+        &RegressionOptions {
+            robust: Some(Huber::default()),
+            ..RegressionOptions::default()
+        },
+    )
+    .unwrap();
+    println!("ols: {:?}, huber: {:?}", ols.predictor.coefficients, huber.predictor.coefficients);
+    assert!((ols.predictor.coefficients[1] - 2.).abs() > 0.5);
+    assert_float_absolute_eq!(2., huber.predictor.coefficients[1], 0.05);
+    assert_float_absolute_eq!(1., huber.predictor.coefficients[0], 0.25);
+    assert!(huber.std_errors[1] < ols.std_errors[1]);
+
+    // the outlier is downweighted in the r-squared, as it is in the fit
+    assert!(huber.r_squared.unadjusted() > ols.r_squared.unadjusted());
+    let unweighted = huber.predictor.r_squared(&WeightedFactor::Y, &data);
+    assert!(huber.r_squared.sum_sq_residual < unweighted.sum_sq_residual);
+    assert_eq!(Significance::A, Significance::lookup(huber.p_values[1]));
+}
+
+#[test]
+fn regression_constant() {
+    #[rustfmt::skip]
+    fn sample_data() -> Matrix<f64> {
+        let mut data = Matrix::allocate(5, 3);
+        data.flatten_mut()
+            .clone_from_slice(&[
+                2., 0., 1.,
+                3., 0., 2.,
+                4., 0., 1.,
+                6., 0., 1.,
+                40., 0., 0.,
+            ]);
+        data
+    }
+    const EPSILON: f64 = 1e-9;
+    let data = sample_data();
+    let weighted = RegressionOptions {
+        weights: Some(WeightedFactor::W),
+        ..RegressionOptions::default()
+    };
+
+    // the intercept is the weighted mean of the response; the excluded sample plays no part
+    let (predictor, r_squared) =
+        fit_constant(&WeightedFactor::Y, &Intercept, &data, &weighted).unwrap();
+    assert_eq!(vec![Intercept], predictor.regressors);
+    assert_float_relative_eq!(3.6, predictor.coefficients[0], EPSILON);
+    let sum_sq_residual = 1.6 * 1.6 + 2. * 0.6 * 0.6 + 0.4 * 0.4 + 2.4 * 2.4;
+    assert_float_relative_eq!(sum_sq_residual, r_squared.sum_sq_residual, EPSILON);
+    assert_float_absolute_eq!(0., r_squared.unadjusted(), EPSILON);
+    assert_eq!(3, r_squared.df_residual);
+
+    // the origin predicts zero, leaving every weighted square as a residual
+    let (predictor, r_squared) =
+        fit_constant(&WeightedFactor::Y, &Origin, &data, &weighted).unwrap();
+    assert_eq!(vec![0.], predictor.coefficients);
+    assert_float_relative_eq!(4. + 18. + 16. + 36., r_squared.sum_sq_residual, EPSILON);
+    assert_eq!(4, r_squared.df_residual);
+
+    // under a robust loss, the outlier is downweighted rather than dominating the mean
+    let robust = RegressionOptions {
+        robust: Some(Huber::default()),
+        ..RegressionOptions::default()
+    };
+    let (predictor, _) = fit_constant(&WeightedFactor::Y, &Intercept, &data, &robust).unwrap();
+    assert!(predictor.coefficients[0] < 6., "{:?}", predictor.coefficients);
+
+    // a constant alone is fitted in closed form, rather than by least squares
+    let options = RegressionOptions::default();
+    let (predictor, _) = fit_predictor(&WeightedFactor::Y, &[Intercept], &data, &options).unwrap();
+    assert_float_relative_eq!(11., predictor.coefficients[0], EPSILON);
+    assert_eq!(
+        "Variable is not a constant regressor",
+        fit_constant(&WeightedFactor::Y, &Variable(WeightedFactor::X), &data, &weighted)
+            .unwrap_err()
+            .to_string()
+    );
+}
+
+#[test]
+fn regression_indicator() {
+    #[rustfmt::skip]
+    fn sample_data() -> Matrix<f64> {
+        let mut data = Matrix::allocate(6, 3);
+        data.flatten_mut()
+            .clone_from_slice(&[
+                3., 1., 0.,
+                5., 2., 0.,
+                8., 2., 1.,
+                10., 3., 1.,
+                12., 3., 2.,
+                14., 4., 2.,
+            ]);
+        data
+    }
+    const EPSILON: f64 = 1e-9;
+
+    // the response is 1 + 2x, offset by 3 and 5 in the second and third levels
+    let regressors = vec![
+        Variable(WeightedFactor::X),
+        Indicator(WeightedFactor::W, 1),
+        Indicator(WeightedFactor::W, 2),
+        Intercept,
+    ];
+    assert_eq!(1., regressors[1].resolve(&[8., 2., 1.]));
+    assert_eq!(0., regressors[1].resolve(&[12., 3., 2.]));
+    assert!(regressors[2].references(&WeightedFactor::W));
+    assert!(!regressors[2].references(&WeightedFactor::X));
+    assert!(!regressors[3].references(&WeightedFactor::W));
+    let model = RegressionModel::fit(WeightedFactor::Y, regressors, &sample_data()).unwrap();
+    assert_slice_f64_relative(&[2., 3., 5., 1.], &model.predictor.coefficients, EPSILON);
+    assert_float_absolute_eq!(1., model.r_squared.unadjusted(), EPSILON);
+}
+
 #[test]
 fn significance_resolve() {
     assert_eq!(Significance::A, Significance::lookup(0.0));
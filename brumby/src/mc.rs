@@ -137,6 +137,22 @@ impl<'a, R: Rand> MonteCarloEngine<'a, R> {
         );
     }
 
+    /// Simulates the podium conditional on the runners fixed at some of its ranks, returning the
+    /// top-_N_ probabilities of the runners. See [simulate_top_n_conditional].
+    pub fn simulate_top_n_conditional(&mut self, fixed: &[Option<usize>]) -> Matrix<f64> {
+        self.ensure_init();
+
+        simulate_top_n_conditional(
+            self.trials,
+            fixed,
+            self.probs.as_ref().unwrap(),
+            self.podium.as_mut().unwrap(),
+            self.bitmap.as_mut().unwrap(),
+            self.totals.as_mut().unwrap(),
+            &mut *self.rand,
+        )
+    }
+
     fn ensure_init(&mut self) {
         if self.bitmap.is_none() {
             self.bitmap = Some(CaptureMut::Owned(vec![true; self.num_runners()]));
@@ -208,6 +224,104 @@ pub fn simulate(
     }
 }
 
+/// Simulates the podium conditional on the runners fixed at some of its ranks. `fixed` holds an
+/// entry for each rank, with `None` for the ranks that are open. Row `n` of the result holds the
+/// probability of each runner finishing in the top `n + 1` places.
+///
+/// The open ranks are sampled from the runners that remain, and each trial is weighted by the
+/// ratio of its probability under the unconditional model to the probability of it having been
+/// sampled, so that the estimates converge on the exact conditional probabilities. The result is
+/// zeroed if the fixed runners cannot occupy their ranks.
+pub fn simulate_top_n_conditional(
+    trials: u64,
+    fixed: &[Option<usize>],
+    probs: &Matrix<f64>,
+    podium: &mut [usize],
+    bitmap: &mut [bool],
+    totals: &mut [f64],
+    rand: &mut impl Rand,
+) -> Matrix<f64> {
+    assert!(validate_args(probs, podium, bitmap, totals));
+    assert!(validate_fixed(probs, podium, fixed));
+
+    let (ranks, runners) = (probs.rows(), probs.cols());
+    let mut summary = Matrix::allocate(ranks, runners);
+    let mut total_weight = 0.0;
+    for _ in 0..trials {
+        let weight = run_once_conditional(fixed, probs, podium, bitmap, totals, rand);
+        total_weight += weight;
+        for (rank, &runner) in podium.iter().enumerate() {
+            for top_n in rank..ranks {
+                summary[(top_n, runner)] += weight;
+            }
+        }
+    }
+
+    if total_weight > 0.0 {
+        for prob in summary.flatten_mut() {
+            *prob /= total_weight;
+        }
+    }
+    summary
+}
+
+/// Runs a single trial with the runners fixed at some of the ranks, returning the weight of the
+/// sampled podium.
+#[inline(always)]
+pub fn run_once_conditional(
+    fixed: &[Option<usize>],
+    probs: &Matrix<f64>,
+    podium: &mut [usize],
+    bitmap: &mut [bool],
+    totals: &mut [f64],
+    rand: &mut impl Rand,
+) -> f64 {
+    debug_assert!(validate_args(probs, podium, bitmap, totals));
+    debug_assert!(validate_fixed(probs, podium, fixed));
+    bitmap.fill(true);
+    totals.fill(1.0);
+
+    let ranks = podium.len();
+    for (rank, fixed_runner) in fixed.iter().enumerate() {
+        if let Some(runner) = *fixed_runner {
+            podium[rank] = runner;
+            bitmap[runner] = false;
+            for open_rank in 0..ranks {
+                if fixed[open_rank].is_none() {
+                    totals[open_rank] -= probs[(open_rank, runner)];
+                }
+            }
+        }
+    }
+
+    let mut weight = 1.0;
+    for rank in 0..ranks {
+        let rank_probs = probs.row_slice(rank);
+        let mut remaining_prob = 1.0;
+        for prev_rank in 0..rank {
+            remaining_prob -= rank_probs[podium[prev_rank]];
+        }
+        match fixed[rank] {
+            Some(runner) => {
+                weight *= rank_probs[runner] / remaining_prob;
+            }
+            None => {
+                let total = totals[rank];
+                let runner = choose_runner(rank_probs, bitmap, total, rand);
+                podium[rank] = runner;
+                bitmap[runner] = false;
+                for future_rank in rank + 1..ranks {
+                    if fixed[future_rank].is_none() {
+                        totals[future_rank] -= probs[(future_rank, runner)];
+                    }
+                }
+                weight *= total / remaining_prob;
+            }
+        }
+    }
+    weight
+}
+
 #[inline(always)]
 pub fn run_once(
     probs: &Matrix<f64>,
@@ -220,48 +334,40 @@ pub fn run_once(
     bitmap.fill(true);
     totals.fill(1.0);
 
-    let runners = probs.cols();
     let ranks = podium.len();
     // reset_bitmap(bitmap);
     // println!("podium.len: {}", podium.len());
     for (rank, ranked_runner) in podium.iter_mut().enumerate() {
-        let mut cumulative = 0.0;
-
         let rank_probs = probs.row_slice(rank);
-        let total = totals[rank];
-        let random = random_f64(rand) * total;
-        // println!("random={random:.3}, prob_sum={prob_sum}");
-        let mut chosen = false;
-        let mut last_eligible_runner = 0;
-        for runner in 0..runners {
-            if bitmap[runner] {
-                let prob = rank_probs[runner];
-                if prob > 0.0 {
-                    last_eligible_runner = runner;
-                    cumulative += prob;
-                    // println!("probabilities[{runner}]={prob:.3}, cumulative={cumulative:.3}");
-                    if cumulative >= random {
-                        // println!("chosen runner {runner} for rank {rank}");
-                        *ranked_runner = runner;
-                        bitmap[runner] = false;
-                        chosen = true;
-                        for future_rank in rank + 1..ranks {
-                            totals[future_rank] -= probs[(future_rank, runner)];
-                        }
-                        break;
-                    }
-                }
-            }
+        let runner = choose_runner(rank_probs, bitmap, totals[rank], rand);
+        *ranked_runner = runner;
+        bitmap[runner] = false;
+        for future_rank in rank + 1..ranks {
+            totals[future_rank] -= probs[(future_rank, runner)];
         }
-        if !chosen {
-            *ranked_runner = last_eligible_runner;
-            bitmap[last_eligible_runner] = false;
-            for future_rank in rank + 1..ranks {
-                totals[future_rank] -= probs[(future_rank, last_eligible_runner)];
+    }
+}
+
+/// Chooses a runner among those remaining in the bitmap, in proportion to their probabilities,
+/// where `total` is the sum of the remaining probabilities. Falls back to the last eligible runner
+/// should rounding leave the draw unmatched.
+#[inline(always)]
+fn choose_runner(rank_probs: &[f64], bitmap: &[bool], total: f64, rand: &mut impl Rand) -> usize {
+    let random = random_f64(rand) * total;
+    // println!("random={random:.3}, prob_sum={prob_sum}");
+    let mut cumulative = 0.0;
+    let mut last_eligible_runner = 0;
+    for (runner, &prob) in rank_probs.iter().enumerate() {
+        if bitmap[runner] && prob > 0.0 {
+            last_eligible_runner = runner;
+            cumulative += prob;
+            // println!("probabilities[{runner}]={prob:.3}, cumulative={cumulative:.3}");
+            if cumulative >= random {
+                return runner;
             }
-            //panic!("no runner chosen in rank {rank}! cumulative: {cumulative}, random: {random}, bitmap: {bitmap:?}, totals: {totals:?}");
         }
     }
+    last_eligible_runner
 }
 
 fn validate_args(
@@ -303,6 +409,21 @@ fn validate_args(
     true
 }
 
+fn validate_fixed(probs: &Matrix<f64>, podium: &[usize], fixed: &[Option<usize>]) -> bool {
+    assert_eq!(
+        podium.len(),
+        fixed.len(),
+        "a fixed entry must exist for each podium rank"
+    );
+    let mut seen = vec![false; probs.cols()];
+    for &runner in fixed.iter().flatten() {
+        assert!(runner < probs.cols(), "invalid fixed runner {runner}");
+        assert!(!seen[runner], "runner {runner} cannot be fixed at more than one rank");
+        seen[runner] = true;
+    }
+    true
+}
+
 #[inline(always)]
 fn random_f64(rand: &mut impl Rand) -> f64 {
     rand.next_u64() as f64 / u64::MAX as f64